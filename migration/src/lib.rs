@@ -7,6 +7,7 @@ mod m20250420_000001_create_scroll_session_table;
 mod m20250420_000002_create_scroll_event_table;
 mod m20250422_001344_add_session_state_column;
 mod m20250501_000001_create_adk_tables;
+mod m20261018_000001_create_archive_tables;
 
 pub struct Migrator;
 
@@ -18,6 +19,7 @@ impl MigratorTrait for Migrator {
             Box::new(m20250420_000002_create_scroll_event_table::Migration),
             Box::new(m20250422_001344_add_session_state_column::Migration),
             Box::new(m20250501_000001_create_adk_tables::Migration),
+            Box::new(m20261018_000001_create_archive_tables::Migration),
         ]
     }
 }
@@ -0,0 +1,172 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Archived scrolls, one row per scroll with the full scroll as JSON
+#[derive(Iden)]
+enum ArchiveScrolls {
+    Table,
+    Id,
+    Title,
+    ScrollType,
+    Status,
+    Path,
+    ContentHash,
+    Data,
+    UpdatedAt,
+}
+
+/// Tags of archived scrolls
+#[derive(Iden)]
+enum ArchiveTags {
+    Table,
+    ScrollId,
+    Tag,
+}
+
+/// Links between archived scrolls
+#[derive(Iden)]
+enum ArchiveLinks {
+    Table,
+    SourceId,
+    TargetId,
+    LinkType,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create scrolls table
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArchiveScrolls::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ArchiveScrolls::Id).string().not_null())
+                    .col(ColumnDef::new(ArchiveScrolls::Title).string().not_null())
+                    .col(
+                        ColumnDef::new(ArchiveScrolls::ScrollType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ArchiveScrolls::Status).string().not_null())
+                    .col(ColumnDef::new(ArchiveScrolls::Path).string())
+                    .col(ColumnDef::new(ArchiveScrolls::ContentHash).string())
+                    .col(ColumnDef::new(ArchiveScrolls::Data).json().not_null())
+                    .col(
+                        ColumnDef::new(ArchiveScrolls::UpdatedAt)
+                            .double()
+                            .not_null(),
+                    )
+                    .primary_key(Index::create().col(ArchiveScrolls::Id))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create tags table
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArchiveTags::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ArchiveTags::ScrollId).string().not_null())
+                    .col(ColumnDef::new(ArchiveTags::Tag).string().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ArchiveTags::ScrollId)
+                            .col(ArchiveTags::Tag),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_archive_tags_scrolls")
+                            .from(ArchiveTags::Table, ArchiveTags::ScrollId)
+                            .to(ArchiveScrolls::Table, ArchiveScrolls::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create links table
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArchiveLinks::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ArchiveLinks::SourceId).string().not_null())
+                    .col(ColumnDef::new(ArchiveLinks::TargetId).string().not_null())
+                    .col(ColumnDef::new(ArchiveLinks::LinkType).string().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ArchiveLinks::SourceId)
+                            .col(ArchiveLinks::TargetId)
+                            .col(ArchiveLinks::LinkType),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_archive_links_scrolls")
+                            .from(ArchiveLinks::Table, ArchiveLinks::SourceId)
+                            .to(ArchiveScrolls::Table, ArchiveScrolls::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create indices for performance
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_archive_scrolls_path")
+                    .table(ArchiveScrolls::Table)
+                    .col(ArchiveScrolls::Path)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_archive_tags_tag")
+                    .table(ArchiveTags::Table)
+                    .col(ArchiveTags::Tag)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_archive_links_target")
+                    .table(ArchiveLinks::Table)
+                    .col(ArchiveLinks::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Drop links table
+        manager
+            .drop_table(Table::drop().table(ArchiveLinks::Table).to_owned())
+            .await?;
+
+        // Drop tags table
+        manager
+            .drop_table(Table::drop().table(ArchiveTags::Table).to_owned())
+            .await?;
+
+        // Drop scrolls table
+        manager
+            .drop_table(Table::drop().table(ArchiveScrolls::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
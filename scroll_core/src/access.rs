@@ -0,0 +1,201 @@
+//! Write access for protected scrolls. Sealed and archived scrolls refuse
+//! edits unless the actor holds an override role; every denied or overridden
+//! attempt is kept in the [`AccessLedger`] and logged.
+// src/access.rs
+
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::ScrollStatus;
+use crate::scroll::Scroll;
+
+/// Roles allowed to edit protected scrolls, as listed in the validator spec.
+pub const OVERRIDE_ROLES: &[&str] = &["Prime Seeker", "Quorum", "Override Agent"];
+
+static LEDGER: OnceLock<AccessLedger> = OnceLock::new();
+
+/// Who is writing. Override roles match the actor's name or any of its roles,
+/// case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Actor {
+    pub name: String,
+    pub roles: Vec<String>,
+}
+
+impl Actor {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            roles: Vec::new(),
+        }
+    }
+
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+
+    /// The first of `roles` this actor holds.
+    fn held_role<'a>(&self, roles: &'a [String]) -> Option<&'a str> {
+        roles
+            .iter()
+            .find(|role| {
+                std::iter::once(&self.name)
+                    .chain(&self.roles)
+                    .any(|held| held.trim().eq_ignore_ascii_case(role.trim()))
+            })
+            .map(String::as_str)
+    }
+}
+
+impl fmt::Display for Actor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Which statuses are protected and which roles may override them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessPolicy {
+    pub protected: Vec<ScrollStatus>,
+    pub override_roles: Vec<String>,
+}
+
+impl Default for AccessPolicy {
+    fn default() -> Self {
+        Self {
+            protected: vec![ScrollStatus::Sealed, ScrollStatus::Archived],
+            override_roles: OVERRIDE_ROLES.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessDecision {
+    Allowed,
+    /// Protected, but the actor holds this override role.
+    Overridden(String),
+    Denied,
+}
+
+impl AccessPolicy {
+    pub fn is_protected(&self, status: &ScrollStatus) -> bool {
+        self.protected.contains(status)
+    }
+
+    pub fn decide(&self, actor: &Actor, status: &ScrollStatus) -> AccessDecision {
+        if !self.is_protected(status) {
+            return AccessDecision::Allowed;
+        }
+        match actor.held_role(&self.override_roles) {
+            Some(role) => AccessDecision::Overridden(role.to_string()),
+            None => AccessDecision::Denied,
+        }
+    }
+
+    /// Decides whether `actor` may perform `action` on `scroll`, recording
+    /// denied and overridden attempts in the global ledger.
+    pub fn authorize(
+        &self,
+        actor: &Actor,
+        scroll: &Scroll,
+        action: WriteAction,
+    ) -> Result<AccessDecision, String> {
+        let decision = self.decide(actor, &scroll.status);
+        if decision != AccessDecision::Allowed {
+            AccessLedger::global().record(AccessRecord {
+                at: Utc::now(),
+                scroll_id: scroll.id,
+                title: scroll.title.clone(),
+                status: scroll.status.clone(),
+                actor: actor.clone(),
+                action,
+                decision: decision.clone(),
+            });
+        }
+        match decision {
+            AccessDecision::Denied => Err(format!(
+                "Access denied: '{}' is {:?}; {} may not {action} it without one of: {}",
+                scroll.title,
+                scroll.status,
+                actor,
+                self.override_roles.join(", ")
+            )),
+            decision => Ok(decision),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteAction {
+    Write,
+    Update,
+    Seal,
+}
+
+impl fmt::Display for WriteAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = match self {
+            WriteAction::Write => "overwrite",
+            WriteAction::Update => "update",
+            WriteAction::Seal => "seal",
+        };
+        f.write_str(verb)
+    }
+}
+
+/// A denied or overridden write attempt.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccessRecord {
+    pub at: DateTime<Utc>,
+    pub scroll_id: Uuid,
+    pub title: String,
+    /// Status of the scroll when the attempt was made.
+    pub status: ScrollStatus,
+    pub actor: Actor,
+    pub action: WriteAction,
+    pub decision: AccessDecision,
+}
+
+/// Process-wide record of denied and overridden writes.
+#[derive(Debug, Default)]
+pub struct AccessLedger {
+    records: Mutex<Vec<AccessRecord>>,
+}
+
+impl AccessLedger {
+    pub fn global() -> &'static AccessLedger {
+        LEDGER.get_or_init(AccessLedger::default)
+    }
+
+    pub fn record(&self, record: AccessRecord) {
+        warn!(
+            "{:?} {} of '{}' ({:?}) by {}",
+            record.decision, record.action, record.title, record.status, record.actor
+        );
+        if let Ok(mut records) = self.records.lock() {
+            records.push(record);
+        }
+    }
+
+    pub fn records(&self) -> Vec<AccessRecord> {
+        self.records
+            .lock()
+            .map(|records| records.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn for_scroll(&self, scroll_id: Uuid) -> Vec<AccessRecord> {
+        self.records()
+            .into_iter()
+            .filter(|r| r.scroll_id == scroll_id)
+            .collect()
+    }
+}
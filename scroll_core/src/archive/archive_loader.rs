@@ -1,55 +1,300 @@
 //! Utility functions for loading scroll files from a directory on startup.
-//! The loader filters for markdown files and returns parsed Scroll structs.
+//! The loader walks the archive recursively, honours `.scrollignore` files,
+//! filters by extension and returns parsed Scroll structs with a [`LoadReport`].
 //! See [ArchiveLoader](../../AGENTS.md#filereader) for related constructs.
 //    archive_loader.rs
 //======================================
 
-use crate::parser;
+use crate::archive::scroll_ignore::{ScrollIgnore, IGNORE_FILE};
+use crate::parser::{self, ParseError, ParseErrorKind, ParseMode};
 use crate::scroll::Scroll;
+use log::{info, warn};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-fn is_markdown_file(path: &Path) -> bool {
-    path.extension()
-        .and_then(|ext| ext.to_str())
-        .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
-        .unwrap_or(false)
+pub use crate::archive::link_resolver::{resolve_linked_scrolls, UnresolvedLink};
+
+/// How the archive is walked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadOptions {
+    /// File extensions read as scrolls, without the dot; matched
+    /// case-insensitively.
+    pub extensions: Vec<String>,
+    /// Descend into subdirectories.
+    pub recursive: bool,
+    /// Follow symlinked files and directories instead of skipping them.
+    /// Directory loops are detected and skipped.
+    pub follow_symlinks: bool,
+    /// Name of the per-directory ignore file; `None` disables ignore rules.
+    pub ignore_file: Option<String>,
+    pub mode: ParseMode,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            extensions: ["md", "markdown", "txt"].map(String::from).to_vec(),
+            recursive: true,
+            follow_symlinks: false,
+            ignore_file: Some(IGNORE_FILE.to_string()),
+            mode: ParseMode::Lenient,
+        }
+    }
+}
+
+impl LoadOptions {
+    pub fn with_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extensions = extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Matched a `.scrollignore` rule.
+    Ignored,
+    /// Extension not in [`LoadOptions::extensions`].
+    Extension,
+    /// A symlink while `follow_symlinks` is off.
+    Symlink,
+    /// A symlinked directory already being walked.
+    Loop,
 }
 
-/// Loads all scrolls from the given archive directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedPath {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadFailure {
+    pub path: PathBuf,
+    pub error: ParseError,
+}
+
+/// What a load did: every path read, skipped or failed, plus the links left
+/// unresolved and how long it took.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    pub loaded: Vec<PathBuf>,
+    pub skipped: Vec<SkippedPath>,
+    pub failed: Vec<LoadFailure>,
+    pub unresolved: Vec<UnresolvedLink>,
+    pub duration: Duration,
+}
+
+impl LoadReport {
+    /// No file failed to load.
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Loaded {} scroll(s), skipped {}, failed {} in {:.2?}",
+            self.loaded.len(),
+            self.skipped.len(),
+            self.failed.len(),
+            self.duration
+        )
+    }
+}
+
+/// Loads all scrolls under the given archive directory with the default
+/// [`LoadOptions`], logging the [`LoadReport`] instead of returning it.
 pub fn load_scrolls_from_directory<P: AsRef<Path>>(archive_path: P) -> Result<Vec<Scroll>, String> {
-    let mut loaded_scrolls = Vec::new();
-    let mut failed_count = 0;
-
-    let entries = fs::read_dir(&archive_path)
-        .map_err(|e| format!("Failed to read archive directory: {}", e))?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Error reading directory entry: {}", e))?;
-        let path = entry.path();
-
-        if is_markdown_file(&path) {
-            let raw_content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
-
-            match parser::parse_scroll(&raw_content) {
-                Ok(scroll) => loaded_scrolls.push(scroll),
-                Err(e) => {
-                    eprintln!("⚠️ Failed to parse scroll {}: {}", path.display(), e);
-                    failed_count += 1;
-                }
+    let (scrolls, report) = load_archive(archive_path, &LoadOptions::default())?;
+    for failure in &report.failed {
+        warn!("{}", failure.error.render());
+    }
+    info!("{}", report.summary());
+    Ok(scrolls)
+}
+
+/// Loads the scrolls under `archive_path`. Only an unreadable root is an
+/// error; unreadable or malformed files are listed in the report.
+pub fn load_archive<P: AsRef<Path>>(
+    archive_path: P,
+    options: &LoadOptions,
+) -> Result<(Vec<Scroll>, LoadReport), String> {
+    let started = Instant::now();
+    let root = archive_path.as_ref();
+    let (files, mut report) = scan_archive(root, options)?;
+
+    let mut scrolls = Vec::new();
+    for path in files {
+        match parser::parse_archive_scroll(root, &path, options.mode) {
+            Ok(scroll) => {
+                scrolls.push(scroll);
+                report.loaded.push(path);
             }
+            Err(error) => report.failed.push(LoadFailure { path, error }),
         }
     }
 
-    println!(
-        "📚 Loaded {} scroll(s) from the Archive.",
-        loaded_scrolls.len()
-    );
+    report.unresolved = resolve_linked_scrolls(&mut scrolls);
+    for missing in &report.unresolved {
+        let line = missing
+            .line
+            .map(|line| format!(" (body line {line})"))
+            .unwrap_or_default();
+        warn!(
+            "Unresolved {:?} reference '{}' in scroll {}{}",
+            missing.source, missing.reference, missing.scroll_id, line
+        );
+    }
+    report.duration = started.elapsed();
+    Ok((scrolls, report))
+}
+
+/// Walks the archive without parsing: the scroll files it would load, in
+/// path order, and a report of what was skipped or unreadable.
+pub(crate) fn scan_archive(
+    root: &Path,
+    options: &LoadOptions,
+) -> Result<(Vec<PathBuf>, LoadReport), String> {
+    let entries =
+        fs::read_dir(root).map_err(|e| format!("Failed to read archive directory: {}", e))?;
+    let mut walk = Walk {
+        options,
+        ignores: Vec::new(),
+        active: HashSet::new(),
+        files: Vec::new(),
+        report: LoadReport::default(),
+    };
+    if let Ok(canonical) = root.canonicalize() {
+        walk.active.insert(canonical);
+    }
+    walk.dir(root, Path::new(""), entries);
+    Ok((walk.files, walk.report))
+}
+
+struct Walk<'a> {
+    options: &'a LoadOptions,
+    /// Ignore rules in scope, with the directory (relative to the root) that
+    /// holds them.
+    ignores: Vec<(PathBuf, ScrollIgnore)>,
+    /// Canonical directories on the current path, for loop detection.
+    active: HashSet<PathBuf>,
+    files: Vec<PathBuf>,
+    report: LoadReport,
+}
+
+impl Walk<'_> {
+    fn dir(&mut self, dir: &Path, relative: &Path, entries: fs::ReadDir) {
+        let ignore_file = self.options.ignore_file.as_deref();
+        let rules = ignore_file
+            .and_then(|name| ScrollIgnore::from_file(&dir.join(name)))
+            .filter(|rules| !rules.is_empty());
+        let scoped = rules.is_some();
+        if let Some(rules) = rules {
+            self.ignores.push((relative.to_path_buf(), rules));
+        }
+
+        let mut paths = Vec::new();
+        for entry in entries {
+            match entry {
+                Ok(entry) => paths.push(entry.path()),
+                Err(e) => self.fail(dir, e.to_string()),
+            }
+        }
+        paths.sort();
+
+        for path in paths {
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            if ignore_file.is_some_and(|ignore| name == ignore) {
+                continue;
+            }
+            let rel = relative.join(name);
+            self.entry(path, rel);
+        }
 
-    if failed_count == 0 {
-        println!("🌙 All scrolls passed the veil without harm.");
+        if scoped {
+            self.ignores.pop();
+        }
     }
 
-    Ok(loaded_scrolls)
+    fn entry(&mut self, path: PathBuf, rel: PathBuf) {
+        let link = match fs::symlink_metadata(&path) {
+            Ok(meta) => meta.file_type().is_symlink(),
+            Err(e) => return self.fail(&path, e.to_string()),
+        };
+        if link && !self.options.follow_symlinks {
+            return self.skip(path, SkipReason::Symlink);
+        }
+        let meta = match fs::metadata(&path) {
+            Ok(meta) => meta,
+            Err(e) => return self.fail(&path, e.to_string()),
+        };
+        if self.is_ignored(&rel, meta.is_dir()) {
+            return self.skip(path, SkipReason::Ignored);
+        }
+
+        if meta.is_dir() {
+            if !self.options.recursive {
+                return;
+            }
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if !self.active.insert(canonical.clone()) {
+                return self.skip(path, SkipReason::Loop);
+            }
+            match fs::read_dir(&path) {
+                Ok(entries) => self.dir(&path, &rel, entries),
+                Err(e) => self.fail(&path, e.to_string()),
+            }
+            self.active.remove(&canonical);
+        } else if !self.options.accepts(&path) {
+            self.skip(path, SkipReason::Extension);
+        } else {
+            self.files.push(path);
+        }
+    }
+
+    /// Later and deeper rules override earlier ones, as in `.gitignore`.
+    fn is_ignored(&self, rel: &Path, is_dir: bool) -> bool {
+        self.ignores
+            .iter()
+            .rev()
+            .find_map(|(base, rules)| {
+                let inner = rel.strip_prefix(base).ok()?;
+                rules.matched(inner, is_dir)
+            })
+            .unwrap_or(false)
+    }
+
+    fn skip(&mut self, path: PathBuf, reason: SkipReason) {
+        self.report.skipped.push(SkippedPath { path, reason });
+    }
+
+    fn fail(&mut self, path: &Path, message: String) {
+        self.report.failed.push(LoadFailure {
+            path: path.to_path_buf(),
+            error: ParseError::new(ParseErrorKind::Io(message)).with_path(path),
+        });
+    }
 }
@@ -5,27 +5,62 @@
 //=======================================================================
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::archive::error::ArchiveError;
-use crate::archive::semantic_index::{Embedder, SemanticIndex};
+use crate::archive::full_text::{FullTextIndex, TextHit, TextIndexOptions};
+use crate::archive::semantic_index::{Embedder, IndexSync, SemanticIndex};
+use crate::archive::watcher::ArchiveChange;
 use crate::schema::EmotionSignature;
 use crate::scroll::Scroll;
 use log::info;
 
 /// Defines access methods for interacting with the Archive's scroll memory.
+///
+/// Mutations return the [`ArchiveChange`] they made, so callers can forward
+/// it to caches, other archives or the orchestra bus. A changed scroll drops
+/// out of the semantic index until the index is refreshed; the full-text
+/// index is kept current.
 pub trait ArchiveMemory {
     fn get_all_scrolls(&self) -> Vec<&Scroll>;
     fn get_scroll_by_id(&self, id: Uuid) -> Option<&Scroll>;
     fn get_scrolls_by_tag(&self, tag: &str) -> Vec<&Scroll>;
     fn count(&self) -> usize;
     fn query_semantic(&self, input: &str, k: usize) -> Vec<(Scroll, f32)>;
+
+    /// BM25-ranked matches over whole scroll bodies, best first. Empty until
+    /// a full-text index has been built.
+    fn query_text(&self, query: &str, k: usize) -> Vec<(Scroll, TextHit)>;
+
+    fn contains(&self, id: Uuid) -> bool {
+        self.get_scroll_by_id(id).is_some()
+    }
+
+    /// Adds a scroll whose id is not in the archive yet.
+    fn insert(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError>;
+
+    /// Replaces a scroll already in the archive.
+    fn update(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError>;
+
+    fn remove(&mut self, id: Uuid) -> Result<ArchiveChange, ArchiveError>;
+
+    /// Updates the scroll if its id is known, otherwise inserts it.
+    fn upsert(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        if self.contains(scroll.id) {
+            self.update(scroll)
+        } else {
+            self.insert(scroll)
+        }
+    }
 }
 
 /// Simple Phase 1 implementation that holds all scrolls in memory.
 pub struct InMemoryArchive {
     scrolls: HashMap<Uuid, Scroll>,
     semantic_index: Option<SemanticIndex>,
+    text_index: Option<FullTextIndex>,
 }
 
 impl InMemoryArchive {
@@ -34,6 +69,7 @@ impl InMemoryArchive {
         Self {
             scrolls: scrolls_map,
             semantic_index: None,
+            text_index: None,
         }
     }
 }
@@ -77,14 +113,93 @@ impl InMemoryArchive {
             .unwrap_or(0)
     }
 
-    /// Build semantic vector index for all scrolls.
-    pub fn build_semantic_index(&mut self, embedder: &dyn Embedder) -> Result<(), ArchiveError> {
+    /// Build semantic vector index for all scrolls. The index keeps the
+    /// embedder for queries and later changes.
+    pub fn build_semantic_index(
+        &mut self,
+        embedder: impl Embedder + 'static,
+    ) -> Result<(), ArchiveError> {
         let scrolls: Vec<Scroll> = self.scrolls.values().cloned().collect();
-        let index = SemanticIndex::build(&scrolls, embedder)?;
+        let index = SemanticIndex::build(&scrolls, Arc::new(embedder))?;
         self.semantic_index = Some(index);
         Ok(())
     }
 
+    /// Loads the semantic index saved at `path`, embedding only scrolls that
+    /// are new or changed since it was written, and saves it back. See
+    /// [`SemanticIndex::load_or_build`].
+    pub fn load_semantic_index(
+        &mut self,
+        path: &Path,
+        embedder: impl Embedder + 'static,
+    ) -> Result<IndexSync, ArchiveError> {
+        let scrolls: Vec<Scroll> = self.scrolls.values().cloned().collect();
+        let (index, sync) = SemanticIndex::load_or_build(path, &scrolls, Arc::new(embedder))?;
+        self.semantic_index = Some(index);
+        Ok(sync)
+    }
+
+    /// Saves the semantic index to `path`; does nothing if none is built.
+    pub fn save_semantic_index(&self, path: &Path) -> Result<(), ArchiveError> {
+        match &self.semantic_index {
+            Some(index) => index.save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Builds the full-text index over all scroll bodies.
+    pub fn build_text_index(&mut self, options: TextIndexOptions) {
+        let scrolls: Vec<Scroll> = self.scrolls.values().cloned().collect();
+        self.text_index = Some(FullTextIndex::build(&scrolls, options));
+    }
+
+    pub fn text_index_len(&self) -> usize {
+        self.text_index.as_ref().map(|i| i.len()).unwrap_or(0)
+    }
+
+    /// Applies a change reported by the archive watcher, keeping the semantic
+    /// and full-text indexes in step if they have been built.
+    pub fn apply_change(&mut self, change: &ArchiveChange) -> Result<(), ArchiveError> {
+        match change {
+            ArchiveChange::Created(scroll) | ArchiveChange::Modified(scroll) => {
+                if let Some(index) = &mut self.semantic_index {
+                    index.upsert(scroll)?;
+                }
+                if let Some(index) = &mut self.text_index {
+                    index.upsert(scroll);
+                }
+                self.scrolls.insert(scroll.id, (**scroll).clone());
+            }
+            ArchiveChange::Removed { id, .. } => {
+                if let Some(index) = &mut self.semantic_index {
+                    index.remove(*id);
+                }
+                if let Some(index) = &mut self.text_index {
+                    index.remove(*id);
+                }
+                self.scrolls.remove(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Embeds every scroll missing from the semantic index, such as those
+    /// changed through [`ArchiveMemory`]. Returns how many were embedded;
+    /// does nothing if no index has been built.
+    pub fn refresh_semantic_index(&mut self) -> Result<usize, ArchiveError> {
+        let Some(index) = &mut self.semantic_index else {
+            return Ok(0);
+        };
+        let mut embedded = 0;
+        for scroll in self.scrolls.values() {
+            if !index.contains(scroll.id) {
+                index.upsert(scroll)?;
+                embedded += 1;
+            }
+        }
+        Ok(embedded)
+    }
+
     /// Query scrolls using semantic similarity of title and tags.
     pub fn query_semantic(&self, input: &str, k: usize) -> Vec<(Scroll, f32)> {
         if let Some(idx) = &self.semantic_index {
@@ -97,6 +212,19 @@ impl InMemoryArchive {
             Vec::new()
         }
     }
+
+    /// Full-text query over scroll bodies; see [`FullTextIndex::search`].
+    pub fn query_text(&self, query: &str, k: usize) -> Vec<(Scroll, TextHit)> {
+        let Some(index) = &self.text_index else {
+            info!("Full-text index not built; returning empty results");
+            return Vec::new();
+        };
+        index
+            .search(query, k)
+            .into_iter()
+            .filter_map(|hit| Some((self.scrolls.get(&hit.id)?.clone(), hit)))
+            .collect()
+    }
 }
 
 impl ArchiveMemory for InMemoryArchive {
@@ -122,8 +250,57 @@ impl ArchiveMemory for InMemoryArchive {
     fn query_semantic(&self, input: &str, k: usize) -> Vec<(Scroll, f32)> {
         InMemoryArchive::query_semantic(self, input, k)
     }
+
+    fn query_text(&self, query: &str, k: usize) -> Vec<(Scroll, TextHit)> {
+        InMemoryArchive::query_text(self, query, k)
+    }
+
+    fn contains(&self, id: Uuid) -> bool {
+        self.scrolls.contains_key(&id)
+    }
+
+    fn insert(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        if self.scrolls.contains_key(&scroll.id) {
+            return Err(ArchiveError::DuplicateScroll(scroll.id));
+        }
+        self.reindex(&scroll);
+        self.scrolls.insert(scroll.id, scroll.clone());
+        Ok(ArchiveChange::Created(Box::new(scroll)))
+    }
+
+    fn update(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        let Some(existing) = self.scrolls.get_mut(&scroll.id) else {
+            return Err(ArchiveError::ScrollNotFound(scroll.id));
+        };
+        *existing = scroll.clone();
+        self.reindex(&scroll);
+        Ok(ArchiveChange::Modified(Box::new(scroll)))
+    }
+
+    fn remove(&mut self, id: Uuid) -> Result<ArchiveChange, ArchiveError> {
+        let scroll = self
+            .scrolls
+            .remove(&id)
+            .ok_or(ArchiveError::ScrollNotFound(id))?;
+        if let Some(index) = &mut self.semantic_index {
+            index.remove(id);
+        }
+        if let Some(index) = &mut self.text_index {
+            index.remove(id);
+        }
+        Ok(ArchiveChange::removed(&scroll))
+    }
 }
 
-// Future implementation placeholder for cache-aware archive model.
-// pub struct HybridCacheArchive { /* future phase */ }
-// impl ArchiveMemory for HybridCacheArchive { /* ... */ }
+impl InMemoryArchive {
+    /// Re-indexes the full text of a changed scroll and drops its stale
+    /// semantic vector.
+    fn reindex(&mut self, scroll: &Scroll) {
+        if let Some(index) = &mut self.semantic_index {
+            index.remove(scroll.id);
+        }
+        if let Some(index) = &mut self.text_index {
+            index.upsert(scroll);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Embedders for the semantic index. [`HashingEmbedder`] works offline by
+//! hashing TF-IDF weighted words and word pairs into a fixed-size vector;
+//! [`HttpEmbedder`] calls an OpenAI-compatible `/embeddings` endpoint.
+// src/archive/embedding.rs
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+
+use dotenvy::dotenv;
+use log::info;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::archive::error::ArchiveError;
+use crate::archive::full_text::{analyze, TextIndexOptions};
+use crate::archive::semantic_index::{fnv1a, index_text, Embedder};
+use crate::scroll::Scroll;
+
+/// Dimensions of a [`HashingEmbedder`] built with `default()`.
+pub const DEFAULT_HASHING_DIMS: usize = 512;
+
+/// Model requested by [`HttpEmbedder::from_env`] unless overridden.
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// How far the archive may grow or shrink, as a share of the scrolls a
+/// saved [`HashingEmbedder`] model was fitted on, before the model is
+/// refitted instead of restored.
+pub const MAX_FIT_DRIFT: f32 = 0.25;
+
+/// Offline feature-hashing embedder. Stemmed words and adjacent word pairs
+/// are weighted by `(1 + ln tf) * idf`, hashed with a sign into `dims`
+/// buckets and L2-normalized. Fitting on the archive learns the document
+/// frequencies behind the idf weights; unfitted, every feature weighs the
+/// same.
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dims: usize,
+    options: TextIndexOptions,
+    /// Scrolls the document frequencies were counted over.
+    documents: usize,
+    df: HashMap<String, usize>,
+    /// Decline saved models, see [`HashingEmbedder::refit`].
+    refit: bool,
+}
+
+/// The fitted state of a [`HashingEmbedder`], as saved with the index.
+#[derive(Serialize, Deserialize)]
+struct HashingModel {
+    documents: usize,
+    df: HashMap<String, usize>,
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASHING_DIMS)
+    }
+}
+
+impl HashingEmbedder {
+    pub fn new(dims: usize) -> Self {
+        Self {
+            dims: dims.max(1),
+            options: TextIndexOptions::default(),
+            documents: 0,
+            df: HashMap::new(),
+            refit: false,
+        }
+    }
+
+    /// Counts document frequencies over the text the semantic index embeds
+    /// for each scroll.
+    pub fn fit(mut self, scrolls: &[Scroll]) -> Self {
+        self.df.clear();
+        for scroll in scrolls {
+            let mut features = self.features(&index_text(scroll));
+            features.sort();
+            features.dedup();
+            for feature in features {
+                *self.df.entry(feature).or_default() += 1;
+            }
+        }
+        self.documents = scrolls.len();
+        self
+    }
+
+    /// Keeps this fit even when a saved index has a model that could be
+    /// restored, so loading the index re-embeds every scroll.
+    pub fn refit(mut self) -> Self {
+        self.refit = true;
+        self
+    }
+
+    pub fn dims(&self) -> usize {
+        self.dims
+    }
+
+    /// Smoothed inverse document frequency of `feature`.
+    fn idf(&self, feature: &str) -> f32 {
+        let df = self.df.get(feature).copied().unwrap_or(0) as f32;
+        ((1.0 + self.documents as f32) / (1.0 + df)).ln() + 1.0
+    }
+
+    /// Analyzed words plus each pair of adjacent words.
+    fn features(&self, text: &str) -> Vec<String> {
+        let terms: Vec<String> = analyze(text, &self.options)
+            .into_iter()
+            .map(|(_, term)| term)
+            .collect();
+        let pairs: Vec<String> = terms
+            .windows(2)
+            .map(|pair| format!("{} {}", pair[0], pair[1]))
+            .collect();
+        terms.into_iter().chain(pairs).collect()
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for feature in self.features(text) {
+            *counts.entry(feature).or_default() += 1;
+        }
+        let mut vector = vec![0.0f32; self.dims];
+        for (feature, tf) in counts {
+            let weight = (1.0 + (tf as f32).ln()) * self.idf(&feature);
+            let hash = fnv1a(feature.as_bytes());
+            let sign = if hash >> 63 == 1 { -1.0 } else { 1.0 };
+            vector[(hash % self.dims as u64) as usize] += sign * weight;
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        Ok(vector)
+    }
+
+    fn identity(&self) -> String {
+        format!("hashing:{}", self.dims)
+    }
+
+    fn model(&self) -> Option<serde_json::Value> {
+        let model = HashingModel {
+            documents: self.documents,
+            df: self.df.clone(),
+        };
+        serde_json::to_value(model).ok()
+    }
+
+    /// Restores the saved document frequencies unless [`refit`] was asked
+    /// for or this embedder was fitted on an archive whose size differs from
+    /// the saved one by more than [`MAX_FIT_DRIFT`].
+    ///
+    /// [`refit`]: HashingEmbedder::refit
+    fn restore(&self, model: serde_json::Value) -> Option<Arc<dyn Embedder>> {
+        let model: HashingModel = serde_json::from_value(model).ok()?;
+        if self.refit {
+            return None;
+        }
+        if self.documents > 0 {
+            let drift = self.documents.abs_diff(model.documents) as f32;
+            if drift > MAX_FIT_DRIFT * model.documents.max(1) as f32 {
+                info!(
+                    "Refitting embedder: archive has {} scrolls, model was fitted on {}",
+                    self.documents, model.documents
+                );
+                return None;
+            }
+        }
+        Some(Arc::new(Self {
+            documents: model.documents,
+            df: model.df,
+            ..self.clone()
+        }))
+    }
+}
+
+/// Embeds text through an OpenAI-compatible `POST /embeddings` endpoint.
+/// Uses a blocking client, so call it from outside an async runtime.
+#[derive(Debug, Clone)]
+pub struct HttpEmbedder {
+    pub endpoint: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    client: Client,
+}
+
+impl HttpEmbedder {
+    /// `base_url` is the API root, e.g. `https://api.openai.com/v1`.
+    pub fn new(base_url: &str, model: impl Into<String>) -> Self {
+        Self {
+            endpoint: format!("{}/embeddings", base_url.trim_end_matches('/')),
+            model: model.into(),
+            api_key: None,
+            client: Client::new(),
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Reads `SCROLL_CORE_EMBEDDINGS_URL`, `SCROLL_CORE_EMBEDDINGS_MODEL`
+    /// and `OPENAI_API_KEY`; `None` when no URL is configured.
+    pub fn from_env() -> Option<Self> {
+        dotenv().ok();
+        let url = env::var("SCROLL_CORE_EMBEDDINGS_URL").ok()?;
+        let model = env::var("SCROLL_CORE_EMBEDDINGS_MODEL")
+            .unwrap_or_else(|_| DEFAULT_EMBEDDING_MODEL.into());
+        let embedder = Self::new(&url, model);
+        Some(match env::var("OPENAI_API_KEY") {
+            Ok(key) => embedder.with_api_key(key),
+            Err(_) => embedder,
+        })
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError> {
+        let body = serde_json::json!({ "model": self.model, "input": text });
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let failure = |e: reqwest::Error| ArchiveError::EmbeddingFailure(e.to_string());
+        let json: serde_json::Value = request
+            .send()
+            .and_then(|res| res.error_for_status())
+            .map_err(failure)?
+            .json()
+            .map_err(failure)?;
+        json["data"][0]["embedding"]
+            .as_array()
+            .and_then(|values| {
+                values
+                    .iter()
+                    .map(|v| v.as_f64().map(|x| x as f32))
+                    .collect()
+            })
+            .ok_or_else(|| ArchiveError::EmbeddingFailure("malformed embeddings response".into()))
+    }
+
+    fn identity(&self) -> String {
+        format!("http:{}#{}", self.endpoint, self.model)
+    }
+}
+
+/// The embedder to use for `scrolls`: an [`HttpEmbedder`] when
+/// `SCROLL_CORE_EMBEDDINGS_URL` is set, otherwise a [`HashingEmbedder`]
+/// fitted on the scrolls. Setting `SCROLL_CORE_REFIT_EMBEDDINGS` keeps that
+/// fit instead of the model saved with the index.
+pub fn embedder_from_env(scrolls: &[Scroll]) -> Box<dyn Embedder> {
+    match HttpEmbedder::from_env() {
+        Some(embedder) => {
+            info!(
+                "Embedding scrolls with {} ({})",
+                embedder.endpoint, embedder.model
+            );
+            Box::new(embedder)
+        }
+        None => {
+            let embedder = HashingEmbedder::default().fit(scrolls);
+            match env::var_os("SCROLL_CORE_REFIT_EMBEDDINGS") {
+                Some(_) => Box::new(embedder.refit()),
+                None => Box::new(embedder),
+            }
+        }
+    }
+}
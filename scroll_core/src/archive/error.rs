@@ -3,6 +3,9 @@
 // src/archive/error.rs
 
 use thiserror::Error;
+use uuid::Uuid;
+
+use crate::parser::ParseError;
 
 #[derive(Debug, Error)]
 pub enum ArchiveError {
@@ -12,6 +15,18 @@ pub enum ArchiveError {
     MissingModel,
     #[error("embedding failed: {0}")]
     EmbeddingFailure(String),
+    #[error("scroll {0} is already in the archive")]
+    DuplicateScroll(Uuid),
+    #[error("scroll {0} is not in the archive")]
+    ScrollNotFound(Uuid),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("write failed: {0}")]
+    Write(String),
+    #[error("semantic index file is corrupt: {0}")]
+    CorruptIndex(String),
+    #[error("database error: {0}")]
+    Database(String),
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 }
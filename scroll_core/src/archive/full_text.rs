@@ -0,0 +1,438 @@
+//! Full-text search over whole scroll bodies. An inverted index records the
+//! position of every term, so queries can rank scrolls with BM25, match
+//! quoted phrases and `prefix*` terms, and return highlighted snippets.
+// src/archive/full_text.rs
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use log::info;
+use uuid::Uuid;
+
+use crate::scroll::Scroll;
+
+/// Common English words left out of the index when stopwords are enabled.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "he",
+    "her", "his", "i", "if", "in", "into", "is", "it", "its", "of", "on", "or", "she", "so",
+    "that", "the", "their", "them", "then", "there", "they", "this", "to", "was", "we", "were",
+    "which", "who", "will", "with", "you",
+];
+
+/// How scroll text is analyzed and ranked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextIndexOptions {
+    /// Strip common English suffixes, so "rivers" and "river" match.
+    pub stemming: bool,
+    /// Leave common words such as "the" out of the index and of queries.
+    pub stopwords: bool,
+    /// BM25 term-frequency saturation.
+    pub k1: f32,
+    /// BM25 length normalization.
+    pub b: f32,
+    /// Words of context in each snippet.
+    pub snippet_words: usize,
+}
+
+impl Default for TextIndexOptions {
+    fn default() -> Self {
+        Self {
+            stemming: true,
+            stopwords: true,
+            k1: 1.2,
+            b: 0.75,
+            snippet_words: 16,
+        }
+    }
+}
+
+impl TextIndexOptions {
+    pub fn stemming(mut self, stemming: bool) -> Self {
+        self.stemming = stemming;
+        self
+    }
+
+    pub fn stopwords(mut self, stopwords: bool) -> Self {
+        self.stopwords = stopwords;
+        self
+    }
+}
+
+/// A ranked match. Matched words in `snippet` are wrapped in `**`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextHit {
+    pub id: Uuid,
+    pub score: f32,
+    pub snippet: String,
+}
+
+struct Document {
+    text: String,
+    terms: HashSet<String>,
+    len: usize,
+}
+
+/// One part of a query: a term, a `prefix*`, or a quoted phrase of terms
+/// with their word offsets.
+#[derive(Debug, Clone, PartialEq)]
+enum Clause {
+    Term(String),
+    Prefix(String),
+    Phrase(Vec<(u32, String)>),
+}
+
+/// Inverted index from analyzed terms to their word positions per scroll.
+pub struct FullTextIndex {
+    options: TextIndexOptions,
+    postings: BTreeMap<String, HashMap<Uuid, Vec<u32>>>,
+    documents: HashMap<Uuid, Document>,
+    total_len: usize,
+}
+
+impl FullTextIndex {
+    pub fn new(options: TextIndexOptions) -> Self {
+        Self {
+            options,
+            postings: BTreeMap::new(),
+            documents: HashMap::new(),
+            total_len: 0,
+        }
+    }
+
+    pub fn build(scrolls: &[Scroll], options: TextIndexOptions) -> Self {
+        info!("Indexing full text of {} scrolls", scrolls.len());
+        let mut index = Self::new(options);
+        for scroll in scrolls {
+            index.upsert(scroll);
+        }
+        index
+    }
+
+    pub fn options(&self) -> &TextIndexOptions {
+        &self.options
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.documents.contains_key(&id)
+    }
+
+    /// Indexes the title and body of `scroll`, replacing any earlier version.
+    pub fn upsert(&mut self, scroll: &Scroll) {
+        self.remove(scroll.id);
+        let text = format!("{}\n\n{}", scroll.title, scroll.markdown_body);
+        let analyzed = analyze(&text, &self.options);
+        let mut terms = HashSet::new();
+        for (position, term) in &analyzed {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .entry(scroll.id)
+                .or_default()
+                .push(*position);
+            terms.insert(term.clone());
+        }
+        self.total_len += analyzed.len();
+        self.documents.insert(
+            scroll.id,
+            Document {
+                text,
+                terms,
+                len: analyzed.len(),
+            },
+        );
+    }
+
+    /// Drops `id` from the index; returns whether it was indexed.
+    pub fn remove(&mut self, id: Uuid) -> bool {
+        let Some(document) = self.documents.remove(&id) else {
+            return false;
+        };
+        for term in &document.terms {
+            if let Some(docs) = self.postings.get_mut(term) {
+                docs.remove(&id);
+                if docs.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.total_len -= document.len;
+        true
+    }
+
+    /// Ranks scrolls against `query` with BM25 and returns the best `k`.
+    ///
+    /// Words are alternatives; `"quoted words"` only match as a phrase and
+    /// `word*` matches every term starting with `word`.
+    pub fn search(&self, query: &str, k: usize) -> Vec<TextHit> {
+        info!("Performing full-text search for '{query}'");
+        let mut scores: HashMap<Uuid, f32> = HashMap::new();
+        let mut matched: HashMap<Uuid, HashSet<u32>> = HashMap::new();
+        for clause in parse_query(query, &self.options) {
+            for (id, positions, tf, df) in self.clause_matches(&clause) {
+                *scores.entry(id).or_default() += self.bm25(id, tf, df);
+                matched.entry(id).or_default().extend(positions);
+            }
+        }
+
+        let mut ranked: Vec<(Uuid, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+            .into_iter()
+            .take(k)
+            .map(|(id, score)| TextHit {
+                id,
+                score,
+                snippet: self.snippet(id, &matched[&id]),
+            })
+            .collect()
+    }
+
+    /// Documents matching `clause` with the matched word positions, the
+    /// clause frequency in each and the number of matching documents.
+    fn clause_matches(&self, clause: &Clause) -> Vec<(Uuid, Vec<u32>, usize, usize)> {
+        match clause {
+            Clause::Term(term) => self.term_matches(term),
+            Clause::Prefix(prefix) => self
+                .postings
+                .range(prefix.clone()..)
+                .take_while(|(term, _)| term.starts_with(prefix.as_str()))
+                .flat_map(|(term, _)| self.term_matches(term))
+                .collect(),
+            Clause::Phrase(words) => {
+                let Some((_, first)) = words.first() else {
+                    return Vec::new();
+                };
+                let Some(candidates) = self.postings.get(first) else {
+                    return Vec::new();
+                };
+                let mut found = Vec::new();
+                for (id, starts) in candidates {
+                    let mut positions = Vec::new();
+                    let mut count = 0;
+                    for start in starts {
+                        let whole = words.iter().all(|(offset, term)| {
+                            self.postings
+                                .get(term)
+                                .and_then(|docs| docs.get(id))
+                                .is_some_and(|p| p.contains(&(start + offset)))
+                        });
+                        if whole {
+                            count += 1;
+                            positions.extend(words.iter().map(|(offset, _)| start + offset));
+                        }
+                    }
+                    if count > 0 {
+                        found.push((*id, positions, count));
+                    }
+                }
+                let df = found.len();
+                found
+                    .into_iter()
+                    .map(|(id, positions, tf)| (id, positions, tf, df))
+                    .collect()
+            }
+        }
+    }
+
+    fn term_matches(&self, term: &str) -> Vec<(Uuid, Vec<u32>, usize, usize)> {
+        let Some(docs) = self.postings.get(term) else {
+            return Vec::new();
+        };
+        docs.iter()
+            .map(|(id, positions)| (*id, positions.clone(), positions.len(), docs.len()))
+            .collect()
+    }
+
+    fn bm25(&self, id: Uuid, tf: usize, df: usize) -> f32 {
+        let n = self.documents.len() as f32;
+        let idf = (1.0 + (n - df as f32 + 0.5) / (df as f32 + 0.5)).ln();
+        let len = self.documents.get(&id).map(|d| d.len).unwrap_or(0) as f32;
+        let avg_len = (self.total_len as f32 / n.max(1.0)).max(1.0);
+        let tf = tf as f32;
+        let TextIndexOptions { k1, b, .. } = self.options;
+        idf * tf * (k1 + 1.0) / (tf + k1 * (1.0 - b + b * len / avg_len))
+    }
+
+    /// The window of the document with the most matched words, matches
+    /// wrapped in `**`, with `…` where text was cut.
+    fn snippet(&self, id: Uuid, matched: &HashSet<u32>) -> String {
+        let Some(document) = self.documents.get(&id) else {
+            return String::new();
+        };
+        let spans = words(&document.text);
+        let width = self.options.snippet_words.max(1);
+        let start = matched
+            .iter()
+            .map(|p| {
+                let start = (*p as usize).saturating_sub(width / 4);
+                let hits = matched
+                    .iter()
+                    .filter(|q| (start..start + width).contains(&(**q as usize)))
+                    .count();
+                (hits, std::cmp::Reverse(start))
+            })
+            .max()
+            .map(|(_, std::cmp::Reverse(start))| start)
+            .unwrap_or(0);
+        let end = (start + width).min(spans.len());
+        if start >= end {
+            return String::new();
+        }
+
+        let text = &document.text;
+        let mut out = String::new();
+        if start > 0 {
+            out.push('…');
+        }
+        let mut cursor = spans[start].0;
+        for (position, (from, to)) in spans.iter().enumerate().take(end).skip(start) {
+            out.push_str(&collapse_whitespace(&text[cursor..*from]));
+            if matched.contains(&(position as u32)) {
+                out.push_str("**");
+                out.push_str(&text[*from..*to]);
+                out.push_str("**");
+            } else {
+                out.push_str(&text[*from..*to]);
+            }
+            cursor = *to;
+        }
+        if end < spans.len() {
+            out.push('…');
+        }
+        out
+    }
+}
+
+/// Byte spans of the words in `text`: runs of letters and digits.
+fn words(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+    spans
+}
+
+/// Index terms of `text` with their word positions. Stopwords are dropped
+/// but still count as positions, so phrases across them keep their shape.
+pub(crate) fn analyze(text: &str, options: &TextIndexOptions) -> Vec<(u32, String)> {
+    words(text)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(position, (from, to))| {
+            let word = text[from..to].to_lowercase();
+            if options.stopwords && STOPWORDS.contains(&word.as_str()) {
+                return None;
+            }
+            let term = if options.stemming { stem(&word) } else { word };
+            Some((position as u32, term))
+        })
+        .collect()
+}
+
+/// Light English suffix stripping; keeps at least three letters of stem.
+fn stem(word: &str) -> String {
+    const SUFFIXES: &[(&str, &str)] = &[
+        ("ational", "ate"),
+        ("ization", "ize"),
+        ("fulness", "ful"),
+        ("iveness", "ive"),
+        ("ousness", "ous"),
+        ("ingly", ""),
+        ("edly", ""),
+        ("ness", ""),
+        ("ment", ""),
+        ("ies", "y"),
+        ("ied", "y"),
+        ("ing", ""),
+        ("ed", ""),
+        ("ly", ""),
+        ("s", ""),
+    ];
+    for (suffix, replacement) in SUFFIXES {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            let keeps_s = *suffix == "s" && stem.ends_with(['s', 'u', 'i']);
+            if stem.chars().count() >= 3 && !keeps_s {
+                return format!("{stem}{replacement}");
+            }
+        }
+    }
+    word.to_string()
+}
+
+fn parse_query(query: &str, options: &TextIndexOptions) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let terms = analyze(part, options);
+            let Some(&(first, _)) = terms.first() else {
+                continue;
+            };
+            let phrase: Vec<(u32, String)> = terms
+                .into_iter()
+                .map(|(position, term)| (position - first, term))
+                .collect();
+            match phrase.as_slice() {
+                [(_, term)] => clauses.push(Clause::Term(term.clone())),
+                _ => clauses.push(Clause::Phrase(phrase)),
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            match word.strip_suffix('*') {
+                Some(prefix) => {
+                    let prefix: String = prefix
+                        .chars()
+                        .filter(|c| c.is_alphanumeric())
+                        .collect::<String>()
+                        .to_lowercase();
+                    if !prefix.is_empty() {
+                        clauses.push(Clause::Prefix(prefix));
+                    }
+                }
+                None => clauses.extend(
+                    analyze(word, options)
+                        .into_iter()
+                        .map(|(_, term)| Clause::Term(term)),
+                ),
+            }
+        }
+    }
+    clauses
+}
+
+fn collapse_whitespace(gap: &str) -> String {
+    if gap.chars().any(char::is_whitespace) {
+        let trimmed: String = gap.split_whitespace().collect::<Vec<_>>().join(" ");
+        let lead = if gap.starts_with(char::is_whitespace) {
+            " "
+        } else {
+            ""
+        };
+        let tail = if gap.ends_with(char::is_whitespace) && !trimmed.is_empty() {
+            " "
+        } else {
+            ""
+        };
+        format!("{lead}{trimmed}{tail}")
+    } else {
+        gap.to_string()
+    }
+}
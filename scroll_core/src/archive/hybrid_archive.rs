@@ -0,0 +1,511 @@
+//! Cache-aware archive memory. Hot scrolls stay in the [`CacheManager`],
+//! ranked by mythic heat; cold ones live in a [`ScrollStore`] (the archive
+//! directory or the SQLite archive tables) and are paged in when read.
+// src/archive/hybrid_archive.rs
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use log::warn;
+use uuid::Uuid;
+
+use crate::access::Actor;
+use crate::archive::archive_loader::{scan_archive, LoadOptions};
+use crate::archive::archive_memory::ArchiveMemory;
+use crate::archive::error::ArchiveError;
+use crate::archive::full_text::{FullTextIndex, TextHit, TextIndexOptions};
+use crate::archive::link_resolver::resolve_linked_scrolls;
+use crate::archive::scroll_access_log::ScrollAccessLog;
+use crate::archive::semantic_index::{Embedder, SemanticIndex};
+use crate::archive::watcher::ArchiveChange;
+use crate::cache_manager::CacheManager;
+use crate::core::cost_manager::InvocationCost;
+use crate::parser::{self, body::slugify};
+use crate::scroll::{Scroll, ScrollLink};
+use crate::scroll_writer::ScrollWriter;
+
+/// Backing storage for scrolls that are not held in memory.
+pub trait ScrollStore {
+    /// Every stored scroll id with its tags.
+    fn catalog(&self) -> Result<HashMap<Uuid, Vec<String>>, ArchiveError>;
+    fn load(&self, id: Uuid) -> Result<Option<Scroll>, ArchiveError>;
+    /// Stores `scroll` on behalf of `actor`, refusing to overwrite a
+    /// protected scroll the actor may not edit.
+    fn save(&mut self, scroll: &Scroll, actor: &Actor) -> Result<(), ArchiveError>;
+    fn delete(&mut self, id: Uuid) -> Result<(), ArchiveError>;
+}
+
+struct StoredFile {
+    path: PathBuf,
+    tags: Vec<String>,
+    links: Vec<ScrollLink>,
+}
+
+/// Scroll files under an archive root. Each file is parsed once when the
+/// store opens, to learn its id, tags and resolved links, and again each
+/// time it is loaded.
+pub struct DirectoryStore {
+    root: PathBuf,
+    options: LoadOptions,
+    files: HashMap<Uuid, StoredFile>,
+}
+
+impl DirectoryStore {
+    /// Indexes the scroll files under `root`. Files that fail to parse are
+    /// logged and left out.
+    pub fn open(root: impl Into<PathBuf>, options: LoadOptions) -> Result<Self, ArchiveError> {
+        let root = root.into();
+        let (paths, _) = scan_archive(&root, &options)
+            .map_err(|e| ArchiveError::Io(std::io::Error::other(e)))?;
+        let mut scrolls: Vec<Scroll> = paths
+            .iter()
+            .filter_map(
+                |path| match parser::parse_archive_scroll(&root, path, options.mode) {
+                    Ok(scroll) => Some(scroll),
+                    Err(e) => {
+                        warn!("{}", e.render());
+                        None
+                    }
+                },
+            )
+            .collect();
+        resolve_linked_scrolls(&mut scrolls);
+        let files = scrolls
+            .into_iter()
+            .filter_map(|scroll| {
+                let file = StoredFile {
+                    path: scroll.origin.source_path?,
+                    tags: scroll.yaml_metadata.tags,
+                    links: scroll.linked_scrolls,
+                };
+                Some((scroll.id, file))
+            })
+            .collect();
+        Ok(Self {
+            root,
+            options,
+            files,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where `scroll` is written: its known file, the file it was read from,
+    /// or a new file named after its title. New files never replace another
+    /// scroll's file; a taken name gets a `-2`, `-3`, ... suffix.
+    fn path_for(&self, scroll: &Scroll) -> PathBuf {
+        if let Some(file) = self.files.get(&scroll.id) {
+            return file.path.clone();
+        }
+        let claimed = |path: &Path| self.files.values().any(|file| file.path == path);
+        if let Some(path) = &scroll.origin.source_path {
+            if !claimed(path) {
+                return path.clone();
+            }
+        }
+        let slug = match slugify(&scroll.title) {
+            slug if slug.is_empty() => scroll.id.to_string(),
+            slug => slug,
+        };
+        (1..)
+            .map(|n| match n {
+                1 => self.root.join(format!("{slug}.md")),
+                n => self.root.join(format!("{slug}-{n}.md")),
+            })
+            .find(|path| !path.exists() && !claimed(path))
+            .expect("some suffix is free")
+    }
+}
+
+impl ScrollStore for DirectoryStore {
+    fn catalog(&self) -> Result<HashMap<Uuid, Vec<String>>, ArchiveError> {
+        Ok(self
+            .files
+            .iter()
+            .map(|(id, file)| (*id, file.tags.clone()))
+            .collect())
+    }
+
+    fn load(&self, id: Uuid) -> Result<Option<Scroll>, ArchiveError> {
+        let Some(file) = self.files.get(&id) else {
+            return Ok(None);
+        };
+        let mut scroll = parser::parse_archive_scroll(&self.root, &file.path, self.options.mode)?;
+        scroll.linked_scrolls = file.links.clone();
+        Ok(Some(scroll))
+    }
+
+    fn save(&mut self, scroll: &Scroll, actor: &Actor) -> Result<(), ArchiveError> {
+        let path = self.path_for(scroll);
+        ScrollWriter::write_scroll_as(scroll, &path, actor).map_err(ArchiveError::Write)?;
+        self.files.insert(
+            scroll.id,
+            StoredFile {
+                path,
+                tags: scroll.yaml_metadata.tags.clone(),
+                links: scroll.linked_scrolls.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    fn delete(&mut self, id: Uuid) -> Result<(), ArchiveError> {
+        if let Some(file) = self.files.remove(&id) {
+            fs::remove_file(&file.path)?;
+        }
+        Ok(())
+    }
+}
+
+struct Entry {
+    tags: Vec<String>,
+    /// A cold scroll paged in by a shared read, held until [`settle`]
+    /// offers it to the cache. `Some(None)` records a failed load.
+    ///
+    /// [`settle`]: HybridCacheArchive::settle
+    paged: OnceCell<Option<Scroll>>,
+}
+
+impl Entry {
+    fn new(tags: Vec<String>) -> Self {
+        Self {
+            tags,
+            paged: OnceCell::new(),
+        }
+    }
+}
+
+/// Archive memory that keeps at most `capacity` scrolls cached by heat and
+/// pages the rest in from a [`ScrollStore`]. Mutations write through to the
+/// store first, on behalf of the archive's actor (`system` unless set with
+/// [`with_actor`](Self::with_actor)).
+///
+/// Reads through [`ArchiveMemory`] take `&self`. Those that return a
+/// reference hold the cold scroll they page in beside the cache until the
+/// next [`settle`] (called by every mutation and by [`fetch`]), which lets
+/// the cache decide, by heat, which scrolls stay and drops the rest; call
+/// it between batches of reads to bound memory. Queries that return copies
+/// load cold scrolls without holding them and only note the read.
+///
+/// [`settle`]: Self::settle
+/// [`fetch`]: Self::fetch
+pub struct HybridCacheArchive {
+    store: Box<dyn ScrollStore>,
+    cache: CacheManager,
+    access: RefCell<ScrollAccessLog>,
+    entries: HashMap<Uuid, Entry>,
+    /// Cold scrolls copied out by queries since the last settle.
+    copied: RefCell<HashSet<Uuid>>,
+    semantic_index: Option<SemanticIndex>,
+    text_index: Option<FullTextIndex>,
+    actor: Actor,
+}
+
+impl HybridCacheArchive {
+    /// Reads the store's catalog; no scroll is loaded until it is read.
+    pub fn new(store: impl ScrollStore + 'static, capacity: usize) -> Result<Self, ArchiveError> {
+        let entries = store
+            .catalog()?
+            .into_iter()
+            .map(|(id, tags)| (id, Entry::new(tags)))
+            .collect();
+        Ok(Self {
+            store: Box::new(store),
+            cache: CacheManager::new(capacity),
+            access: RefCell::new(ScrollAccessLog::new()),
+            entries,
+            copied: RefCell::new(HashSet::new()),
+            semantic_index: None,
+            text_index: None,
+            actor: Actor::new("system"),
+        })
+    }
+
+    /// Writes to the store as `actor`.
+    pub fn with_actor(mut self, actor: Actor) -> Self {
+        self.actor = actor;
+        self
+    }
+
+    pub fn cache(&self) -> &CacheManager {
+        &self.cache
+    }
+
+    pub fn is_hot(&self, id: Uuid) -> bool {
+        self.cache.get(&id).is_some()
+    }
+
+    /// Reads a scroll and offers it to the cache straight away.
+    pub fn fetch(&mut self, id: Uuid) -> Option<&Scroll> {
+        let scroll = self.get_scroll_by_id(id)?.clone();
+        self.settle();
+        if !self.is_hot(id) {
+            // Colder than everything cached; keep it paged for this read.
+            let _ = self.entries.get_mut(&id)?.paged.set(Some(scroll));
+        }
+        self.resident(id)
+    }
+
+    /// Cold scrolls currently held for shared reads.
+    pub fn paged_count(&self) -> usize {
+        self.entries
+            .values()
+            .filter(|entry| entry.paged.get().is_some_and(Option::is_some))
+            .count()
+    }
+
+    /// Moves scrolls paged in or copied out by shared reads into the cache
+    /// and re-scores cached scrolls read since they were cached. Scrolls that
+    /// are too cold to stay are dropped from memory. Returns how many
+    /// scrolls were paged.
+    pub fn settle(&mut self) -> usize {
+        let mut paged: Vec<Scroll> = self
+            .entries
+            .values_mut()
+            .filter_map(|entry| entry.paged.take().flatten())
+            .collect();
+        let copied: Vec<Scroll> = self
+            .copied
+            .get_mut()
+            .drain()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter(|id| self.cache.get(id).is_none() && paged.iter().all(|s| s.id != *id))
+            .filter_map(|id| self.load(id))
+            .collect();
+        paged.extend(copied);
+        let access = self.access.get_mut();
+        let reread: Vec<Scroll> = self
+            .cache
+            .heat_scores
+            .iter()
+            .filter(|(id, heat)| {
+                access
+                    .get(id)
+                    .is_some_and(|a| a.last_accessed > heat.last_accessed)
+            })
+            .filter_map(|(id, _)| self.cache.get(id).cloned())
+            .collect();
+        let count = paged.len();
+        for scroll in paged.into_iter().chain(reread) {
+            self.cache_hot(scroll);
+        }
+        count
+    }
+
+    /// Embeds every scroll, paging each in once without caching it.
+    pub fn build_semantic_index(
+        &mut self,
+        embedder: impl Embedder + 'static,
+    ) -> Result<(), ArchiveError> {
+        if self.entries.is_empty() {
+            return Err(ArchiveError::EmptyScrollSet);
+        }
+        let mut index = SemanticIndex::new(Arc::new(embedder));
+        for id in self.entries.keys() {
+            if let Some(scroll) = self.cache.get(id).cloned().or_else(|| self.load(*id)) {
+                index.upsert(&scroll)?;
+            }
+        }
+        self.semantic_index = Some(index);
+        Ok(())
+    }
+
+    /// Indexes the full text of every scroll, paging each in once without
+    /// caching it.
+    pub fn build_text_index(&mut self, options: TextIndexOptions) {
+        let mut index = FullTextIndex::new(options);
+        for id in self.entries.keys() {
+            if let Some(scroll) = self.cache.get(id).cloned().or_else(|| self.load(*id)) {
+                index.upsert(&scroll);
+            }
+        }
+        self.text_index = Some(index);
+    }
+
+    /// Embeds scrolls changed since the semantic index was built.
+    pub fn refresh_semantic_index(&mut self) -> Result<usize, ArchiveError> {
+        let Some(index) = &self.semantic_index else {
+            return Ok(0);
+        };
+        let stale: Vec<Uuid> = self
+            .entries
+            .keys()
+            .filter(|id| !index.contains(**id))
+            .copied()
+            .collect();
+        let mut embedded = 0;
+        for id in stale {
+            if let Some(scroll) = self.cache.get(&id).cloned().or_else(|| self.load(id)) {
+                if let Some(index) = &mut self.semantic_index {
+                    index.upsert(&scroll)?;
+                    embedded += 1;
+                }
+            }
+        }
+        Ok(embedded)
+    }
+
+    /// A copy of the scroll for queries that return owned results. Cold
+    /// scrolls are loaded without being held; the read is logged and the
+    /// scroll offered to the cache at the next settle.
+    fn copy_of(&self, id: Uuid) -> Option<Scroll> {
+        let entry = self.entries.get(&id)?;
+        self.access.borrow_mut().register_access(id);
+        if let Some(scroll) = self.cache.get(&id).or_else(|| entry.paged.get()?.as_ref()) {
+            return Some(scroll.clone());
+        }
+        let scroll = self.load(id)?;
+        self.copied.borrow_mut().insert(id);
+        Some(scroll)
+    }
+
+    /// The scroll if it is cached or paged, without logging an access.
+    fn resident(&self, id: Uuid) -> Option<&Scroll> {
+        self.cache.get(&id).or_else(|| {
+            let entry = self.entries.get(&id)?;
+            entry.paged.get()?.as_ref()
+        })
+    }
+
+    fn load(&self, id: Uuid) -> Option<Scroll> {
+        self.store
+            .load(id)
+            .map_err(|e| warn!("Failed to page in scroll {id}: {e}"))
+            .ok()
+            .flatten()
+    }
+
+    fn cache_hot(&mut self, scroll: Scroll) {
+        let access = self
+            .access
+            .get_mut()
+            .get(&scroll.id)
+            .cloned()
+            .unwrap_or_default();
+        let emotion = scroll.emotion_signature.clone();
+        self.cache
+            .cache_scroll(scroll, &emotion, &access, &InvocationCost::default());
+    }
+
+    /// Records a write as an access and caches the written scroll.
+    fn store_written(&mut self, scroll: Scroll) {
+        self.settle();
+        self.access.get_mut().register_access(scroll.id);
+        if let Some(index) = &mut self.semantic_index {
+            index.remove(scroll.id);
+        }
+        if let Some(index) = &mut self.text_index {
+            index.upsert(&scroll);
+        }
+        let entry = self
+            .entries
+            .entry(scroll.id)
+            .or_insert_with(|| Entry::new(Vec::new()));
+        entry.tags = scroll.yaml_metadata.tags.clone();
+        entry.paged.take();
+        self.cache_hot(scroll);
+    }
+}
+
+impl ArchiveMemory for HybridCacheArchive {
+    /// Pages in every cold scroll; prefer tag or id lookups on large
+    /// archives.
+    fn get_all_scrolls(&self) -> Vec<&Scroll> {
+        self.entries
+            .keys()
+            .filter_map(|id| self.get_scroll_by_id(*id))
+            .collect()
+    }
+
+    fn get_scroll_by_id(&self, id: Uuid) -> Option<&Scroll> {
+        let entry = self.entries.get(&id)?;
+        self.access.borrow_mut().register_access(id);
+        self.cache
+            .get(&id)
+            .or_else(|| entry.paged.get_or_init(|| self.load(id)).as_ref())
+    }
+
+    fn get_scrolls_by_tag(&self, tag: &str) -> Vec<&Scroll> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.tags.iter().any(|t| t == tag))
+            .filter_map(|(id, _)| self.get_scroll_by_id(*id))
+            .collect()
+    }
+
+    fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn query_semantic(&self, input: &str, k: usize) -> Vec<(Scroll, f32)> {
+        let Some(index) = &self.semantic_index else {
+            return Vec::new();
+        };
+        index
+            .query(input, k)
+            .into_iter()
+            .filter_map(|(id, score)| Some((self.copy_of(id)?, score)))
+            .collect()
+    }
+
+    fn query_text(&self, query: &str, k: usize) -> Vec<(Scroll, TextHit)> {
+        let Some(index) = &self.text_index else {
+            return Vec::new();
+        };
+        index
+            .search(query, k)
+            .into_iter()
+            .filter_map(|hit| Some((self.copy_of(hit.id)?, hit)))
+            .collect()
+    }
+
+    fn contains(&self, id: Uuid) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    fn insert(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        if self.entries.contains_key(&scroll.id) {
+            return Err(ArchiveError::DuplicateScroll(scroll.id));
+        }
+        self.store.save(&scroll, &self.actor)?;
+        self.store_written(scroll.clone());
+        Ok(ArchiveChange::Created(Box::new(scroll)))
+    }
+
+    fn update(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        if !self.entries.contains_key(&scroll.id) {
+            return Err(ArchiveError::ScrollNotFound(scroll.id));
+        }
+        self.store.save(&scroll, &self.actor)?;
+        self.store_written(scroll.clone());
+        Ok(ArchiveChange::Modified(Box::new(scroll)))
+    }
+
+    fn remove(&mut self, id: Uuid) -> Result<ArchiveChange, ArchiveError> {
+        let change = match self.get_scroll_by_id(id) {
+            Some(scroll) => ArchiveChange::removed(scroll),
+            None if self.entries.contains_key(&id) => ArchiveChange::Removed {
+                id,
+                path: PathBuf::new(),
+            },
+            None => return Err(ArchiveError::ScrollNotFound(id)),
+        };
+        self.store.delete(id)?;
+        self.entries.remove(&id);
+        self.cache.apply_change(&change);
+        if let Some(index) = &mut self.semantic_index {
+            index.remove(id);
+        }
+        if let Some(index) = &mut self.text_index {
+            index.remove(id);
+        }
+        self.settle();
+        Ok(change)
+    }
+}
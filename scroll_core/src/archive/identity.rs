@@ -0,0 +1,117 @@
+//! Stable scroll identities: stamps missing `id` headers into archive files
+//! and reports ids claimed by more than one scroll.
+// src/archive/identity.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::archive::archive_loader::{scan_archive, LoadOptions};
+use crate::archive::error::ArchiveError;
+use crate::parser::{self, split_front_matter, FrontMatterFormat, ParseError, ParseMode};
+use crate::scroll::Scroll;
+
+/// An id shared by several scrolls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdCollision {
+    pub id: Uuid,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Outcome of [`stamp_archive_ids`].
+#[derive(Debug, Default)]
+pub struct StampReport {
+    /// Files whose header had no `id`, with the id that was (or would be) stamped.
+    pub stamped: Vec<(PathBuf, Uuid)>,
+    pub collisions: Vec<IdCollision>,
+    pub failed: Vec<ParseError>,
+}
+
+/// Groups scrolls by id and returns every id used more than once.
+pub fn find_id_collisions(scrolls: &[Scroll]) -> Vec<IdCollision> {
+    let mut by_id: BTreeMap<Uuid, Vec<PathBuf>> = BTreeMap::new();
+    for scroll in scrolls {
+        let path = scroll
+            .origin
+            .source_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(&scroll.title));
+        by_id.entry(scroll.id).or_default().push(path);
+    }
+    by_id
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(id, paths)| IdCollision { id, paths })
+        .collect()
+}
+
+/// Finds archive scrolls without an `id` header and, when `write` is set,
+/// inserts the path-derived id as the first header line. Collisions are
+/// checked across the whole archive after stamping.
+pub fn stamp_archive_ids(archive_root: &Path, write: bool) -> Result<StampReport, ArchiveError> {
+    let mut report = StampReport::default();
+    let mut scrolls = Vec::new();
+
+    let (paths, scan) = scan_archive(archive_root, &LoadOptions::default())
+        .map_err(|e| ArchiveError::Io(std::io::Error::other(e)))?;
+    report
+        .failed
+        .extend(scan.failed.into_iter().map(|failure| failure.error));
+
+    for path in paths {
+        let scroll = match parser::parse_archive_scroll(archive_root, &path, ParseMode::Lenient) {
+            Ok(scroll) => scroll,
+            Err(e) => {
+                report.failed.push(e);
+                continue;
+            }
+        };
+
+        let contents = fs::read_to_string(&path)?;
+        if !declares_id(&contents) {
+            if write {
+                fs::write(&path, insert_id(&contents, scroll.id))?;
+            }
+            report.stamped.push((path, scroll.id));
+        }
+        scrolls.push(scroll);
+    }
+
+    report.collisions = find_id_collisions(&scrolls);
+    Ok(report)
+}
+
+fn declares_id(contents: &str) -> bool {
+    split_front_matter(contents)
+        .and_then(|raw| raw.format.decode(raw.header).ok())
+        .and_then(|header| header.get("id").cloned())
+        .is_some()
+}
+
+/// Inserts the `id` declaration as the first entry of the header, in the
+/// header's own format.
+fn insert_id(contents: &str, id: Uuid) -> String {
+    let Some(raw) = split_front_matter(contents) else {
+        return contents.to_string();
+    };
+    let mut at = raw.header_offset;
+    let mut line = raw.format.id_line(&id.to_string());
+    if matches!(
+        raw.format,
+        FrontMatterFormat::Json | FrontMatterFormat::JsonObject
+    ) {
+        // JSON ids go right after the opening brace of the object.
+        if let Some(brace) = raw.header.find('{') {
+            at += brace + 1;
+            let empty = raw.header[brace + 1..].trim_start().starts_with('}');
+            line = if empty {
+                format!("\n{}\n", line.trim_end().trim_end_matches(','))
+            } else {
+                format!("\n{}", line.trim_end_matches('\n'))
+            };
+        }
+    }
+    format!("{}{}{}", &contents[..at], line, &contents[at..])
+}
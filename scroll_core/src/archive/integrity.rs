@@ -0,0 +1,376 @@
+//! Archive-wide integrity pass over resolved scroll links: dangling targets,
+//! ancestry cycles, one-sided reflections, orphaned scrolls and duplicate
+//! titles. `initialize_scroll_core` runs it at the level set by
+//! `SCROLL_CORE_INTEGRITY`.
+// src/archive/integrity.rs
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use log::warn;
+use uuid::Uuid;
+
+use crate::scroll::{Scroll, ScrollLinkType};
+
+/// Environment variable read by [`IntegrityLevel::from_env`].
+pub const INTEGRITY_ENV: &str = "SCROLL_CORE_INTEGRITY";
+
+/// How the integrity pass is applied when the archive loads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegrityLevel {
+    /// Skip the pass.
+    Off,
+    /// Log every issue and keep going.
+    #[default]
+    Warn,
+    /// Log every issue and refuse to start on errors.
+    Strict,
+}
+
+impl IntegrityLevel {
+    /// Reads [`INTEGRITY_ENV`], defaulting to `Warn` when unset.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var(INTEGRITY_ENV) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+impl FromStr for IntegrityLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!(
+                "invalid {INTEGRITY_ENV} value '{other}' (expected off, warn or strict)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityIssue {
+    /// A link whose target is not in the archive.
+    DanglingLink {
+        scroll_id: Uuid,
+        target: Uuid,
+        link_type: ScrollLinkType,
+    },
+    /// Scrolls that are their own ancestors through `Ancestor`/`Derivative`
+    /// links, listed from child to parent. A scroll that is its own ancestor
+    /// or derivative is a cycle of one.
+    AncestryCycle { scrolls: Vec<Uuid> },
+    /// A `Reflection`, `Binding` or `Echo` link from a scroll to itself.
+    SelfLink {
+        scroll_id: Uuid,
+        link_type: ScrollLinkType,
+    },
+    /// `scroll_id` reflects `target` but `target` does not reflect it back.
+    AsymmetricReflection { scroll_id: Uuid, target: Uuid },
+    /// A scroll with no links in either direction.
+    Orphan { scroll_id: Uuid },
+    /// Titles shared by several scrolls, compared case-insensitively.
+    DuplicateTitle { title: String, scrolls: Vec<Uuid> },
+}
+
+impl IntegrityIssue {
+    /// Dangling links and cycles break traversal; the rest are warnings.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            IntegrityIssue::DanglingLink { .. } | IntegrityIssue::AncestryCycle { .. }
+        )
+    }
+}
+
+/// Outcome of [`check_archive_integrity`].
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+    pub scrolls_checked: usize,
+    /// Display names used when rendering issues.
+    names: HashMap<Uuid, String>,
+}
+
+impl IntegrityReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(IntegrityIssue::is_error)
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.is_error()).count()
+    }
+
+    /// One line describing the issue, naming scrolls by title.
+    pub fn describe(&self, issue: &IntegrityIssue) -> String {
+        match issue {
+            IntegrityIssue::DanglingLink {
+                scroll_id,
+                target,
+                link_type,
+            } => format!(
+                "{} has a {link_type:?} link to missing scroll {target}",
+                self.name(scroll_id)
+            ),
+            IntegrityIssue::AncestryCycle { scrolls } => {
+                let mut chain: Vec<String> = scrolls.iter().map(|id| self.name(id)).collect();
+                chain.extend(scrolls.first().map(|id| self.name(id)));
+                format!("ancestry cycle: {}", chain.join(" -> "))
+            }
+            IntegrityIssue::SelfLink {
+                scroll_id,
+                link_type,
+            } => format!(
+                "{} has a {link_type:?} link to itself",
+                self.name(scroll_id)
+            ),
+            IntegrityIssue::AsymmetricReflection { scroll_id, target } => format!(
+                "{} reflects {} but is not reflected back",
+                self.name(scroll_id),
+                self.name(target)
+            ),
+            IntegrityIssue::Orphan { scroll_id } => {
+                format!(
+                    "{} is not linked to or from any scroll",
+                    self.name(scroll_id)
+                )
+            }
+            IntegrityIssue::DuplicateTitle { title, scrolls } => {
+                format!("title '{title}' is used by {} scrolls", scrolls.len())
+            }
+        }
+    }
+
+    /// `error:`/`warning:` lines followed by a summary.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for issue in &self.issues {
+            let level = if issue.is_error() { "error" } else { "warning" };
+            let _ = writeln!(out, "{level}: {}", self.describe(issue));
+        }
+        let _ = writeln!(
+            out,
+            "{} scroll(s) checked: {} error(s), {} warning(s)",
+            self.scrolls_checked,
+            self.error_count(),
+            self.issues.len() - self.error_count()
+        );
+        out
+    }
+
+    /// Logs every issue unless `level` is `Off`; under `Strict`, errors fail.
+    pub fn enforce(&self, level: IntegrityLevel) -> Result<(), String> {
+        if level == IntegrityLevel::Off {
+            return Ok(());
+        }
+        for issue in &self.issues {
+            warn!("Archive integrity: {}", self.describe(issue));
+        }
+        if level == IntegrityLevel::Strict && self.has_errors() {
+            return Err(format!(
+                "archive integrity check failed with {} error(s)",
+                self.error_count()
+            ));
+        }
+        Ok(())
+    }
+
+    fn name(&self, id: &Uuid) -> String {
+        self.names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+/// Checks links between loaded scrolls. Run it after
+/// [`resolve_linked_scrolls`](crate::archive::link_resolver::resolve_linked_scrolls)
+/// so body references are included. Orphans are only reported in archives
+/// with more than one scroll.
+pub fn check_archive_integrity(scrolls: &[Scroll]) -> IntegrityReport {
+    let ids: HashSet<Uuid> = scrolls.iter().map(|s| s.id).collect();
+    let mut issues = Vec::new();
+
+    for scroll in scrolls {
+        for link in &scroll.linked_scrolls {
+            if link.target == scroll.id {
+                issues.push(match link.link_type {
+                    ScrollLinkType::Ancestor | ScrollLinkType::Derivative => {
+                        IntegrityIssue::AncestryCycle {
+                            scrolls: vec![scroll.id],
+                        }
+                    }
+                    _ => IntegrityIssue::SelfLink {
+                        scroll_id: scroll.id,
+                        link_type: link.link_type.clone(),
+                    },
+                });
+            } else if !ids.contains(&link.target) {
+                issues.push(IntegrityIssue::DanglingLink {
+                    scroll_id: scroll.id,
+                    target: link.target,
+                    link_type: link.link_type.clone(),
+                });
+            }
+        }
+    }
+    issues.extend(
+        ancestry_cycles(scrolls, &ids)
+            .into_iter()
+            .map(|scrolls| IntegrityIssue::AncestryCycle { scrolls }),
+    );
+    issues.extend(asymmetric_reflections(scrolls, &ids));
+    if scrolls.len() > 1 {
+        issues.extend(orphans(scrolls, &ids));
+    }
+    issues.extend(duplicate_titles(scrolls));
+
+    IntegrityReport {
+        issues,
+        scrolls_checked: scrolls.len(),
+        names: scrolls
+            .iter()
+            .map(|s| {
+                let name = if s.title.trim().is_empty() {
+                    s.id.to_string()
+                } else {
+                    s.title.clone()
+                };
+                (s.id, name)
+            })
+            .collect(),
+    }
+}
+
+/// Child-to-parent edges: `A -Ancestor-> B` makes B a parent of A, and
+/// `A -Derivative-> B` makes A a parent of B.
+fn parent_edges(scrolls: &[Scroll], ids: &HashSet<Uuid>) -> BTreeMap<Uuid, Vec<Uuid>> {
+    let mut parents: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    for scroll in scrolls {
+        for link in &scroll.linked_scrolls {
+            if link.target == scroll.id || !ids.contains(&link.target) {
+                continue;
+            }
+            let (child, parent) = match link.link_type {
+                ScrollLinkType::Ancestor => (scroll.id, link.target),
+                ScrollLinkType::Derivative => (link.target, scroll.id),
+                _ => continue,
+            };
+            let entry = parents.entry(child).or_default();
+            if !entry.contains(&parent) {
+                entry.push(parent);
+            }
+        }
+    }
+    parents
+}
+
+/// Each distinct cycle once, rotated to start at its smallest id.
+fn ancestry_cycles(scrolls: &[Scroll], ids: &HashSet<Uuid>) -> Vec<Vec<Uuid>> {
+    let parents = parent_edges(scrolls, ids);
+    let mut finished: HashSet<Uuid> = HashSet::new();
+    let mut seen_cycles: HashSet<Vec<Uuid>> = HashSet::new();
+    let mut cycles = Vec::new();
+
+    for start in scrolls.iter().map(|s| s.id) {
+        if finished.contains(&start) {
+            continue;
+        }
+        // Iterative DFS: (node, index of the next parent to visit).
+        let mut stack: Vec<(Uuid, usize)> = vec![(start, 0)];
+        let mut on_path: Vec<Uuid> = vec![start];
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            let Some(&parent) = parents.get(&node).and_then(|p| p.get(*next)) else {
+                finished.insert(node);
+                stack.pop();
+                on_path.pop();
+                continue;
+            };
+            *next += 1;
+            if let Some(at) = on_path.iter().position(|id| *id == parent) {
+                let mut cycle = on_path[at..].to_vec();
+                let min = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+                cycle.rotate_left(min);
+                if seen_cycles.insert(cycle.clone()) {
+                    cycles.push(cycle);
+                }
+            } else if !finished.contains(&parent) {
+                stack.push((parent, 0));
+                on_path.push(parent);
+            }
+        }
+    }
+    cycles
+}
+
+fn asymmetric_reflections(scrolls: &[Scroll], ids: &HashSet<Uuid>) -> Vec<IntegrityIssue> {
+    let reflections: HashSet<(Uuid, Uuid)> = scrolls
+        .iter()
+        .flat_map(|s| {
+            s.linked_scrolls
+                .iter()
+                .filter(|l| l.link_type == ScrollLinkType::Reflection)
+                .map(move |l| (s.id, l.target))
+        })
+        .collect();
+    let mut issues = Vec::new();
+    for scroll in scrolls {
+        for link in &scroll.linked_scrolls {
+            let pair = (scroll.id, link.target);
+            if link.link_type == ScrollLinkType::Reflection
+                && link.target != scroll.id
+                && ids.contains(&link.target)
+                && !reflections.contains(&(pair.1, pair.0))
+            {
+                issues.push(IntegrityIssue::AsymmetricReflection {
+                    scroll_id: pair.0,
+                    target: pair.1,
+                });
+            }
+        }
+    }
+    issues
+}
+
+fn orphans(scrolls: &[Scroll], ids: &HashSet<Uuid>) -> Vec<IntegrityIssue> {
+    let mut linked: HashSet<Uuid> = HashSet::new();
+    for scroll in scrolls {
+        for link in &scroll.linked_scrolls {
+            if link.target != scroll.id && ids.contains(&link.target) {
+                linked.insert(scroll.id);
+                linked.insert(link.target);
+            }
+        }
+    }
+    scrolls
+        .iter()
+        .filter(|s| !linked.contains(&s.id))
+        .map(|s| IntegrityIssue::Orphan { scroll_id: s.id })
+        .collect()
+}
+
+fn duplicate_titles(scrolls: &[Scroll]) -> Vec<IntegrityIssue> {
+    let mut by_title: BTreeMap<String, (String, Vec<Uuid>)> = BTreeMap::new();
+    for scroll in scrolls {
+        let title = scroll.title.trim();
+        if title.is_empty() {
+            continue;
+        }
+        by_title
+            .entry(title.to_lowercase())
+            .or_insert_with(|| (title.to_string(), Vec::new()))
+            .1
+            .push(scroll.id);
+    }
+    by_title
+        .into_values()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(title, scrolls)| IntegrityIssue::DuplicateTitle { title, scrolls })
+        .collect()
+}
@@ -0,0 +1,268 @@
+//! Resolves references between scrolls once the whole archive is loaded.
+//! References come from the header `linked_scrolls` list and from bodies:
+//! `[[Wiki_Links]]`, markdown links to `.md` files and `file_anchor:` lines.
+// src/archive/link_resolver.rs
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::parser::body::slugify;
+use crate::scroll::{Scroll, ScrollLink, ScrollLinkType};
+
+/// Where a reference was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferenceSource {
+    FrontMatter,
+    WikiLink,
+    MarkdownLink,
+    FileAnchor,
+}
+
+/// A reference to another scroll, before resolution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollReference {
+    /// The reference as written, without surrounding brackets.
+    pub raw: String,
+    /// File name, stem or title; empty for anchor-only references.
+    pub target: String,
+    /// Heading anchor after `#`, if any.
+    pub anchor: Option<String>,
+    /// Link type named by a `type:` prefix such as `[[ancestor:Canon_Roots]]`.
+    pub link_type: Option<ScrollLinkType>,
+    pub source: ReferenceSource,
+    /// 1-based body line; `None` for header references.
+    pub line: Option<usize>,
+}
+
+/// A reference that did not match any loaded scroll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedLink {
+    pub scroll_id: Uuid,
+    pub reference: String,
+    pub source: ReferenceSource,
+    pub line: Option<usize>,
+}
+
+/// Lookup tables over a loaded archive.
+pub struct LinkResolver {
+    by_name: HashMap<String, Uuid>,
+    by_anchor: HashMap<String, Uuid>,
+}
+
+impl LinkResolver {
+    /// Indexes scrolls by file name, file stem, title and heading anchors,
+    /// all case-insensitive. The first scroll to claim a key keeps it.
+    pub fn new(scrolls: &[Scroll]) -> Self {
+        let mut by_name: HashMap<String, Uuid> = HashMap::new();
+        let mut by_anchor: HashMap<String, Uuid> = HashMap::new();
+        for scroll in scrolls {
+            if let Some(path) = &scroll.origin.source_path {
+                for key in [path.file_name(), path.file_stem()].into_iter().flatten() {
+                    by_name
+                        .entry(key.to_string_lossy().to_lowercase())
+                        .or_insert(scroll.id);
+                }
+            }
+            by_name
+                .entry(scroll.title.trim().to_lowercase())
+                .or_insert(scroll.id);
+            for section in scroll.body().all_sections() {
+                by_anchor
+                    .entry(slugify(&section.heading.text))
+                    .or_insert(scroll.id);
+            }
+        }
+        Self { by_name, by_anchor }
+    }
+
+    pub fn resolve(&self, reference: &ScrollReference) -> Option<Uuid> {
+        if !reference.target.is_empty() {
+            let name = reference.target.trim().to_lowercase();
+            let file_name = Path::new(&name)
+                .file_name()
+                .map(|f| f.to_string_lossy().into_owned());
+            return self
+                .by_name
+                .get(&name)
+                .or_else(|| file_name.and_then(|f| self.by_name.get(&f)))
+                .copied();
+        }
+        let anchor = reference.anchor.as_deref()?;
+        self.by_anchor
+            .get(&slugify(anchor))
+            .or_else(|| self.by_name.get(&anchor.trim().to_lowercase()))
+            .copied()
+    }
+}
+
+/// Collects every reference in the scroll's header and body, in order.
+pub fn extract_references(scroll: &Scroll) -> Vec<ScrollReference> {
+    let mut references: Vec<ScrollReference> = scroll
+        .yaml_metadata
+        .linked_scrolls
+        .iter()
+        .map(|raw| reference(raw, ReferenceSource::FrontMatter, None))
+        .collect();
+
+    let mut fence: Option<&str> = None;
+    for (index, line) in scroll.markdown_body.lines().enumerate() {
+        let line_no = Some(index + 1);
+        let trimmed = line.trim_start();
+        if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+            fence = match fence {
+                Some(open) if open == marker => None,
+                Some(open) => Some(open),
+                None => Some(marker),
+            };
+            continue;
+        }
+
+        // `file_anchor:` usually sits in YAML blocks, so code is scanned too.
+        let entry = trimmed.strip_prefix("- ").unwrap_or(trimmed).trim_start();
+        if let Some(anchor) = entry.strip_prefix("file_anchor:") {
+            let anchor = anchor.trim().trim_matches(['"', '\'']);
+            let anchor = anchor.trim_start_matches('#').trim();
+            if !anchor.is_empty() {
+                references.push(ScrollReference {
+                    raw: anchor.to_string(),
+                    target: String::new(),
+                    anchor: Some(anchor.to_string()),
+                    link_type: None,
+                    source: ReferenceSource::FileAnchor,
+                    line: line_no,
+                });
+            }
+        }
+        if fence.is_some() {
+            continue;
+        }
+
+        references.extend(
+            wiki_links(line)
+                .into_iter()
+                .map(|raw| reference(raw, ReferenceSource::WikiLink, line_no)),
+        );
+        for (text, href) in markdown_links(line) {
+            let mut found = reference(href, ReferenceSource::MarkdownLink, line_no);
+            found.link_type = split_link_type(text).0;
+            references.push(found);
+        }
+    }
+    references
+}
+
+/// Resolves each scroll's references into `ScrollLink`s, inferring the link
+/// type from a prefix and defaulting to `Binding`. Resolved links record
+/// their `source`, so writers keep the references rather than the links,
+/// and are replaced when the archive is resolved again. Returns the
+/// references that matched nothing; references to the scroll itself are
+/// ignored.
+///
+/// Must run after the whole archive is loaded, since references usually point
+/// at files that are parsed later.
+pub fn resolve_linked_scrolls(scrolls: &mut [Scroll]) -> Vec<UnresolvedLink> {
+    let resolver = LinkResolver::new(scrolls);
+    let mut unresolved = Vec::new();
+    for scroll in scrolls.iter_mut() {
+        scroll.linked_scrolls.retain(|link| link.source.is_none());
+        for reference in extract_references(scroll) {
+            match resolver.resolve(&reference) {
+                Some(target) if target != scroll.id => {
+                    if !scroll.is_linked_to(&target) {
+                        scroll.linked_scrolls.push(ScrollLink {
+                            target,
+                            link_type: reference.link_type.unwrap_or(ScrollLinkType::Binding),
+                            source: Some(reference.source),
+                        });
+                    }
+                }
+                Some(_) => {}
+                None => unresolved.push(UnresolvedLink {
+                    scroll_id: scroll.id,
+                    reference: reference.raw,
+                    source: reference.source,
+                    line: reference.line,
+                }),
+            }
+        }
+    }
+    unresolved
+}
+
+/// Parses `[type:]target[#anchor][|alias]`.
+fn reference(raw: &str, source: ReferenceSource, line: Option<usize>) -> ScrollReference {
+    let raw = raw.trim();
+    let (link_type, rest) = split_link_type(raw);
+    let rest = rest.split('|').next().unwrap_or_default();
+    let (target, anchor) = match rest.split_once('#') {
+        Some((target, anchor)) => (target.trim(), Some(anchor.trim().to_string())),
+        None => (rest.trim(), None),
+    };
+    ScrollReference {
+        raw: raw.to_string(),
+        target: target.to_string(),
+        anchor,
+        link_type,
+        source,
+        line,
+    }
+}
+
+/// Splits a leading `ancestor:`-style prefix off `text`. Anything before a
+/// colon that is not a link type stays part of the text, so titles such as
+/// `Safeguards of the Link: Flame Revelation` are left alone.
+fn split_link_type(text: &str) -> (Option<ScrollLinkType>, &str) {
+    let Some((prefix, rest)) = text.split_once(':') else {
+        return (None, text);
+    };
+    let link_type = match prefix.trim().to_ascii_lowercase().as_str() {
+        "ancestor" => ScrollLinkType::Ancestor,
+        "reflection" => ScrollLinkType::Reflection,
+        "derivative" => ScrollLinkType::Derivative,
+        "binding" => ScrollLinkType::Binding,
+        "echo" => ScrollLinkType::Echo,
+        _ => return (None, text),
+    };
+    (Some(link_type), rest.trim())
+}
+
+fn wiki_links(line: &str) -> Vec<&str> {
+    let mut links = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            break;
+        };
+        if !after[..end].trim().is_empty() {
+            links.push(&after[..end]);
+        }
+        rest = &after[end + 2..];
+    }
+    links
+}
+
+/// `[text](target.md)` links to local markdown files, as `(text, target)`.
+fn markdown_links(line: &str) -> Vec<(&str, &str)> {
+    let mut links = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        let text = rest[..start]
+            .rfind('[')
+            .map_or("", |open| &rest[open + 1..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find(')') else {
+            break;
+        };
+        let href = after[..end].trim();
+        let path = href.split('#').next().unwrap_or_default().to_lowercase();
+        if !href.contains("://") && (path.ends_with(".md") || path.ends_with(".markdown")) {
+            links.push((text, href));
+        }
+        rest = &after[end + 1..];
+    }
+    links
+}
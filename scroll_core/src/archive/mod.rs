@@ -14,8 +14,18 @@
 
 pub mod archive_loader;
 pub mod archive_memory;
+pub mod embedding;
 pub mod error;
+pub mod full_text;
+pub mod hybrid_archive;
+pub mod identity;
 pub mod initialize;
+pub mod integrity;
+pub mod link_resolver;
 pub mod mythic_heat;
+pub mod schema_migration;
 pub mod scroll_access_log;
+pub mod scroll_ignore;
 pub mod semantic_index;
+pub mod sqlite_archive;
+pub mod watcher;
@@ -42,7 +42,7 @@ impl MythicHeat {
         let access_factor = (self.access_count as f32).sqrt();
         let recency_factor = {
             let elapsed = Utc::now().signed_duration_since(self.last_accessed);
-            1.0 / ((elapsed.num_seconds().max(1)) as f32).log2() // decay with time
+            1.0 / ((elapsed.num_seconds().max(1) + 1) as f32).log2() // decay with time
         };
         let cost_penalty = self.cost_weight;
 
@@ -66,7 +66,7 @@ impl MythicHeat {
         let access = (self.access_count as f32).sqrt();
         let recency = {
             let elapsed = Utc::now().signed_duration_since(self.last_accessed);
-            1.0 / ((elapsed.num_seconds().max(1)) as f32).log2()
+            1.0 / ((elapsed.num_seconds().max(1) + 1) as f32).log2()
         };
         let cost = self.cost_weight;
         (emotion, access, recency, cost)
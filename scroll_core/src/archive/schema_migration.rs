@@ -0,0 +1,121 @@
+//! Rewrites archive scrolls whose headers predate the current schema,
+//! running them through the parser's step-by-step migrations.
+// src/archive/schema_migration.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_yaml::{Mapping, Value};
+
+use crate::archive::archive_loader::{scan_archive, LoadOptions};
+use crate::archive::error::ArchiveError;
+use crate::parser::migrate;
+use crate::parser::{self, split_front_matter, ParseError, ParseErrorKind, ParseMode};
+use crate::scroll_writer::rewrite_header;
+
+/// Outcome of [`migrate_archive`].
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// Files below the current schema, with the version they started from.
+    pub upgraded: Vec<(PathBuf, u32)>,
+    /// Files already at the current schema.
+    pub current: Vec<PathBuf>,
+    pub failed: Vec<ParseError>,
+}
+
+/// Upgrades every archive scroll to `CURRENT_SCHEMA_VERSION`. Files are only
+/// rewritten when `write` is set; otherwise the report lists what would change.
+pub fn migrate_archive(archive_root: &Path, write: bool) -> Result<MigrationReport, ArchiveError> {
+    let mut report = MigrationReport::default();
+
+    let (paths, scan) = scan_archive(archive_root, &LoadOptions::default())
+        .map_err(|e| ArchiveError::Io(std::io::Error::other(e)))?;
+    report
+        .failed
+        .extend(scan.failed.into_iter().map(|failure| failure.error));
+
+    for path in paths {
+        // Parsing runs the same migrations and surfaces any header errors.
+        if let Err(e) = parser::parse_archive_scroll(archive_root, &path, ParseMode::Lenient) {
+            report.failed.push(e);
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let mut header =
+            match split_front_matter(&contents).map(|raw| raw.format.decode(raw.header)) {
+                Some(Ok(Value::Mapping(header))) => header,
+                Some(Ok(Value::Null)) => Mapping::new(),
+                _ => {
+                    report
+                        .failed
+                        .push(failure(&path, "scroll header is not a mapping".into()));
+                    continue;
+                }
+            };
+        let from = migrate::schema_version(&header).unwrap_or(0);
+        if from == migrate::CURRENT_SCHEMA_VERSION {
+            report.current.push(path);
+            continue;
+        }
+
+        match upgrade_header(&path, &contents, &mut header) {
+            Ok(upgraded) => {
+                if write {
+                    fs::write(&path, upgraded)?;
+                }
+                report.upgraded.push((path, from));
+            }
+            Err(e) => report.failed.push(e),
+        }
+    }
+
+    Ok(report)
+}
+
+/// `contents` with its header replaced by `header` upgraded to the current
+/// schema.
+fn upgrade_header(path: &Path, contents: &str, header: &mut Mapping) -> Result<String, ParseError> {
+    migrate::upgrade(header).map_err(|e| failure(path, format!("{}: {}", e.field, e.message)))?;
+    rewrite_header(contents, header)
+        .ok_or_else(|| failure(path, "the upgraded header could not be written back".into()))
+}
+
+fn failure(path: &Path, message: String) -> ParseError {
+    ParseError::new(ParseErrorKind::Validation(message)).with_path(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = "---\ntitle: Old\ntype: Canon\nemotion: calm // deep\n---\nBody\n";
+
+    fn header(yaml: &str) -> Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn a_failed_upgrade_is_reported_with_its_path() {
+        let path = Path::new("scrolls/newer.md");
+        let mut newer = header("schema_version: 99\ntitle: Newer\n");
+        let err = upgrade_header(path, LEGACY, &mut newer).unwrap_err();
+        assert_eq!(err.path.as_deref(), Some(path));
+        assert!(matches!(err.kind, ParseErrorKind::Validation(ref m) if m.contains("newer")));
+    }
+
+    #[test]
+    fn a_header_that_cannot_be_written_back_is_reported_with_its_path() {
+        let path = Path::new("scrolls/bare.md");
+        let mut legacy = header("title: Old\ntype: Canon\n");
+        let err = upgrade_header(path, "No front matter here.\n", &mut legacy).unwrap_err();
+        assert_eq!(err.path.as_deref(), Some(path));
+        assert!(
+            matches!(err.kind, ParseErrorKind::Validation(ref m) if m.contains("written back"))
+        );
+
+        let upgraded =
+            upgrade_header(path, LEGACY, &mut header("title: Old\ntype: Canon\n")).unwrap();
+        assert!(upgraded.contains("scroll_type: Canon"));
+    }
+}
@@ -0,0 +1,148 @@
+//! `.scrollignore` rules used by the archive loader to leave paths out.
+//! The syntax follows `.gitignore`: one glob per line, `#` comments, `!` to
+//! re-include, a trailing `/` for directories only and a leading `/` (or any
+//! inner `/`) to anchor the pattern to the file's directory.
+// src/archive/scroll_ignore.rs
+
+use std::fs;
+use std::path::Path;
+
+/// Name of the ignore file looked up in every archive directory.
+pub const IGNORE_FILE: &str = ".scrollignore";
+
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    pattern: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+/// The rules of one ignore file, matched against paths relative to the
+/// directory holding it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScrollIgnore {
+    rules: Vec<Rule>,
+}
+
+impl ScrollIgnore {
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+                let anchored = line.contains('/');
+                Rule {
+                    pattern: line.trim_start_matches('/').chars().collect(),
+                    negated,
+                    dir_only,
+                    anchored,
+                }
+            })
+            .filter(|rule| !rule.pattern.is_empty())
+            .collect();
+        Self { rules }
+    }
+
+    /// Reads an ignore file; `None` when it does not exist or is unreadable.
+    pub fn from_file(path: &Path) -> Option<Self> {
+        fs::read_to_string(path).ok().map(|text| Self::parse(&text))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `Some(true)` if the last matching rule ignores `relative`,
+    /// `Some(false)` if it re-includes it and `None` if no rule matches.
+    pub fn matched(&self, relative: &Path, is_dir: bool) -> Option<bool> {
+        let path: Vec<char> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+            .chars()
+            .collect();
+        let name: Vec<char> = relative
+            .file_name()
+            .map(|n| n.to_string_lossy().chars().collect())
+            .unwrap_or_default();
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                (is_dir || !rule.dir_only)
+                    && glob_match(&rule.pattern, if rule.anchored { &path } else { &name })
+            })
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// Matches `*` and `?` within one path segment, `**` across segments and
+/// `[...]` character classes, with `\` escaping the next character.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            match rest.strip_prefix(&['/']) {
+                Some(rest) => (0..=text.len())
+                    .filter(|&i| i == 0 || text[i - 1] == '/')
+                    .any(|i| glob_match(rest, &text[i..])),
+                None => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+            }
+        }
+        Some('*') => {
+            let segment = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=segment).any(|i| glob_match(&pattern[1..], &text[i..]))
+        }
+        Some('?') => {
+            matches!(text.first(), Some(c) if *c != '/') && glob_match(&pattern[1..], &text[1..])
+        }
+        Some('[') => match (class_match(&pattern[1..], text.first()), text.first()) {
+            (Some((true, len)), Some(_)) => glob_match(&pattern[len + 1..], &text[1..]),
+            (Some(_), _) => false,
+            // An unclosed bracket is a literal.
+            (None, Some('[')) => glob_match(&pattern[1..], &text[1..]),
+            (None, _) => false,
+        },
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Matches `c` against the class starting after `[`. Returns whether it
+/// matched and how many pattern characters the class used, including `]`.
+fn class_match(class: &[char], c: Option<&char>) -> Option<(bool, usize)> {
+    let negated = matches!(class.first(), Some('!') | Some('^'));
+    let start = usize::from(negated);
+    let end = start + 1 + class.get(start + 1..)?.iter().position(|&ch| ch == ']')?;
+    let set = &class[start..end];
+    let c = match c {
+        Some(c) if *c != '/' => *c,
+        _ => return Some((false, end + 1)),
+    };
+    let mut hit = false;
+    let mut i = 0;
+    while i < set.len() {
+        if i + 2 < set.len() && set[i + 1] == '-' {
+            hit |= set[i] <= c && c <= set[i + 2];
+            i += 3;
+        } else {
+            hit |= set[i] == c;
+            i += 1;
+        }
+    }
+    Some((hit != negated, end + 1))
+}
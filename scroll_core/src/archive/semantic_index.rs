@@ -3,8 +3,13 @@
 //! See [Loreweaver](../../AGENTS.md#loreweaver) for narrative use cases.
 // src/archive/semantic_index.rs
 
-use log::info;
-use std::collections::HashSet;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[cfg(feature = "metrics")]
@@ -13,32 +18,151 @@ use metrics::histogram;
 use crate::archive::error::ArchiveError;
 use crate::scroll::Scroll;
 
-pub trait Embedder {
-    fn embed(&self, text: &str) -> Result<HashSet<String>, ArchiveError>;
+/// Turns text into a dense vector. Vectors from one embedder are compared
+/// with cosine similarity, so their scale does not matter.
+pub trait Embedder: Send + Sync {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError>;
+
+    /// Names the embedder and its settings. A saved index is only reused by
+    /// an embedder with the same identity.
+    fn identity(&self) -> String;
+
+    /// State learned from the archive, saved with the index so that later
+    /// runs embed with the same weights. `None` if the embedder learns nothing.
+    fn model(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// This embedder with a saved [`model`](Embedder::model) in place of its
+    /// own, or `None` if the saved model should be refitted, which rebuilds
+    /// the index.
+    fn restore(&self, _model: serde_json::Value) -> Option<Arc<dyn Embedder>> {
+        None
+    }
+}
+
+impl<E: Embedder + ?Sized> Embedder for Box<E> {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError> {
+        (**self).embed(text)
+    }
+
+    fn identity(&self) -> String {
+        (**self).identity()
+    }
+
+    fn model(&self) -> Option<serde_json::Value> {
+        (**self).model()
+    }
+
+    fn restore(&self, model: serde_json::Value) -> Option<Arc<dyn Embedder>> {
+        (**self).restore(model)
+    }
 }
 
+impl<E: Embedder + ?Sized> Embedder for Arc<E> {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError> {
+        (**self).embed(text)
+    }
+
+    fn identity(&self) -> String {
+        (**self).identity()
+    }
+
+    fn model(&self) -> Option<serde_json::Value> {
+        (**self).model()
+    }
+
+    fn restore(&self, model: serde_json::Value) -> Option<Arc<dyn Embedder>> {
+        (**self).restore(model)
+    }
+}
+
+/// Dimensions of [`TokenEmbedder`] vectors.
+const TOKEN_DIMS: usize = 1024;
+
+/// Marks each lowercased word in one of a fixed set of buckets, so cosine
+/// similarity counts shared words. Simple and deterministic, for tests.
 pub struct TokenEmbedder;
 
 impl Embedder for TokenEmbedder {
-    fn embed(&self, text: &str) -> Result<HashSet<String>, ArchiveError> {
-        Ok(tokenize(text))
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError> {
+        let mut vector = vec![0.0; TOKEN_DIMS];
+        for token in tokenize(text) {
+            vector[(fnv1a(token.as_bytes()) % TOKEN_DIMS as u64) as usize] = 1.0;
+        }
+        Ok(vector)
+    }
+
+    fn identity(&self) -> String {
+        format!("token:{TOKEN_DIMS}")
     }
 }
 
 pub struct MockEmbedder;
 
 impl Embedder for MockEmbedder {
-    fn embed(&self, _text: &str) -> Result<HashSet<String>, ArchiveError> {
-        Ok(HashSet::new())
+    fn embed(&self, _text: &str) -> Result<Vec<f32>, ArchiveError> {
+        Ok(Vec::new())
+    }
+
+    fn identity(&self) -> String {
+        "mock".into()
     }
 }
 
+/// Version of the file written by [`SemanticIndex::save`].
+pub const INDEX_FILE_VERSION: u32 = 1;
+
+/// Scroll vectors together with the embedder that produced them, which is
+/// also used to embed queries.
 pub struct SemanticIndex {
-    pub vectors: Vec<(Uuid, HashSet<String>)>,
+    embedder: Arc<dyn Embedder>,
+    pub vectors: Vec<(Uuid, Vec<f32>)>,
+    /// Hash of the text each vector was embedded from.
+    hashes: HashMap<Uuid, u64>,
+}
+
+/// What [`SemanticIndex::load_or_build`] did with the saved index.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexSync {
+    /// Vectors taken from the file unchanged.
+    pub reused: usize,
+    /// Scrolls embedded because they were new or their text changed.
+    pub embedded: usize,
+    /// Saved vectors of scrolls no longer in the archive.
+    pub dropped: usize,
+    /// Why the saved index was discarded, if it was.
+    pub rebuilt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    embedder: String,
+    /// The embedder's [`Embedder::model`] when the vectors were embedded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model: Option<serde_json::Value>,
+    checksum: u64,
+    entries: Vec<IndexEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexEntry {
+    id: Uuid,
+    hash: u64,
+    vector: Vec<f32>,
 }
 
 impl SemanticIndex {
-    pub fn build(scrolls: &[Scroll], embedder: &dyn Embedder) -> Result<Self, ArchiveError> {
+    pub fn new(embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            embedder,
+            vectors: Vec::new(),
+            hashes: HashMap::new(),
+        }
+    }
+
+    pub fn build(scrolls: &[Scroll], embedder: Arc<dyn Embedder>) -> Result<Self, ArchiveError> {
         if scrolls.is_empty() {
             return Err(ArchiveError::EmptyScrollSet);
         }
@@ -48,62 +172,262 @@ impl SemanticIndex {
         #[cfg(feature = "metrics")]
         let build_timer = std::time::Instant::now();
 
-        let vectors = scrolls
-            .iter()
-            .map(|s| {
-                #[cfg(feature = "metrics")]
-                let embed_timer = std::time::Instant::now();
-
-                let first_lines = s
-                    .markdown_body
-                    .lines()
-                    .take(3)
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                let text = format!(
-                    "{} {} {}",
-                    s.title,
-                    s.yaml_metadata.tags.join(" "),
-                    first_lines
-                );
-                let vec = embedder.embed(&text)?;
-
-                #[cfg(feature = "metrics")]
-                histogram!("scroll_embed_time_seconds").record(embed_timer.elapsed().as_secs_f64());
-
-                Ok((s.id, vec))
-            })
-            .collect::<Result<Vec<_>, ArchiveError>>()?;
+        let mut index = Self::new(embedder);
+        for scroll in scrolls {
+            index.embed(scroll)?;
+        }
 
         #[cfg(feature = "metrics")]
-        {
-            histogram!("vector_index_update_time_seconds")
-                .record(build_timer.elapsed().as_secs_f64());
-            let mut bytes: usize = std::mem::size_of_val(&vectors);
-            for (_, tokens) in &vectors {
-                bytes += std::mem::size_of_val(tokens);
-                for t in tokens {
-                    bytes += t.len();
+        index.record_build(build_timer);
+
+        info!("Vector generation complete");
+        Ok(index)
+    }
+
+    /// Reads the index saved at `path`, keeps the vectors of scrolls whose
+    /// text is unchanged, embeds the rest and saves the result back. A file
+    /// that is missing, corrupt or written by another embedder is replaced
+    /// by a full build. A saved [`Embedder::model`] is restored into
+    /// `embedder` unless the embedder declines it.
+    pub fn load_or_build(
+        path: &Path,
+        scrolls: &[Scroll],
+        mut embedder: Arc<dyn Embedder>,
+    ) -> Result<(Self, IndexSync), ArchiveError> {
+        if scrolls.is_empty() {
+            return Err(ArchiveError::EmptyScrollSet);
+        }
+
+        #[cfg(feature = "metrics")]
+        let build_timer = std::time::Instant::now();
+
+        let mut sync = IndexSync::default();
+        let mut saved: HashMap<Uuid, IndexEntry> = match read_index_file(path) {
+            Ok(Some(file)) if file.embedder == embedder.identity() => {
+                match (file.model, embedder.model()) {
+                    (None, None) => file.entries.into_iter().map(|e| (e.id, e)).collect(),
+                    (Some(model), Some(_)) => match embedder.restore(model) {
+                        Some(restored) => {
+                            embedder = restored;
+                            file.entries.into_iter().map(|e| (e.id, e)).collect()
+                        }
+                        None => {
+                            sync.rebuilt = Some("embedder was refitted".into());
+                            HashMap::new()
+                        }
+                    },
+                    _ => {
+                        sync.rebuilt = Some("index was embedded with another model".into());
+                        HashMap::new()
+                    }
+                }
+            }
+            Ok(Some(file)) => {
+                sync.rebuilt = Some(format!("index was embedded with {}", file.embedder));
+                HashMap::new()
+            }
+            Ok(None) => HashMap::new(),
+            Err(ArchiveError::CorruptIndex(reason)) => {
+                sync.rebuilt = Some(reason);
+                HashMap::new()
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(reason) = &sync.rebuilt {
+            warn!("Rebuilding semantic index {}: {reason}", path.display());
+        }
+
+        let mut index = Self::new(embedder);
+        for scroll in scrolls {
+            let hash = fnv1a(index_text(scroll).as_bytes());
+            match saved.remove(&scroll.id) {
+                Some(entry) if entry.hash == hash => {
+                    index.hashes.insert(scroll.id, hash);
+                    index.vectors.push((scroll.id, entry.vector));
+                    sync.reused += 1;
+                }
+                _ => {
+                    index.embed(scroll)?;
+                    sync.embedded += 1;
                 }
             }
-            histogram!("vector_index_memory_bytes").record(bytes as f64);
         }
+        sync.dropped = saved.len();
 
-        info!("Vector generation complete");
-        Ok(Self { vectors })
+        #[cfg(feature = "metrics")]
+        index.record_build(build_timer);
+
+        info!(
+            "Semantic index: {} reused, {} embedded, {} dropped",
+            sync.reused, sync.embedded, sync.dropped
+        );
+        if sync.embedded > 0 || sync.dropped > 0 || sync.rebuilt.is_some() || !path.exists() {
+            index.save(path)?;
+        }
+        Ok((index, sync))
+    }
+
+    /// Writes the vectors, their text hashes, the embedder identity and its
+    /// model to `path`, replacing any previous file in one step.
+    pub fn save(&self, path: &Path) -> Result<(), ArchiveError> {
+        let embedder = self.embedder.identity();
+        let model = self.embedder.model();
+        let entries: Vec<IndexEntry> = self
+            .vectors
+            .iter()
+            .map(|(id, vector)| IndexEntry {
+                id: *id,
+                hash: self.hashes.get(id).copied().unwrap_or_default(),
+                vector: vector.clone(),
+            })
+            .collect();
+        let file = IndexFile {
+            version: INDEX_FILE_VERSION,
+            checksum: checksum(&embedder, model.as_ref(), &entries),
+            embedder,
+            model,
+            entries,
+        };
+        let json = serde_json::to_vec(&file).map_err(|e| ArchiveError::Write(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn embedder(&self) -> &dyn Embedder {
+        self.embedder.as_ref()
+    }
+
+    /// Re-embeds `scroll`, replacing its previous vector if it had one.
+    /// Does nothing if the embedded text is unchanged.
+    pub fn upsert(&mut self, scroll: &Scroll) -> Result<(), ArchiveError> {
+        let hash = fnv1a(index_text(scroll).as_bytes());
+        if self.hashes.get(&scroll.id) == Some(&hash) {
+            return Ok(());
+        }
+        self.embed(scroll)
     }
 
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.vectors.iter().any(|(existing, _)| *existing == id)
+    }
+
+    /// Drops the vector for `id`; returns whether there was one.
+    pub fn remove(&mut self, id: Uuid) -> bool {
+        self.hashes.remove(&id);
+        let before = self.vectors.len();
+        self.vectors.retain(|(existing, _)| *existing != id);
+        self.vectors.len() != before
+    }
+
+    /// The `k` scrolls whose vectors are closest to the embedded `input` by
+    /// cosine similarity. Empty if the input cannot be embedded.
     pub fn query(&self, input: &str, k: usize) -> Vec<(Uuid, f32)> {
         info!("Performing k-NN search for '{input}'");
-        let query_tokens = tokenize(input);
+        let query = match self.embedder.embed(input) {
+            Ok(query) => query,
+            Err(e) => {
+                warn!("Failed to embed query '{input}': {e}");
+                return Vec::new();
+            }
+        };
         let mut scores: Vec<(Uuid, f32)> = self
             .vectors
             .iter()
-            .map(|(id, tokens)| (*id, jaccard_similarity(tokens, &query_tokens)))
+            .map(|(id, vector)| (*id, cosine_similarity(vector, &query)))
             .collect();
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
         scores.into_iter().take(k).collect()
     }
+
+    fn embed(&mut self, scroll: &Scroll) -> Result<(), ArchiveError> {
+        #[cfg(feature = "metrics")]
+        let embed_timer = std::time::Instant::now();
+
+        let text = index_text(scroll);
+        let vec = self.embedder.embed(&text)?;
+
+        #[cfg(feature = "metrics")]
+        histogram!("scroll_embed_time_seconds").record(embed_timer.elapsed().as_secs_f64());
+
+        self.hashes.insert(scroll.id, fnv1a(text.as_bytes()));
+        match self.vectors.iter_mut().find(|(id, _)| *id == scroll.id) {
+            Some(entry) => entry.1 = vec,
+            None => self.vectors.push((scroll.id, vec)),
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "metrics")]
+    fn record_build(&self, started: std::time::Instant) {
+        histogram!("vector_index_update_time_seconds").record(started.elapsed().as_secs_f64());
+        let mut bytes: usize = std::mem::size_of_val(&self.vectors);
+        for (_, vector) in &self.vectors {
+            bytes += std::mem::size_of_val(vector.as_slice());
+        }
+        histogram!("vector_index_memory_bytes").record(bytes as f64);
+    }
+}
+
+/// The saved index at `path`, or `None` if there is no file. Unreadable
+/// JSON, another format version, a checksum mismatch or vectors of
+/// differing lengths are reported as [`ArchiveError::CorruptIndex`].
+fn read_index_file(path: &Path) -> Result<Option<IndexFile>, ArchiveError> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let corrupt = |reason: String| ArchiveError::CorruptIndex(reason);
+    let file: IndexFile = serde_json::from_slice(&bytes).map_err(|e| corrupt(e.to_string()))?;
+    if file.version != INDEX_FILE_VERSION {
+        return Err(corrupt(format!("unsupported version {}", file.version)));
+    }
+    if checksum(&file.embedder, file.model.as_ref(), &file.entries) != file.checksum {
+        return Err(corrupt("checksum mismatch".into()));
+    }
+    if let Some(first) = file.entries.first() {
+        if file
+            .entries
+            .iter()
+            .any(|e| e.vector.len() != first.vector.len())
+        {
+            return Err(corrupt("vectors differ in length".into()));
+        }
+    }
+    Ok(Some(file))
+}
+
+fn checksum(embedder: &str, model: Option<&serde_json::Value>, entries: &[IndexEntry]) -> u64 {
+    let mut bytes = embedder.as_bytes().to_vec();
+    if let Some(model) = model {
+        bytes.extend_from_slice(model.to_string().as_bytes());
+    }
+    for entry in entries {
+        bytes.extend_from_slice(entry.id.as_bytes());
+        bytes.extend_from_slice(&entry.hash.to_le_bytes());
+        for x in &entry.vector {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+    }
+    fnv1a(&bytes)
+}
+
+/// Title, tags and the first three body lines.
+pub(crate) fn index_text(s: &Scroll) -> String {
+    let first_lines = s
+        .markdown_body
+        .lines()
+        .take(3)
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{} {} {}",
+        s.title,
+        s.yaml_metadata.tags.join(" "),
+        first_lines
+    )
 }
 
 fn tokenize(text: &str) -> HashSet<String> {
@@ -114,12 +438,25 @@ fn tokenize(text: &str) -> HashSet<String> {
         .collect()
 }
 
-fn jaccard_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
-    let intersection = a.intersection(b).count() as f32;
-    let union = a.union(b).count() as f32;
-    if union == 0.0 {
+/// Cosine of the angle between two vectors; 0 for mismatched lengths or
+/// zero vectors.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
         0.0
     } else {
-        intersection / union
+        dot / norms
     }
 }
+
+/// 64-bit FNV-1a, stable across runs, for hashing features into buckets.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
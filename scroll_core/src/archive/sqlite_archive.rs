@@ -0,0 +1,603 @@
+//! SQLite-backed archive memory. Scrolls are stored with their tags and links
+//! in the `archive_*` tables (created by the `migration` crate, or on open),
+//! loaded into memory at startup and synced from the archive directory by
+//! content hash, so only new or edited files are parsed. [`SqliteStore`] is
+//! the blocking counterpart that pages single scrolls in for the
+//! [`HybridCacheArchive`](crate::archive::hybrid_archive::HybridCacheArchive).
+// src/archive/sqlite_archive.rs
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use log::warn;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::{Row, Sqlite, Transaction};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+use crate::access::{AccessPolicy, Actor, WriteAction};
+use crate::archive::archive_loader::{scan_archive, LoadFailure, LoadOptions};
+use crate::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use crate::archive::error::ArchiveError;
+use crate::archive::full_text::{TextHit, TextIndexOptions};
+use crate::archive::hybrid_archive::ScrollStore;
+use crate::archive::link_resolver::resolve_linked_scrolls;
+use crate::archive::semantic_index::{fnv1a, Embedder, IndexSync};
+use crate::archive::watcher::ArchiveChange;
+use crate::parser::{self, ParseError, ParseErrorKind};
+use crate::scroll::{Scroll, ScrollLinkType};
+
+/// Same tables as the `m20261018_000001_create_archive_tables` migration.
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS archive_scrolls (id TEXT NOT NULL PRIMARY KEY, title TEXT NOT NULL, scroll_type TEXT NOT NULL, status TEXT NOT NULL, path TEXT, content_hash TEXT, data TEXT NOT NULL, updated_at REAL NOT NULL);",
+    "CREATE TABLE IF NOT EXISTS archive_tags (scroll_id TEXT NOT NULL, tag TEXT NOT NULL, PRIMARY KEY (scroll_id, tag), FOREIGN KEY (scroll_id) REFERENCES archive_scrolls (id) ON DELETE CASCADE);",
+    "CREATE TABLE IF NOT EXISTS archive_links (source_id TEXT NOT NULL, target_id TEXT NOT NULL, link_type TEXT NOT NULL, PRIMARY KEY (source_id, target_id, link_type), FOREIGN KEY (source_id) REFERENCES archive_scrolls (id) ON DELETE CASCADE);",
+    "CREATE INDEX IF NOT EXISTS idx_archive_scrolls_path ON archive_scrolls (path);",
+    "CREATE INDEX IF NOT EXISTS idx_archive_tags_tag ON archive_tags (tag);",
+    "CREATE INDEX IF NOT EXISTS idx_archive_links_target ON archive_links (target_id);",
+];
+
+/// What a directory sync changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub failed: Vec<LoadFailure>,
+    pub duration: Duration,
+}
+
+impl SyncReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "Synced archive: {} added, {} updated, {} unchanged, {} removed, {} failed in {:.2?}",
+            self.added,
+            self.updated,
+            self.unchanged,
+            self.removed,
+            self.failed.len(),
+            self.duration
+        )
+    }
+}
+
+/// Scrolls persisted in SQLite and served from memory. Changes made through
+/// [`ArchiveMemory`] are held until [`flush`](Self::flush).
+pub struct SqliteArchive {
+    pool: SqlitePool,
+    memory: InMemoryArchive,
+    pending: Vec<ArchiveChange>,
+}
+
+impl SqliteArchive {
+    /// Opens (creating if needed) the database at `path`, or an in-memory
+    /// database for `":memory:"`, and loads every stored scroll.
+    pub async fn open(path: &str) -> Result<Self, ArchiveError> {
+        let mut archive = Self {
+            pool: connect(path).await?,
+            memory: InMemoryArchive::new(Vec::new()),
+            pending: Vec::new(),
+        };
+        archive.reload().await?;
+        Ok(archive)
+    }
+
+    /// Reloads the in-memory view from the database, dropping its indexes
+    /// and any unflushed changes. Rows that no longer deserialize are
+    /// logged and skipped.
+    pub async fn reload(&mut self) -> Result<usize, ArchiveError> {
+        self.pending.clear();
+        let rows = sqlx::query("SELECT id, data FROM archive_scrolls")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+        let scrolls: Vec<Scroll> = rows
+            .iter()
+            .filter_map(|row| {
+                let data: String = row.get("data");
+                serde_json::from_str(&data)
+                    .map_err(|e| {
+                        warn!("Skipping stored scroll {}: {e}", row.get::<String, _>("id"))
+                    })
+                    .ok()
+            })
+            .collect();
+        let count = scrolls.len();
+        self.memory = InMemoryArchive::new(scrolls);
+        Ok(count)
+    }
+
+    /// Brings the database in line with the scroll files under `root`.
+    /// Files whose content hash matches the stored one are not parsed;
+    /// stored scrolls whose file under `root` is gone are removed. Pending
+    /// changes are flushed first; the files win over them.
+    pub async fn sync_from_directory(
+        &mut self,
+        root: &Path,
+        options: &LoadOptions,
+    ) -> Result<SyncReport, ArchiveError> {
+        let started = Instant::now();
+        self.flush().await?;
+        let mut report = SyncReport::default();
+        let (files, _) =
+            scan_archive(root, options).map_err(|e| ArchiveError::Io(std::io::Error::other(e)))?;
+
+        let stored: HashMap<PathBuf, (Uuid, Option<String>)> = sqlx::query(
+            "SELECT id, path, content_hash FROM archive_scrolls WHERE path IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .filter_map(|row| {
+            let id = Uuid::parse_str(row.get::<&str, _>("id")).ok()?;
+            let path = PathBuf::from(row.get::<String, _>("path"));
+            Some((path, (id, row.get("content_hash"))))
+        })
+        .collect();
+
+        let mut changed = Vec::new();
+        let mut hashes = HashMap::new();
+        let mut dropped = HashSet::new();
+        let mut present = HashSet::new();
+        for path in files {
+            present.insert(path.clone());
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) => {
+                    let error = ParseError::new(ParseErrorKind::Io(e.to_string())).with_path(&path);
+                    report.failed.push(LoadFailure { path, error });
+                    continue;
+                }
+            };
+            let hash = content_hash(&text);
+            let previous = stored.get(&path);
+            if previous.is_some_and(|(_, stored_hash)| stored_hash.as_deref() == Some(&hash)) {
+                report.unchanged += 1;
+                continue;
+            }
+            match parser::parse_archive_scroll(root, &path, options.mode) {
+                Ok(scroll) => {
+                    match previous {
+                        Some((id, _)) => {
+                            report.updated += 1;
+                            if *id != scroll.id {
+                                dropped.insert(*id);
+                            }
+                        }
+                        None => report.added += 1,
+                    }
+                    hashes.insert(scroll.id, hash);
+                    changed.push(scroll);
+                }
+                Err(error) => report.failed.push(LoadFailure { path, error }),
+            }
+        }
+        for (path, (id, _)) in &stored {
+            if path.starts_with(root) && !present.contains(path) {
+                dropped.insert(*id);
+                report.removed += 1;
+            }
+        }
+
+        // Resolve links over the whole archive so unchanged scrolls pick up
+        // references to newly added ones.
+        let replaced: HashSet<Uuid> = changed
+            .iter()
+            .map(|s| s.id)
+            .chain(dropped.clone())
+            .collect();
+        let mut all: Vec<Scroll> = self
+            .memory
+            .get_all_scrolls()
+            .into_iter()
+            .filter(|s| !replaced.contains(&s.id))
+            .cloned()
+            .collect();
+        let before: HashMap<Uuid, HashSet<(Uuid, ScrollLinkType)>> =
+            all.iter().map(|s| (s.id, link_set(s))).collect();
+        all.extend(changed);
+        resolve_linked_scrolls(&mut all);
+
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for id in &dropped {
+            delete_scroll(&mut tx, *id).await?;
+        }
+        for scroll in &all {
+            let relinked = before
+                .get(&scroll.id)
+                .is_some_and(|links| *links != link_set(scroll));
+            if hashes.contains_key(&scroll.id) || relinked {
+                let hash = match hashes.get(&scroll.id) {
+                    Some(hash) => Some(hash.clone()),
+                    None => stored_hash(&stored, scroll),
+                };
+                upsert_scroll(&mut tx, scroll, hash.as_deref()).await?;
+            }
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        self.memory = InMemoryArchive::new(all);
+        report.duration = started.elapsed();
+        Ok(report)
+    }
+
+    /// Persists a change, e.g. from the archive watcher, and applies it to
+    /// the in-memory view and its semantic index.
+    pub async fn apply_change(&mut self, change: &ArchiveChange) -> Result<(), ArchiveError> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        persist(&mut tx, change, true).await?;
+        tx.commit().await.map_err(db_error)?;
+        self.memory.apply_change(change)
+    }
+
+    /// Writes the changes made through [`ArchiveMemory`] in one transaction
+    /// and returns how many there were. Their content hash is left empty, so
+    /// the next sync re-reads their files.
+    pub async fn flush(&mut self) -> Result<usize, ArchiveError> {
+        if self.pending.is_empty() {
+            return Ok(0);
+        }
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for change in &self.pending {
+            persist(&mut tx, change, false).await?;
+        }
+        tx.commit().await.map_err(db_error)?;
+        Ok(std::mem::take(&mut self.pending).len())
+    }
+
+    /// Changes waiting for [`flush`](Self::flush).
+    pub fn pending(&self) -> &[ArchiveChange] {
+        &self.pending
+    }
+
+    /// Ids of stored scrolls carrying `tag`, straight from the database.
+    pub async fn ids_with_tag(&self, tag: &str) -> Result<Vec<Uuid>, ArchiveError> {
+        let rows =
+            sqlx::query("SELECT scroll_id FROM archive_tags WHERE tag = ? ORDER BY scroll_id")
+                .bind(tag)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(db_error)?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| Uuid::parse_str(row.get::<&str, _>("scroll_id")).ok())
+            .collect())
+    }
+
+    /// Ids of stored scrolls linking to `target`.
+    pub async fn backlinks(&self, target: Uuid) -> Result<Vec<Uuid>, ArchiveError> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT source_id FROM archive_links WHERE target_id = ? ORDER BY source_id",
+        )
+        .bind(target.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| Uuid::parse_str(row.get::<&str, _>("source_id")).ok())
+            .collect())
+    }
+
+    /// Builds the semantic index over the loaded scrolls.
+    pub fn build_semantic_index(
+        &mut self,
+        embedder: impl Embedder + 'static,
+    ) -> Result<(), ArchiveError> {
+        self.memory.build_semantic_index(embedder)
+    }
+
+    /// Loads the saved semantic index, embedding only changed scrolls.
+    pub fn load_semantic_index(
+        &mut self,
+        path: &Path,
+        embedder: impl Embedder + 'static,
+    ) -> Result<IndexSync, ArchiveError> {
+        self.memory.load_semantic_index(path, embedder)
+    }
+
+    /// Saves the semantic index to `path`.
+    pub fn save_semantic_index(&self, path: &Path) -> Result<(), ArchiveError> {
+        self.memory.save_semantic_index(path)
+    }
+
+    /// Builds the full-text index over the loaded scrolls.
+    pub fn build_text_index(&mut self, options: TextIndexOptions) {
+        self.memory.build_text_index(options)
+    }
+
+    /// Embeds scrolls changed since the semantic index was built.
+    pub fn refresh_semantic_index(&mut self) -> Result<usize, ArchiveError> {
+        self.memory.refresh_semantic_index()
+    }
+
+    /// The in-memory view, for queries `ArchiveMemory` does not cover.
+    pub fn memory(&self) -> &InMemoryArchive {
+        &self.memory
+    }
+}
+
+impl ArchiveMemory for SqliteArchive {
+    fn get_all_scrolls(&self) -> Vec<&Scroll> {
+        self.memory.get_all_scrolls()
+    }
+
+    fn get_scroll_by_id(&self, id: Uuid) -> Option<&Scroll> {
+        self.memory.get_scroll_by_id(id)
+    }
+
+    fn get_scrolls_by_tag(&self, tag: &str) -> Vec<&Scroll> {
+        self.memory.get_scrolls_by_tag(tag)
+    }
+
+    fn count(&self) -> usize {
+        self.memory.count()
+    }
+
+    fn query_semantic(&self, input: &str, k: usize) -> Vec<(Scroll, f32)> {
+        self.memory.query_semantic(input, k)
+    }
+
+    fn query_text(&self, query: &str, k: usize) -> Vec<(Scroll, TextHit)> {
+        self.memory.query_text(query, k)
+    }
+
+    fn contains(&self, id: Uuid) -> bool {
+        self.memory.contains(id)
+    }
+
+    fn insert(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        let change = self.memory.insert(scroll)?;
+        self.pending.push(change.clone());
+        Ok(change)
+    }
+
+    fn update(&mut self, scroll: Scroll) -> Result<ArchiveChange, ArchiveError> {
+        let change = self.memory.update(scroll)?;
+        self.pending.push(change.clone());
+        Ok(change)
+    }
+
+    fn remove(&mut self, id: Uuid) -> Result<ArchiveChange, ArchiveError> {
+        let change = self.memory.remove(id)?;
+        self.pending.push(change.clone());
+        Ok(change)
+    }
+}
+
+/// Blocking access to the archive tables, paging single scrolls in on
+/// demand. It runs queries on its own runtime, so it must not be used from
+/// inside an async context.
+pub struct SqliteStore {
+    runtime: Runtime,
+    pool: SqlitePool,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path` like [`SqliteArchive::open`], without
+    /// loading any scrolls.
+    pub fn open(path: &str) -> Result<Self, ArchiveError> {
+        let runtime = Runtime::new()?;
+        let pool = runtime.block_on(connect(path))?;
+        Ok(Self { runtime, pool })
+    }
+
+    fn write(&self, change: &ArchiveChange) -> Result<(), ArchiveError> {
+        self.runtime.block_on(async {
+            let mut tx = self.pool.begin().await.map_err(db_error)?;
+            persist(&mut tx, change, false).await?;
+            tx.commit().await.map_err(db_error)
+        })
+    }
+}
+
+impl ScrollStore for SqliteStore {
+    fn catalog(&self) -> Result<HashMap<Uuid, Vec<String>>, ArchiveError> {
+        let rows = self
+            .runtime
+            .block_on(
+                sqlx::query(
+                    "SELECT s.id AS id, t.tag AS tag FROM archive_scrolls s \
+                     LEFT JOIN archive_tags t ON t.scroll_id = s.id",
+                )
+                .fetch_all(&self.pool),
+            )
+            .map_err(db_error)?;
+        let mut catalog: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for row in &rows {
+            let Ok(id) = Uuid::parse_str(row.get::<&str, _>("id")) else {
+                continue;
+            };
+            let tags = catalog.entry(id).or_default();
+            if let Some(tag) = row.get::<Option<String>, _>("tag") {
+                tags.push(tag);
+            }
+        }
+        Ok(catalog)
+    }
+
+    fn load(&self, id: Uuid) -> Result<Option<Scroll>, ArchiveError> {
+        let row = self
+            .runtime
+            .block_on(
+                sqlx::query("SELECT data FROM archive_scrolls WHERE id = ?")
+                    .bind(id.to_string())
+                    .fetch_optional(&self.pool),
+            )
+            .map_err(db_error)?;
+        row.map(|row| {
+            serde_json::from_str(row.get::<&str, _>("data"))
+                .map_err(|e| ArchiveError::Database(e.to_string()))
+        })
+        .transpose()
+    }
+
+    fn save(&mut self, scroll: &Scroll, actor: &Actor) -> Result<(), ArchiveError> {
+        if let Some(existing) = self.load(scroll.id)? {
+            AccessPolicy::default()
+                .authorize(actor, &existing, WriteAction::Write)
+                .map_err(ArchiveError::Write)?;
+        }
+        self.write(&ArchiveChange::Modified(Box::new(scroll.clone())))
+    }
+
+    fn delete(&mut self, id: Uuid) -> Result<(), ArchiveError> {
+        self.write(&ArchiveChange::Removed {
+            id,
+            path: PathBuf::new(),
+        })
+    }
+}
+
+async fn connect(path: &str) -> Result<SqlitePool, ArchiveError> {
+    let pool = if path == ":memory:" {
+        // Every connection would get its own in-memory database.
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+    } else {
+        SqlitePool::connect(&format!("sqlite://{}?mode=rwc", path)).await
+    }
+    .map_err(db_error)?;
+    for statement in SCHEMA {
+        sqlx::query(statement)
+            .execute(&pool)
+            .await
+            .map_err(db_error)?;
+    }
+    Ok(pool)
+}
+
+/// Stores or deletes the scroll behind `change`. With `hash_file`, the
+/// content hash of the scroll's file is recorded so syncs can skip it.
+async fn persist(
+    tx: &mut Transaction<'_, Sqlite>,
+    change: &ArchiveChange,
+    hash_file: bool,
+) -> Result<(), ArchiveError> {
+    match change {
+        ArchiveChange::Created(scroll) | ArchiveChange::Modified(scroll) => {
+            let hash = scroll
+                .origin
+                .source_path
+                .as_ref()
+                .filter(|_| hash_file)
+                .and_then(|path| fs::read_to_string(path).ok())
+                .map(|text| content_hash(&text));
+            upsert_scroll(tx, scroll, hash.as_deref()).await
+        }
+        ArchiveChange::Removed { id, .. } => delete_scroll(tx, *id).await,
+    }
+}
+
+fn stored_hash(
+    stored: &HashMap<PathBuf, (Uuid, Option<String>)>,
+    scroll: &Scroll,
+) -> Option<String> {
+    let path = scroll.origin.source_path.as_ref()?;
+    stored.get(path).and_then(|(_, hash)| hash.clone())
+}
+
+async fn upsert_scroll(
+    tx: &mut Transaction<'_, Sqlite>,
+    scroll: &Scroll,
+    content_hash: Option<&str>,
+) -> Result<(), ArchiveError> {
+    let id = scroll.id.to_string();
+    let data = serde_json::to_string(scroll).map_err(|e| ArchiveError::Database(e.to_string()))?;
+    sqlx::query(
+        "INSERT INTO archive_scrolls (id, title, scroll_type, status, path, content_hash, data, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET title = excluded.title, scroll_type = excluded.scroll_type, \
+         status = excluded.status, path = excluded.path, content_hash = excluded.content_hash, \
+         data = excluded.data, updated_at = excluded.updated_at",
+    )
+    .bind(&id)
+    .bind(&scroll.title)
+    .bind(scroll.scroll_type.to_string())
+    .bind(format!("{:?}", scroll.status))
+    .bind(
+        scroll
+            .origin
+            .source_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned()),
+    )
+    .bind(content_hash)
+    .bind(data)
+    .bind(Utc::now().timestamp_millis() as f64 / 1000.0)
+    .execute(&mut **tx)
+    .await
+    .map_err(db_error)?;
+
+    for table in [
+        "archive_tags WHERE scroll_id",
+        "archive_links WHERE source_id",
+    ] {
+        sqlx::query(&format!("DELETE FROM {table} = ?"))
+            .bind(&id)
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+    }
+    for tag in &scroll.yaml_metadata.tags {
+        sqlx::query("INSERT OR IGNORE INTO archive_tags (scroll_id, tag) VALUES (?, ?)")
+            .bind(&id)
+            .bind(tag)
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+    }
+    for link in &scroll.linked_scrolls {
+        sqlx::query(
+            "INSERT OR IGNORE INTO archive_links (source_id, target_id, link_type) VALUES (?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(link.target.to_string())
+        .bind(format!("{:?}", link.link_type))
+        .execute(&mut **tx)
+        .await
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+async fn delete_scroll(tx: &mut Transaction<'_, Sqlite>, id: Uuid) -> Result<(), ArchiveError> {
+    let id = id.to_string();
+    for table in [
+        "archive_tags WHERE scroll_id",
+        "archive_links WHERE source_id",
+        "archive_scrolls WHERE id",
+    ] {
+        sqlx::query(&format!("DELETE FROM {table} = ?"))
+            .bind(&id)
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+/// The targets and types of a scroll's links, regardless of order.
+fn link_set(scroll: &Scroll) -> HashSet<(Uuid, ScrollLinkType)> {
+    scroll
+        .linked_scrolls
+        .iter()
+        .map(|link| (link.target, link.link_type.clone()))
+        .collect()
+}
+
+/// 64-bit FNV-1a of the file text, stable across runs and Rust versions.
+fn content_hash(text: &str) -> String {
+    format!("{:016x}", fnv1a(text.as_bytes()))
+}
+
+fn db_error(e: sqlx::Error) -> ArchiveError {
+    ArchiveError::Database(e.to_string())
+}
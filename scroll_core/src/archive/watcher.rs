@@ -0,0 +1,265 @@
+//! Live reload for a running archive. The [`ArchiveWatcher`] polls the archive
+//! directory, re-parses only files whose size or modification time changed
+//! and reports each difference as an [`ArchiveChange`]. Spawned watchers also
+//! broadcast the changes on the orchestra bus.
+// src/archive/watcher.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crossbeam::channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+use log::warn;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::archive::archive_loader::{scan_archive, LoadOptions};
+use crate::orchestra::{AgentMessage, Bus};
+use crate::parser;
+use crate::scroll::Scroll;
+
+/// `kind` field of the bus payload carrying an archive change.
+pub const ARCHIVE_CHANGE_KIND: &str = "archive_change";
+
+/// A scroll file that appeared, changed or went away.
+#[derive(Debug, Clone)]
+pub enum ArchiveChange {
+    Created(Box<Scroll>),
+    Modified(Box<Scroll>),
+    /// `path` is empty for scrolls that were never read from a file.
+    Removed {
+        id: Uuid,
+        path: PathBuf,
+    },
+}
+
+impl ArchiveChange {
+    /// The removal of `scroll`.
+    pub fn removed(scroll: &Scroll) -> Self {
+        ArchiveChange::Removed {
+            id: scroll.id,
+            path: scroll.origin.source_path.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        match self {
+            ArchiveChange::Created(scroll) | ArchiveChange::Modified(scroll) => scroll.id,
+            ArchiveChange::Removed { id, .. } => *id,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ArchiveChange::Created(_) => "created",
+            ArchiveChange::Modified(_) => "modified",
+            ArchiveChange::Removed { .. } => "removed",
+        }
+    }
+
+    /// Applies the change to a plain list of scrolls, such as a chat
+    /// session's memory.
+    pub fn apply_to(&self, scrolls: &mut Vec<Scroll>) {
+        match self {
+            ArchiveChange::Created(scroll) | ArchiveChange::Modified(scroll) => {
+                match scrolls.iter_mut().find(|s| s.id == scroll.id) {
+                    Some(existing) => *existing = (**scroll).clone(),
+                    None => scrolls.push((**scroll).clone()),
+                }
+            }
+            ArchiveChange::Removed { id, .. } => scrolls.retain(|s| s.id != *id),
+        }
+    }
+
+    /// A broadcast message describing the change; the scroll itself is not
+    /// included.
+    pub fn to_message(&self) -> AgentMessage {
+        let (title, path) = match self {
+            ArchiveChange::Created(scroll) | ArchiveChange::Modified(scroll) => (
+                Some(scroll.title.clone()),
+                scroll.origin.source_path.clone(),
+            ),
+            ArchiveChange::Removed { path, .. } => (None, Some(path.clone())),
+        };
+        AgentMessage {
+            id: Uuid::new_v4(),
+            from: "archive_watcher".into(),
+            to: "broadcast".into(),
+            payload: json!({
+                "kind": ARCHIVE_CHANGE_KIND,
+                "change": self.kind(),
+                "scroll_id": self.id(),
+                "title": title,
+                "path": path,
+            }),
+            trace: vec!["archive_watcher".into()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    /// Id of the scroll last parsed from the file; `None` if it never parsed.
+    id: Option<Uuid>,
+}
+
+/// Polls an archive directory for changed scroll files.
+pub struct ArchiveWatcher {
+    root: PathBuf,
+    options: LoadOptions,
+    known: HashMap<PathBuf, Stamp>,
+}
+
+impl ArchiveWatcher {
+    /// Starts from the current state of the directory. `scrolls` are the
+    /// already loaded scrolls, matched to files by their source path, so
+    /// nothing is re-parsed until it changes.
+    pub fn new(root: impl Into<PathBuf>, options: LoadOptions, scrolls: &[Scroll]) -> Self {
+        let root = root.into();
+        let ids: HashMap<&Path, Uuid> = scrolls
+            .iter()
+            .filter_map(|s| Some((s.origin.source_path.as_deref()?, s.id)))
+            .collect();
+        let known = scan_archive(&root, &options)
+            .map(|(files, _)| files)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|path| {
+                let mut stamp = stamp(&path);
+                stamp.id = ids.get(path.as_path()).copied();
+                (path, stamp)
+            })
+            .collect();
+        Self {
+            root,
+            options,
+            known,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Rescans the archive and returns what changed since the last poll.
+    /// A file that no longer parses keeps its previous scroll and is logged.
+    pub fn poll(&mut self) -> Vec<ArchiveChange> {
+        let files = match scan_archive(&self.root, &self.options) {
+            Ok((files, _)) => files,
+            Err(e) => {
+                warn!("Archive watch on {} failed: {e}", self.root.display());
+                return Vec::new();
+            }
+        };
+
+        let mut changes = Vec::new();
+        let mut seen = HashMap::new();
+        for path in files {
+            let mut current = stamp(&path);
+            let previous = self.known.remove(&path);
+            if let Some(previous) = &previous {
+                if previous.modified == current.modified && previous.len == current.len {
+                    seen.insert(path, previous.clone());
+                    continue;
+                }
+            }
+            let previous_id = previous.and_then(|p| p.id);
+            match parser::parse_archive_scroll(&self.root, &path, self.options.mode) {
+                Ok(scroll) => {
+                    current.id = Some(scroll.id);
+                    match previous_id {
+                        Some(id) if id == scroll.id => {
+                            changes.push(ArchiveChange::Modified(Box::new(scroll)))
+                        }
+                        Some(id) => {
+                            changes.push(ArchiveChange::Removed {
+                                id,
+                                path: path.clone(),
+                            });
+                            changes.push(ArchiveChange::Created(Box::new(scroll)));
+                        }
+                        None => changes.push(ArchiveChange::Created(Box::new(scroll))),
+                    }
+                }
+                Err(e) => {
+                    warn!("{}", e.render());
+                    current.id = previous_id;
+                }
+            }
+            seen.insert(path, current);
+        }
+
+        for (path, stamp) in self.known.drain() {
+            if let Some(id) = stamp.id {
+                changes.push(ArchiveChange::Removed { id, path });
+            }
+        }
+        self.known = seen;
+        changes
+    }
+
+    /// Polls every `interval` on a background thread. Changes arrive on the
+    /// handle and, when a bus is given, are broadcast on it.
+    pub fn spawn(mut self, interval: Duration, bus: Option<Bus>) -> WatchHandle {
+        let (tx, rx) = unbounded();
+        let (stop, stopped) = unbounded::<()>();
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                for change in self.poll() {
+                    if let Some(bus) = &bus {
+                        bus.send(change.to_message());
+                    }
+                    if tx.send(change).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        WatchHandle {
+            changes: rx,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = fs::metadata(path).ok();
+    Stamp {
+        modified: meta.as_ref().and_then(|m| m.modified().ok()),
+        len: meta.map(|m| m.len()).unwrap_or(0),
+        id: None,
+    }
+}
+
+/// A running watcher. Dropping it stops the thread.
+pub struct WatchHandle {
+    changes: Receiver<ArchiveChange>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WatchHandle {
+    /// Changes reported since the last call, without blocking.
+    pub fn drain(&self) -> Vec<ArchiveChange> {
+        self.changes.try_iter().collect()
+    }
+
+    /// Waits up to `timeout` for the next change.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ArchiveChange> {
+        self.changes.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -5,6 +5,7 @@ use uuid::Uuid;
 
 use crate::archive::mythic_heat::MythicHeat;
 use crate::archive::scroll_access_log::ScrollAccess;
+use crate::archive::watcher::ArchiveChange;
 use crate::core::cost_manager::InvocationCost;
 use crate::schema::EmotionSignature;
 use crate::scroll::Scroll;
@@ -64,6 +65,32 @@ impl CacheManager {
         }
     }
 
+    /// Applies a change reported by the archive watcher. New scrolls are
+    /// cached with no access history; edited scrolls are replaced in place
+    /// and keep their heat; removed scrolls are evicted.
+    pub fn apply_change(&mut self, change: &ArchiveChange) {
+        match change {
+            ArchiveChange::Created(scroll) => {
+                let emotion = scroll.emotion_signature.clone();
+                self.cache_scroll(
+                    (**scroll).clone(),
+                    &emotion,
+                    &ScrollAccess::new(),
+                    &InvocationCost::default(),
+                );
+            }
+            ArchiveChange::Modified(scroll) => {
+                if let Some(cached) = self.active_scrolls.get_mut(&scroll.id) {
+                    *cached = (**scroll).clone();
+                }
+            }
+            ArchiveChange::Removed { id, .. } => {
+                self.active_scrolls.remove(id);
+                self.heat_scores.remove(id);
+            }
+        }
+    }
+
     pub fn get(&self, id: &Uuid) -> Option<&Scroll> {
         self.active_scrolls.get(id)
     }
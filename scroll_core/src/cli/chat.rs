@@ -1,16 +1,24 @@
+use crate::archive::archive_memory::InMemoryArchive;
+use crate::archive::watcher::WatchHandle;
+use crate::cache_manager::CacheManager;
 use crate::chat::chat_dispatcher::ChatDispatcher;
 use crate::chat::chat_session::ChatSession;
+use crate::core::context_frame_engine::{ContextFrameEngine, ContextMode};
 use crate::invocation::aelren::AelrenHerald;
 use crate::invocation::invocation_manager::InvocationManager;
 use crate::invocation::types::{Invocation, InvocationMode, InvocationTier};
+use crate::sessions::session::ScrollSession;
+use crate::state_manager::set_active_session;
 use crate::trigger_loom::emotional_state::EmotionalState;
 use crate::Scroll;
 use anyhow::Result;
 use chrono::Utc;
 use ctrlc;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, Mutex,
 };
 use uuid::Uuid;
 
@@ -24,13 +32,16 @@ use tokio::runtime::Runtime;
 #[allow(clippy::too_many_arguments)]
 pub fn run_chat(
     manager: &InvocationManager,
-    aelren: &AelrenHerald,
-    memory: &[Scroll],
+    archive: &mut InMemoryArchive,
+    memory: &mut Vec<Scroll>,
+    cache: &mut CacheManager,
+    index_path: &Path,
     target: &str,
     _stream: bool,
     db: &ChatDb,
     theme: Theme,
     show_banner: bool,
+    watch: Option<&WatchHandle>,
 ) -> Result<()> {
     let rt = Runtime::new()?;
     if show_banner && std::env::var("SCROLL_CI").is_err() {
@@ -40,6 +51,13 @@ pub fn run_chat(
     let mut session = ChatSession::new(Some(target.to_string()), None);
     let mut mood = EmotionalState::new(Vec::new(), 0.0, None);
     let session_id = rt.block_on(db.create_session())?;
+    // Scroll transitions made during the chat are recorded on this session.
+    set_active_session(Some(Arc::new(Mutex::new(ScrollSession::new(
+        session_id.clone(),
+        "scroll_core".into(),
+        "cli".into(),
+        HashMap::new(),
+    )))));
 
     let running = Arc::new(AtomicBool::new(true));
     let rflag = running.clone();
@@ -84,8 +102,32 @@ pub fn run_chat(
             resonance_required: false,
             timestamp: Utc::now(),
         };
+        if let Some(watch) = watch {
+            let changes = watch.drain();
+            for change in &changes {
+                change.apply_to(memory);
+                cache.apply_change(change);
+                if let Err(e) = archive.apply_change(change) {
+                    eprintln!(
+                        "Failed to apply {} scroll {}: {e}",
+                        change.kind(),
+                        change.id()
+                    );
+                }
+            }
+            if !changes.is_empty() {
+                println!("↻ Picked up {} archive change(s).", changes.len());
+                if let Err(e) = archive.save_semantic_index(index_path) {
+                    eprintln!("⚠️ Failed to save semantic index: {e}");
+                }
+            }
+        }
+        let aelren = AelrenHerald::new(
+            ContextFrameEngine::new(archive, ContextMode::Narrow),
+            vec![target.to_string()],
+        );
         let reply =
-            ChatDispatcher::dispatch(&mut session, trimmed, manager, aelren, memory, &mut mood);
+            ChatDispatcher::dispatch(&mut session, trimmed, manager, &aelren, memory, &mut mood);
         if reply.role == "system" {
             println!("{}", reply.content);
             if let Err(e) = rt.block_on(db.log_event(&session_id, "system", &reply.content)) {
@@ -112,5 +154,6 @@ pub fn run_chat(
             let _ = std::fs::write(path, trimmed);
         }
     }
+    set_active_session(None);
     Ok(())
 }
@@ -12,6 +12,9 @@ use crate::archive::scroll_access_log::ScrollAccessLog;
 use crate::construct_ai::ConstructContext;
 use crate::scroll::Scroll;
 
+/// Semantic similarity a scroll needs to be recalled without full-text help.
+pub const SEMANTIC_THRESHOLD: f32 = 0.65;
+
 pub enum ContextMode {
     Narrow,
     Broad,
@@ -48,34 +51,20 @@ impl<'a> ContextFrameEngine<'a> {
                 .archive
                 .query_by_tags(&triggering_scroll.yaml_metadata.tags),
             ContextMode::Broad => {
-                let query = compose_query(triggering_scroll);
-                let semantic: Vec<_> = self
-                    .archive
-                    .query_semantic(&query, self.max_scrolls * 2)
-                    .into_iter()
-                    .filter(|(_, score)| *score > 0.65)
-                    .map(|(s, _)| s)
-                    .collect();
-                if semantic.is_empty() {
+                let recalled = self.recall(triggering_scroll);
+                if recalled.is_empty() {
                     self.archive
                         .query_by_emotion(triggering_scroll.emotion_signature.clone())
                 } else {
-                    semantic
+                    recalled
                 }
             }
             ContextMode::Echo => {
-                let query = compose_query(triggering_scroll);
-                let semantic: Vec<_> = self
-                    .archive
-                    .query_semantic(&query, self.max_scrolls * 2)
-                    .into_iter()
-                    .filter(|(_, score)| *score > 0.65)
-                    .map(|(s, _)| s)
-                    .collect();
-                if semantic.is_empty() {
+                let recalled = self.recall(triggering_scroll);
+                if recalled.is_empty() {
                     self.archive.query_by_links(&triggering_scroll.id)
                 } else {
-                    semantic
+                    recalled
                 }
             }
         };
@@ -96,6 +85,28 @@ impl<'a> ContextFrameEngine<'a> {
             user_input: None,
         }
     }
+
+    /// Scrolls semantically close to `scroll`. When no semantic score clears
+    /// [`SEMANTIC_THRESHOLD`], falls back to full-text matches on the same
+    /// query, which also reach text deep inside long bodies.
+    fn recall(&self, scroll: &Scroll) -> Vec<Scroll> {
+        let query = compose_query(scroll);
+        let semantic: Vec<_> = self
+            .archive
+            .query_semantic(&query, self.max_scrolls * 2)
+            .into_iter()
+            .filter(|(_, score)| *score > SEMANTIC_THRESHOLD)
+            .map(|(s, _)| s)
+            .collect();
+        if !semantic.is_empty() {
+            return semantic;
+        }
+        self.archive
+            .query_text(&query, self.max_scrolls * 2)
+            .into_iter()
+            .map(|(s, _)| s)
+            .collect()
+    }
 }
 
 fn compose_query(scroll: &Scroll) -> String {
@@ -1,3 +1,5 @@
 pub mod scroll_event;
+pub mod transition_event;
 
 pub use scroll_event::ScrollEvent;
+pub use transition_event::ScrollTransitionEvent;
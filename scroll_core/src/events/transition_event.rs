@@ -0,0 +1,103 @@
+//! Typed lifecycle event published whenever a scroll changes status.
+//! The state manager broadcasts it on the orchestra bus and records it as a
+//! [`ScrollEvent`] in the active session, so constructs can react to changes.
+// src/events/transition_event.rs
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::events::scroll_event::ScrollEvent;
+use crate::memory::memory_result::MemoryDelta;
+use crate::models::base_model::LLMResponseContent;
+use crate::orchestra::AgentMessage;
+use crate::schema::ScrollStatus;
+use crate::scroll::Scroll;
+use crate::state_manager::TransitionRecord;
+
+/// `kind` field of the bus payload carrying a transition.
+pub const TRANSITION_KIND: &str = "scroll_transition";
+
+/// Name transition events are sent and authored under.
+pub const TRANSITION_SOURCE: &str = "state_manager";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScrollTransitionEvent {
+    pub scroll_id: Uuid,
+    pub title: String,
+    pub from: ScrollStatus,
+    pub to: ScrollStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl ScrollTransitionEvent {
+    pub fn from_record(scroll: &Scroll, record: &TransitionRecord) -> Self {
+        Self {
+            scroll_id: scroll.id,
+            title: scroll.title.clone(),
+            from: record.from.clone(),
+            to: record.to.clone(),
+            actor: record.actor.clone(),
+            reason: record.reason.clone(),
+            at: record.at,
+        }
+    }
+
+    /// A broadcast message whose payload is the event tagged with
+    /// [`TRANSITION_KIND`].
+    pub fn to_message(&self) -> AgentMessage {
+        let mut payload = serde_json::to_value(self).unwrap_or_default();
+        payload["kind"] = TRANSITION_KIND.into();
+        AgentMessage {
+            id: Uuid::new_v4(),
+            from: TRANSITION_SOURCE.into(),
+            to: "broadcast".into(),
+            payload,
+            trace: vec![TRANSITION_SOURCE.into()],
+        }
+    }
+
+    /// The event carried by `msg`, or `None` for any other message.
+    pub fn from_message(msg: &AgentMessage) -> Option<Self> {
+        if msg.payload["kind"] != TRANSITION_KIND {
+            return None;
+        }
+        serde_json::from_value(msg.payload.clone()).ok()
+    }
+
+    /// The event as a session timeline entry; the status change is kept in
+    /// the memory delta.
+    pub fn to_scroll_event(&self) -> ScrollEvent {
+        let mut state_delta = HashMap::from([
+            ("scroll_id".to_string(), self.scroll_id.to_string()),
+            ("from".to_string(), format!("{:?}", self.from)),
+            ("to".to_string(), format!("{:?}", self.to)),
+        ]);
+        if let Some(actor) = &self.actor {
+            state_delta.insert("actor".into(), actor.clone());
+        }
+        if let Some(reason) = &self.reason {
+            state_delta.insert("reason".into(), reason.clone());
+        }
+        ScrollEvent::new(
+            TRANSITION_SOURCE.into(),
+            Some(LLMResponseContent {
+                text: format!(
+                    "'{}' moved from {:?} to {:?}",
+                    self.title, self.from, self.to
+                ),
+            }),
+            Some(MemoryDelta { state_delta }),
+            false,
+            true,
+            false,
+            None,
+        )
+    }
+}
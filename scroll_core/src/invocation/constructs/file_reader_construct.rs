@@ -3,6 +3,7 @@
 //! See [FileReader](../../../AGENTS.md#filereader) for the high level design.
 // src/invocation/constructs/file_reader_construct.rs
 
+use crate::events::ScrollTransitionEvent;
 use crate::invocation::named_construct::NamedConstruct;
 use crate::invocation::types::{Invocation, InvocationResult};
 use crate::orchestra::{AgentMessage, Bus, OrchestratedConstruct};
@@ -37,6 +38,9 @@ impl OrchestratedConstruct for FileReader {
         thread::spawn(move || {
             let rt = Runtime::new().unwrap();
             while let Ok(msg) = rx.recv() {
+                if ScrollTransitionEvent::from_message(&msg).is_some() {
+                    continue;
+                }
                 let path = msg.payload["path"].as_str().unwrap_or("").to_string();
                 let to = msg.from.clone();
                 let mut trace = msg.trace.clone();
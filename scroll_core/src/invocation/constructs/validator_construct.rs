@@ -6,6 +6,7 @@
 // src/constructs/validator_construct.rs
 // ===============================
 
+use crate::events::ScrollTransitionEvent;
 use crate::invocation::named_construct::NamedConstruct;
 use crate::invocation::types::{Invocation, InvocationMode, InvocationResult};
 use crate::orchestra::{AgentMessage, Bus, OrchestratedConstruct};
@@ -51,6 +52,9 @@ impl OrchestratedConstruct for Validator {
         self.bus = Some(bus.clone());
         thread::spawn(move || {
             while let Ok(msg) = rx.recv() {
+                if ScrollTransitionEvent::from_message(&msg).is_some() {
+                    continue;
+                }
                 let text = msg.payload["text"].as_str().unwrap_or("");
                 let path = text.split_whitespace().last().unwrap_or("");
                 let mut trace = msg.trace.clone();
@@ -41,3 +41,15 @@ pub enum InvocationResult {
     ModifiedScroll(Box<crate::Scroll>),
     Failure(Box<str>),
 }
+
+/// The first word of a construct's reply, lowercased, so a verdict like
+/// "Reject: the door must stay." reads as `reject` while "not sure" reads
+/// as `not` rather than `no`.
+pub fn reply_keyword(reply: &str) -> String {
+    reply
+        .trim_start()
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
@@ -3,6 +3,7 @@
 // ===============================
 #![warn(unused_imports)]
 
+pub mod access;
 pub mod archive;
 pub mod artifact;
 pub mod artifacts;
@@ -21,6 +22,7 @@ pub mod metrics;
 pub mod models;
 pub mod orchestra;
 pub mod parser;
+pub mod quorum;
 pub mod runner;
 pub mod schema;
 pub mod scroll;
@@ -33,6 +35,7 @@ pub mod telemetry;
 pub mod tools;
 pub mod tracing;
 pub mod trigger_loom;
+pub mod type_registry;
 pub mod validator;
 
 pub use cache_manager::CacheManager;
@@ -47,28 +50,45 @@ pub use tracing::{init_tracing, init_tracing_for_test};
 
 use anyhow::Result;
 
-pub use state_manager::{describe_status, is_valid_transition, transition, try_transition};
+pub use state_manager::{
+    describe_status, is_valid_transition, transition, try_transition, StateMachine,
+};
 
 pub const SCROLL_CORE_VERSION: &str = "0.2.0";
 pub const SCROLL_CORE_INVOCATION: &str = "Let structure echo symbol.";
 
+/// The archive directory: `$SCROLL_CORE_ARCHIVE_DIR`, or `scrolls`.
+pub fn archive_dir() -> std::path::PathBuf {
+    std::env::var_os("SCROLL_CORE_ARCHIVE_DIR")
+        .map(Into::into)
+        .unwrap_or_else(|| "scrolls".into())
+}
+
 /// Initializes the Scroll Core system and loads the scroll archive.
+/// Link integrity is checked at the level set by `SCROLL_CORE_INTEGRITY`
+/// (`off`, `warn` or `strict`; defaults to `warn`).
 pub fn initialize_scroll_core() -> Result<(Vec<Scroll>, CacheManager)> {
     use crate::archive::initialize::load_with_cache;
+    use crate::archive::integrity::{check_archive_integrity, IntegrityLevel};
     use log::info;
-    use std::path::Path;
 
-    let archive_dir = std::env::var("SCROLL_CORE_ARCHIVE_DIR").unwrap_or_else(|_| "scrolls".into());
-    let archive_path = Path::new(&archive_dir);
+    let archive_path = archive_dir();
 
     info!("🌀 Scroll Core v{} initializing...", SCROLL_CORE_VERSION);
     println!("🌀 Scroll Core v{} initializing...", SCROLL_CORE_VERSION);
 
-    let (scrolls, cache) = load_with_cache(archive_path).map_err(anyhow::Error::msg)?;
+    let (scrolls, cache) = load_with_cache(&archive_path).map_err(anyhow::Error::msg)?;
 
     info!("✅ Loaded {} scroll(s).", scrolls.len());
     println!("✅ Loaded {} scroll(s).", scrolls.len());
 
+    let level = IntegrityLevel::from_env().map_err(anyhow::Error::msg)?;
+    if level != IntegrityLevel::Off {
+        check_archive_integrity(&scrolls)
+            .enforce(level)
+            .map_err(anyhow::Error::msg)?;
+    }
+
     Ok((scrolls, cache))
 }
 /// Optional teardown hook.
@@ -89,8 +109,7 @@ pub fn validate_scroll_environment() -> bool {
         return false;
     }
 
-    let archive_dir = env::var("SCROLL_CORE_ARCHIVE_DIR").unwrap_or_else(|_| "scrolls".into());
-    match fs::read_dir(&archive_dir) {
+    match fs::read_dir(archive_dir()) {
         Ok(mut entries) => entries.next().is_some(),
         Err(_) => false,
     }
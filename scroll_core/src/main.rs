@@ -5,15 +5,24 @@
 #![warn(unused_imports)]
 
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use dotenvy::dotenv;
 use scroll_core::chat::chat_dispatcher::ChatDispatcher;
 use scroll_core::cli::{chat::run_chat, chat_db::ChatDb, theme::ThemeKind};
 use scroll_core::{
+    archive::archive_loader::{load_scrolls_from_directory, LoadOptions},
     archive::archive_memory::InMemoryArchive,
+    archive::embedding::embedder_from_env,
+    archive::full_text::TextIndexOptions,
+    archive::identity::stamp_archive_ids,
     archive::initialize::ensure_archive_dir,
+    archive::integrity::check_archive_integrity,
+    archive::schema_migration::migrate_archive,
+    archive::sqlite_archive::SqliteArchive,
+    archive::watcher::ArchiveWatcher,
     core::{
         construct_registry::ConstructRegistry,
         context_frame_engine::{ContextFrameEngine, ContextMode},
@@ -24,9 +33,11 @@ use scroll_core::{
         constructs::openai_construct::{Mythscribe, OpenAIClient},
         invocation_manager::InvocationManager,
     },
-    parser::parse_scroll,
+    parser::{parse_scroll_with_mode, ParseMode},
+    state_manager::publish_transitions_to,
     teardown_scroll_core,
     trigger_loom::emotional_state::EmotionalState,
+    validator::{RuleEngine, ValidatorSpec},
 };
 
 /// CLI flags recognised by Scroll Core.
@@ -60,9 +71,68 @@ enum Commands {
         theme: ThemeKind,
         #[arg(long = "no-banner", action = clap::ArgAction::SetTrue, default_value_t = false)]
         no_banner: bool,
+        /// Do not pick up scroll edits made while the chat is open
+        #[arg(long = "no-watch", action = clap::ArgAction::SetTrue, default_value_t = false)]
+        no_watch: bool,
+    },
+    /// Write missing scroll ids into the archive and report id collisions
+    StampIds {
+        /// Archive directory (defaults to $SCROLL_CORE_ARCHIVE_DIR or `scrolls`)
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Only report missing ids and collisions; fail if any are found
+        #[arg(long, action = clap::ArgAction::SetTrue, default_value_t = false)]
+        check: bool,
+    },
+    /// Upgrade every archive scroll header to the current schema version
+    Migrate {
+        /// Archive directory (defaults to $SCROLL_CORE_ARCHIVE_DIR or `scrolls`)
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Only report outdated scrolls; fail if any are found
+        #[arg(long, action = clap::ArgAction::SetTrue, default_value_t = false)]
+        check: bool,
+    },
+    /// Validate every archive scroll and report all findings
+    Validate {
+        /// Archive directory (defaults to $SCROLL_CORE_ARCHIVE_DIR or `scrolls`)
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Validator spec (YAML or a scroll with a `validator_spec` block);
+        /// defaults to the archive's `Scrollbook_Validator_Specs.md`
+        #[arg(long)]
+        spec: Option<String>,
+        #[arg(long, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// Check archive links for dangling targets, cycles, orphans and duplicates
+    Integrity {
+        /// Archive directory (defaults to $SCROLL_CORE_ARCHIVE_DIR or `scrolls`)
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Fail on warnings as well as errors
+        #[arg(long, action = clap::ArgAction::SetTrue, default_value_t = false)]
+        strict: bool,
+    },
+    /// Sync the archive directory into the SQLite scroll store
+    Sync {
+        /// Archive directory (defaults to $SCROLL_CORE_ARCHIVE_DIR or `scrolls`)
+        #[arg(long)]
+        archive: Option<PathBuf>,
+        /// Database file (defaults to $CHAT_DB_PATH or `scroll_core.db`)
+        #[arg(long)]
+        db: Option<String>,
     },
 }
 
+/// Output formats for `validate`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
 fn main() -> Result<()> {
     dotenv().ok();
 
@@ -79,14 +149,21 @@ fn main() -> Result<()> {
         no_stream,
         theme,
         no_banner,
+        no_watch,
     }) = &cli.command
     {
-        let archive_dir =
-            std::env::var("SCROLL_CORE_ARCHIVE_DIR").unwrap_or_else(|_| "scrolls".into());
-        ensure_archive_dir(Path::new(&archive_dir))?;
-        let (scrolls, _cache) = initialize_scroll_core()?;
-        let archive = InMemoryArchive::new(scrolls.clone());
-        let engine = ContextFrameEngine::new(&archive, ContextMode::Narrow);
+        let archive_dir = scroll_core::archive_dir();
+        ensure_archive_dir(&archive_dir)?;
+        let (mut scrolls, mut cache) = initialize_scroll_core()?;
+        let mut archive = InMemoryArchive::new(scrolls.clone());
+        archive.build_text_index(TextIndexOptions::default());
+        let index_path = std::env::var_os("SCROLL_CORE_SEMANTIC_INDEX")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| archive_dir.join("semantic_index.json"));
+        let index_path = index_path.as_path();
+        if let Err(e) = archive.load_semantic_index(index_path, embedder_from_env(&scrolls)) {
+            eprintln!("⚠️ Semantic index unavailable: {e}");
+        }
 
         let mut registry = ConstructRegistry::new();
         if std::env::var("SCROLL_CORE_USE_MOCK").is_ok() {
@@ -103,7 +180,11 @@ fn main() -> Result<()> {
         }
 
         let manager = InvocationManager::new(registry);
-        let aelren = AelrenHerald::new(engine, vec![construct.clone()]);
+        publish_transitions_to(manager.registry.bus());
+        let watch = (!*no_watch).then(|| {
+            ArchiveWatcher::new(&archive_dir, LoadOptions::default(), &scrolls)
+                .spawn(Duration::from_secs(1), Some(manager.registry.bus()))
+        });
         let rt = tokio::runtime::Runtime::new()?;
         let db_path = std::env::var("CHAT_DB_PATH").unwrap_or_else(|_| "scroll_core.db".into());
         let db = rt.block_on(ChatDb::open(&db_path))?;
@@ -111,18 +192,57 @@ fn main() -> Result<()> {
         let theme_struct = theme.styles();
         run_chat(
             &manager,
-            &aelren,
-            &scrolls,
+            &mut archive,
+            &mut scrolls,
+            &mut cache,
+            index_path,
             construct,
             stream_enabled,
             &db,
             theme_struct,
             !*no_banner,
+            watch.as_ref(),
         )?;
+        if let Err(e) = archive.save_semantic_index(index_path) {
+            eprintln!("⚠️ Failed to save semantic index: {e}");
+        }
         teardown_scroll_core();
         return Ok(());
     }
 
+    if let Some(Commands::StampIds { archive, check }) = &cli.command {
+        let archive_dir = archive.clone().unwrap_or_else(scroll_core::archive_dir);
+        return run_stamp_ids(&archive_dir, *check);
+    }
+
+    if let Some(Commands::Migrate { archive, check }) = &cli.command {
+        let archive_dir = archive.clone().unwrap_or_else(scroll_core::archive_dir);
+        return run_migrate(&archive_dir, *check);
+    }
+
+    if let Some(Commands::Validate {
+        archive,
+        spec,
+        format,
+    }) = &cli.command
+    {
+        let archive_dir = archive.clone().unwrap_or_else(scroll_core::archive_dir);
+        return run_validate(&archive_dir, spec.as_deref(), *format);
+    }
+
+    if let Some(Commands::Integrity { archive, strict }) = &cli.command {
+        let archive_dir = archive.clone().unwrap_or_else(scroll_core::archive_dir);
+        return run_integrity(&archive_dir, *strict);
+    }
+
+    if let Some(Commands::Sync { archive, db }) = &cli.command {
+        let archive_dir = archive.clone().unwrap_or_else(scroll_core::archive_dir);
+        let db_path = db.clone().unwrap_or_else(|| {
+            std::env::var("CHAT_DB_PATH").unwrap_or_else(|_| "scroll_core.db".into())
+        });
+        return run_sync(&archive_dir, &db_path);
+    }
+
     // ─── Demo path ──────────────────────────────────────────────────────────────
     if let Some(demo_path) = cli.demo {
         run_demo(&demo_path)?;
@@ -158,6 +278,135 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+// ───────────────────────────────────────────────────────────────────────────────
+// Id stamping
+// ───────────────────────────────────────────────────────────────────────────────
+fn run_stamp_ids(archive_dir: &Path, check: bool) -> Result<()> {
+    let report = stamp_archive_ids(archive_dir, !check)?;
+
+    for error in &report.failed {
+        eprint!("⚠️ {}", error.render());
+    }
+    for (path, id) in &report.stamped {
+        if check {
+            println!("missing id: {} (would stamp {id})", path.display());
+        } else {
+            println!("🔖 stamped {id} into {}", path.display());
+        }
+    }
+    for collision in &report.collisions {
+        let paths: Vec<String> = collision
+            .paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        eprintln!("❌ id {} is shared by: {}", collision.id, paths.join(", "));
+    }
+
+    if !report.collisions.is_empty() {
+        anyhow::bail!("{} id collision(s) in the archive", report.collisions.len());
+    }
+    if check && !report.stamped.is_empty() {
+        anyhow::bail!("{} scroll(s) without an id", report.stamped.len());
+    }
+    Ok(())
+}
+
+// ───────────────────────────────────────────────────────────────────────────────
+// Schema migration
+// ───────────────────────────────────────────────────────────────────────────────
+fn run_migrate(archive_dir: &Path, check: bool) -> Result<()> {
+    let report = migrate_archive(archive_dir, !check)?;
+
+    for error in &report.failed {
+        eprint!("⚠️ {}", error.render());
+    }
+    for (path, from) in &report.upgraded {
+        let to = scroll_core::parser::CURRENT_SCHEMA_VERSION;
+        if check {
+            println!("outdated: {} (schema {from}, current {to})", path.display());
+        } else {
+            println!("📜 upgraded {} from schema {from} to {to}", path.display());
+        }
+    }
+    println!(
+        "{} upgraded, {} already current, {} failed",
+        report.upgraded.len(),
+        report.current.len(),
+        report.failed.len()
+    );
+
+    if check && !report.upgraded.is_empty() {
+        anyhow::bail!("{} scroll(s) need migration", report.upgraded.len());
+    }
+    Ok(())
+}
+
+// ───────────────────────────────────────────────────────────────────────────────
+// Validation
+// ───────────────────────────────────────────────────────────────────────────────
+fn run_validate(archive_dir: &Path, spec: Option<&str>, format: ReportFormat) -> Result<()> {
+    let default_spec = archive_dir.join("Scrollbook_Validator_Specs.md");
+    let spec = match spec {
+        Some(path) => ValidatorSpec::from_file(Path::new(path)).map_err(anyhow::Error::msg)?,
+        None if default_spec.exists() => {
+            ValidatorSpec::from_file(&default_spec).map_err(anyhow::Error::msg)?
+        }
+        None => ValidatorSpec::default(),
+    };
+    let report = RuleEngine::from_spec(&spec).validate_directory(archive_dir)?;
+
+    match format {
+        ReportFormat::Text => print!("{}", report.render_text()),
+        ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report.to_json())?),
+        ReportFormat::Sarif => println!("{}", serde_json::to_string_pretty(&report.to_sarif())?),
+    }
+
+    if report.has_errors() {
+        anyhow::bail!(
+            "{} validation error(s)",
+            report.count(scroll_core::validator::Severity::Error)
+        );
+    }
+    Ok(())
+}
+
+// ───────────────────────────────────────────────────────────────────────────────
+// Link integrity
+// ───────────────────────────────────────────────────────────────────────────────
+fn run_integrity(archive_dir: &Path, strict: bool) -> Result<()> {
+    let mut scrolls = load_scrolls_from_directory(archive_dir).map_err(anyhow::Error::msg)?;
+    scrolls.sort_by(|a, b| a.title.cmp(&b.title).then(a.id.cmp(&b.id)));
+    let report = check_archive_integrity(&scrolls);
+    print!("{}", report.render_text());
+
+    if report.has_errors() {
+        anyhow::bail!("{} integrity error(s)", report.error_count());
+    }
+    if strict && !report.issues.is_empty() {
+        anyhow::bail!("{} integrity warning(s)", report.issues.len());
+    }
+    Ok(())
+}
+
+// ───────────────────────────────────────────────────────────────────────────────
+// SQLite archive sync
+// ───────────────────────────────────────────────────────────────────────────────
+fn run_sync(archive_dir: &Path, db_path: &str) -> Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+    let report = rt.block_on(async {
+        let mut archive = SqliteArchive::open(db_path).await?;
+        archive
+            .sync_from_directory(archive_dir, &LoadOptions::default())
+            .await
+    })?;
+    for failure in &report.failed {
+        eprint!("⚠️ {}", failure.error.render());
+    }
+    println!("🗄️ {}", report.summary());
+    Ok(())
+}
+
 // ───────────────────────────────────────────────────────────────────────────────
 // Demo helper
 // ───────────────────────────────────────────────────────────────────────────────
@@ -169,7 +418,7 @@ fn run_demo<P: AsRef<std::path::Path>>(path: P) -> Result<()> {
 
     // 2️⃣  load demo scroll the same way
     let raw = std::fs::read_to_string(path)?;
-    let demo_scroll = parse_scroll(&raw)?;
+    let demo_scroll = parse_scroll_with_mode(&raw, ParseMode::Lenient)?;
     scrolls.push(demo_scroll.clone());
 
     // 3️⃣  tiny runtime
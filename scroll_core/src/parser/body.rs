@@ -0,0 +1,396 @@
+//! Structured view of a scroll's markdown body: a heading tree whose sections
+//! hold paragraphs, blockquotes and fenced code blocks. Constructs and the
+//! validator use it to address a section by anchor or pull out embedded
+//! `yaml` blocks without pattern matching on the raw text.
+// src/parser/body.rs
+
+use serde::de::DeserializeOwned;
+
+/// A markdown body split into the content before the first heading and a
+/// tree of sections.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScrollBody {
+    pub preamble: Vec<Block>,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    /// 1 for `#`, up to 6 for `######`.
+    pub level: u8,
+    pub text: String,
+    /// Slug of `text`, suffixed with `-1`, `-2`, … when repeated.
+    pub anchor: String,
+    /// 1-based line of the heading within the body.
+    pub line: usize,
+}
+
+/// A heading with its direct content and the deeper headings below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub heading: Heading,
+    pub blocks: Vec<Block>,
+    pub subsections: Vec<Section>,
+    /// Raw markdown from the heading line up to the next heading of the same
+    /// or a higher level.
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Paragraph(String),
+    /// Blockquote text with the `>` markers removed.
+    Quote(String),
+    Code(CodeBlock),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeBlock {
+    /// First word of the info string, e.g. `yaml` for ```` ```yaml ````.
+    pub language: Option<String>,
+    pub info: String,
+    pub content: String,
+    /// 1-based line of the opening fence within the body.
+    pub line: usize,
+    /// False when the fence runs to the end of the body unclosed.
+    pub closed: bool,
+}
+
+impl CodeBlock {
+    /// Deserializes the block content as YAML.
+    pub fn parse_yaml<T: DeserializeOwned>(&self) -> Result<T, serde_yaml::Error> {
+        serde_yaml::from_str(&self.content)
+    }
+
+    pub fn is_language(&self, language: &str) -> bool {
+        self.language
+            .as_deref()
+            .is_some_and(|l| l.eq_ignore_ascii_case(language))
+    }
+}
+
+impl ScrollBody {
+    pub fn parse(markdown: &str) -> Self {
+        parse_body(markdown)
+    }
+
+    /// Every section in document order, nested ones included.
+    pub fn all_sections(&self) -> Vec<&Section> {
+        let mut out = Vec::new();
+        for section in &self.sections {
+            section.collect_sections(&mut out);
+        }
+        out
+    }
+
+    /// Finds a section by anchor or heading text. Leading `#`s, case and
+    /// punctuation are ignored, so `# II. VALIDATION BLOCK STRUCTURE` and
+    /// `ii-validation-block-structure` address the same section.
+    pub fn section(&self, query: &str) -> Option<&Section> {
+        let trimmed = query.trim().trim_start_matches('#').trim();
+        let wanted = slugify(trimmed);
+        self.all_sections()
+            .into_iter()
+            .find(|s| s.heading.anchor == trimmed || s.heading.anchor == wanted)
+    }
+
+    /// Every block in document order.
+    pub fn blocks(&self) -> Vec<&Block> {
+        let mut out: Vec<&Block> = self.preamble.iter().collect();
+        for section in &self.sections {
+            section.collect_blocks(&mut out);
+        }
+        out
+    }
+
+    pub fn code_blocks(&self) -> Vec<&CodeBlock> {
+        code_blocks(self.blocks())
+    }
+
+    /// Code blocks tagged `yaml` or `yml`.
+    pub fn yaml_blocks(&self) -> Vec<&CodeBlock> {
+        self.code_blocks()
+            .into_iter()
+            .filter(|b| b.is_language("yaml") || b.is_language("yml"))
+            .collect()
+    }
+
+    pub fn quotes(&self) -> Vec<&str> {
+        self.blocks()
+            .into_iter()
+            .filter_map(|block| match block {
+                Block::Quote(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Section {
+    /// Direct and nested blocks in document order.
+    pub fn all_blocks(&self) -> Vec<&Block> {
+        let mut out = Vec::new();
+        self.collect_blocks(&mut out);
+        out
+    }
+
+    /// Code blocks in this section and its subsections.
+    pub fn code_blocks(&self) -> Vec<&CodeBlock> {
+        code_blocks(self.all_blocks())
+    }
+
+    fn collect_sections<'a>(&'a self, out: &mut Vec<&'a Section>) {
+        out.push(self);
+        for section in &self.subsections {
+            section.collect_sections(out);
+        }
+    }
+
+    fn collect_blocks<'a>(&'a self, out: &mut Vec<&'a Block>) {
+        out.extend(self.blocks.iter());
+        for section in &self.subsections {
+            section.collect_blocks(out);
+        }
+    }
+}
+
+fn code_blocks(blocks: Vec<&Block>) -> Vec<&CodeBlock> {
+    blocks
+        .into_iter()
+        .filter_map(|block| match block {
+            Block::Code(code) => Some(code),
+            _ => None,
+        })
+        .collect()
+}
+
+/// GitHub-style heading slug: lowercase, punctuation dropped, spaces become
+/// dashes. Leading and trailing dashes are trimmed.
+pub fn slugify(text: &str) -> String {
+    let slug: String = text
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+/// A heading with its direct blocks, before sections are nested.
+struct FlatSection {
+    heading: Heading,
+    start: usize,
+    blocks: Vec<Block>,
+}
+
+/// Line-based parser for the markdown subset scrolls use: ATX headings,
+/// fenced code blocks (``` or ~~~), blockquotes and paragraphs.
+pub fn parse_body(markdown: &str) -> ScrollBody {
+    let lines: Vec<(usize, &str)> = markdown
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line.trim_end_matches(['\n', '\r'])))
+        })
+        .collect();
+
+    let mut preamble = Vec::new();
+    let mut flat: Vec<FlatSection> = Vec::new();
+    let mut anchors: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut quote: Vec<&str> = Vec::new();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let (offset, line) = lines[i];
+        let heading = parse_heading(line);
+        let fence = parse_fence(line);
+        let quoted = strip_quote(line);
+
+        if heading.is_some() || fence.is_some() || quoted.is_none() {
+            flush(&mut quote, Block::Quote, &mut flat, &mut preamble);
+        }
+        if heading.is_some() || fence.is_some() || quoted.is_some() || line.trim().is_empty() {
+            flush(&mut paragraph, Block::Paragraph, &mut flat, &mut preamble);
+        }
+
+        if let Some((level, text)) = heading {
+            let anchor = unique_anchor(&mut anchors, slugify(&text));
+            flat.push(FlatSection {
+                heading: Heading {
+                    level,
+                    text,
+                    anchor,
+                    line: i + 1,
+                },
+                start: offset,
+                blocks: Vec::new(),
+            });
+        } else if let Some((marker, count, info)) = fence {
+            let open_line = i + 1;
+            let mut content = String::new();
+            let mut closed = false;
+            i += 1;
+            while i < lines.len() {
+                if is_closing_fence(lines[i].1, marker, count) {
+                    closed = true;
+                    break;
+                }
+                content.push_str(lines[i].1);
+                content.push('\n');
+                i += 1;
+            }
+            let block = Block::Code(CodeBlock {
+                language: info.split_whitespace().next().map(str::to_string),
+                info,
+                content,
+                line: open_line,
+                closed,
+            });
+            push_block(block, &mut flat, &mut preamble);
+        } else if let Some(text) = quoted {
+            quote.push(text);
+        } else if !line.trim().is_empty() {
+            paragraph.push(line.trim());
+        }
+        i += 1;
+    }
+    flush(&mut quote, Block::Quote, &mut flat, &mut preamble);
+    flush(&mut paragraph, Block::Paragraph, &mut flat, &mut preamble);
+
+    ScrollBody {
+        preamble,
+        sections: nest(markdown, flat),
+    }
+}
+
+fn push_block(block: Block, flat: &mut [FlatSection], preamble: &mut Vec<Block>) {
+    match flat.last_mut() {
+        Some(section) => section.blocks.push(block),
+        None => preamble.push(block),
+    }
+}
+
+fn flush(
+    lines: &mut Vec<&str>,
+    make: fn(String) -> Block,
+    flat: &mut [FlatSection],
+    preamble: &mut Vec<Block>,
+) {
+    if !lines.is_empty() {
+        push_block(make(lines.join("\n")), flat, preamble);
+        lines.clear();
+    }
+}
+
+/// Builds the section tree; each section's markdown runs until the next
+/// heading of the same or a higher level.
+fn nest(markdown: &str, flat: Vec<FlatSection>) -> Vec<Section> {
+    let ends: Vec<usize> = (0..flat.len())
+        .map(|i| {
+            flat[i + 1..]
+                .iter()
+                .find(|next| next.heading.level <= flat[i].heading.level)
+                .map_or(markdown.len(), |next| next.start)
+        })
+        .collect();
+
+    let mut roots: Vec<Section> = Vec::new();
+    let mut stack: Vec<Section> = Vec::new();
+    for (section, end) in flat.into_iter().zip(ends) {
+        while stack
+            .last()
+            .is_some_and(|top| top.heading.level >= section.heading.level)
+        {
+            let done = stack.pop().expect("stack is non-empty");
+            attach(done, &mut stack, &mut roots);
+        }
+        stack.push(Section {
+            markdown: markdown[section.start..end].to_string(),
+            heading: section.heading,
+            blocks: section.blocks,
+            subsections: Vec::new(),
+        });
+    }
+    while let Some(done) = stack.pop() {
+        attach(done, &mut stack, &mut roots);
+    }
+    roots
+}
+
+fn attach(section: Section, stack: &mut [Section], roots: &mut Vec<Section>) {
+    match stack.last_mut() {
+        Some(parent) => parent.subsections.push(section),
+        None => roots.push(section),
+    }
+}
+
+fn unique_anchor(seen: &mut Vec<String>, slug: String) -> String {
+    let repeats = seen.iter().filter(|s| **s == slug).count();
+    seen.push(slug.clone());
+    if repeats == 0 {
+        slug
+    } else {
+        format!("{slug}-{repeats}")
+    }
+}
+
+/// Strips up to three spaces of indentation, as CommonMark allows for
+/// block markers.
+fn block_start(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start_matches(' ');
+    (line.len() - trimmed.len() <= 3).then_some(trimmed)
+}
+
+fn parse_heading(line: &str) -> Option<(u8, String)> {
+    let line = block_start(line)?;
+    let level = line.bytes().take_while(|b| *b == b'#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    // Closing hashes are only syntax when separated by a space.
+    let text = rest.trim();
+    let stripped = text.trim_end_matches('#');
+    let text = if stripped.is_empty() || stripped.ends_with([' ', '\t']) {
+        stripped.trim_end()
+    } else {
+        text
+    };
+    Some((level as u8, text.to_string()))
+}
+
+fn parse_fence(line: &str) -> Option<(char, usize, String)> {
+    let line = block_start(line)?;
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let count = line.chars().take_while(|c| *c == marker).count();
+    if count < 3 {
+        return None;
+    }
+    let info = line[count..].trim();
+    if marker == '`' && info.contains('`') {
+        return None;
+    }
+    Some((marker, count, info.to_string()))
+}
+
+fn is_closing_fence(line: &str, marker: char, count: usize) -> bool {
+    block_start(line).is_some_and(|line| {
+        let line = line.trim_end();
+        line.chars().take_while(|c| *c == marker).count() >= count
+            && line.chars().all(|c| c == marker)
+    })
+}
+
+fn strip_quote(line: &str) -> Option<&str> {
+    let rest = block_start(line)?.strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest).trim_end())
+}
@@ -0,0 +1,150 @@
+//! Typed errors produced while parsing scroll files.
+//! Each error carries the source path and a line/column span in the original
+//! file so it can be rendered as a rustc-style diagnostic.
+// src/parser/error.rs
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use super::format::FrontMatterFormat;
+
+/// Location of a problem in the original scroll file (1-based).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    /// Number of characters to underline; at least one.
+    pub len: usize,
+}
+
+impl Span {
+    /// Builds a span from a byte offset into `source`. An offset inside a
+    /// multi-byte character points at the start of that character.
+    pub fn from_offset(source: &str, offset: usize, len: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = source[line_start..offset].chars().count() + 1;
+        Self {
+            line,
+            column,
+            len: len.max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParseErrorKind {
+    #[error("missing front-matter delimiters")]
+    MissingDelimiter,
+    #[error("invalid YAML: {0}")]
+    Yaml(String),
+    #[error("invalid {format} front matter: {message}")]
+    Format {
+        format: FrontMatterFormat,
+        message: String,
+    },
+    #[error("unknown field `{0}`")]
+    UnknownField(String),
+    #[error("validation failed: {0}")]
+    Validation(String),
+    #[error("could not read scroll: {0}")]
+    Io(String),
+}
+
+/// A scroll parse failure with enough context to point at the offending text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub path: Option<PathBuf>,
+    pub span: Option<Span>,
+    /// The full source line the span points into.
+    pub snippet: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind) -> Self {
+        Self {
+            kind,
+            path: None,
+            span: None,
+            snippet: None,
+        }
+    }
+
+    /// Attaches a span and captures the matching line of `source` as snippet.
+    pub fn at(mut self, source: &str, span: Span) -> Self {
+        self.snippet = source.lines().nth(span.line - 1).map(str::to_string);
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_path(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Renders the error as a multi-line diagnostic in the style of rustc:
+    ///
+    /// ```text
+    /// error: unknown field `scroll_typ`
+    ///  --> scrolls/example.md:3:1
+    ///   |
+    /// 3 | scroll_typ: Canon
+    ///   | ^^^^^^^^^^
+    /// ```
+    pub fn render(&self) -> String {
+        let mut out = format!("error: {}\n", self.kind);
+        let file = self
+            .path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "<input>".into());
+
+        let Some(span) = self.span else {
+            let _ = writeln!(out, " --> {file}");
+            return out;
+        };
+
+        let gutter = " ".repeat(span.line.to_string().len());
+        let _ = writeln!(out, "{gutter}--> {file}:{}:{}", span.line, span.column);
+        if let Some(snippet) = &self.snippet {
+            let pad: String = snippet
+                .chars()
+                .take(span.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let _ = writeln!(out, "{gutter} |");
+            let _ = writeln!(out, "{} | {}", span.line, snippet);
+            let _ = writeln!(out, "{gutter} | {pad}{}", "^".repeat(span.len));
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.path, self.span) {
+            (Some(path), Some(span)) => write!(
+                f,
+                "{}:{}:{}: {}",
+                path.display(),
+                span.line,
+                span.column,
+                self.kind
+            ),
+            (Some(path), None) => write!(f, "{}: {}", path.display(), self.kind),
+            (None, Some(span)) => write!(f, "{}:{}: {}", span.line, span.column, self.kind),
+            (None, None) => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
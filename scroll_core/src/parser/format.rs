@@ -0,0 +1,271 @@
+//! Front-matter formats recognised at the top of a scroll file.
+//! The format is detected from the opening delimiter: `---` YAML, `+++` TOML,
+//! `;;;` fenced JSON or a bare `{ ... }` JSON object. Delimiters only count at
+//! the start of a line, so horizontal rules in the body are left alone.
+// src/parser/format.rs
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+/// Syntax of a scroll's front matter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterFormat {
+    /// `---` fenced YAML.
+    #[default]
+    Yaml,
+    /// Hugo-style `+++` fenced TOML.
+    Toml,
+    /// `;;;` fenced JSON.
+    Json,
+    /// A bare JSON object starting on the first line, closed by its matching brace.
+    JsonObject,
+}
+
+impl fmt::Display for FrontMatterFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            FrontMatterFormat::Yaml => "YAML",
+            FrontMatterFormat::Toml => "TOML",
+            FrontMatterFormat::Json | FrontMatterFormat::JsonObject => "JSON",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A front-matter syntax error, with a byte offset into the header text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SyntaxError {
+    pub message: String,
+    pub offset: Option<usize>,
+}
+
+/// Why a file could not be split into front matter and body, with the byte
+/// offset and length of the text to point at.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SplitError {
+    /// The first content line is not an opening delimiter.
+    NoOpening { offset: usize, len: usize },
+    /// The opening delimiter is never closed.
+    Unclosed { offset: usize, len: usize },
+}
+
+/// The raw pieces of a scroll file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RawFrontMatter<'a> {
+    pub format: FrontMatterFormat,
+    /// Header text between the delimiters, without the delimiter lines.
+    pub header: &'a str,
+    /// Byte offset of `header` in the file.
+    pub header_offset: usize,
+    /// Byte offset and length of the opening delimiter.
+    pub open_offset: usize,
+    pub open_len: usize,
+    pub body: &'a str,
+}
+
+impl FrontMatterFormat {
+    /// The fence line for delimited formats.
+    pub fn delimiter(self) -> Option<&'static str> {
+        match self {
+            FrontMatterFormat::Yaml => Some("---"),
+            FrontMatterFormat::Toml => Some("+++"),
+            FrontMatterFormat::Json => Some(";;;"),
+            FrontMatterFormat::JsonObject => None,
+        }
+    }
+
+    fn detect(line: &str) -> Option<Self> {
+        match line {
+            "---" => Some(FrontMatterFormat::Yaml),
+            "+++" => Some(FrontMatterFormat::Toml),
+            ";;;" => Some(FrontMatterFormat::Json),
+            _ if line.starts_with('{') => Some(FrontMatterFormat::JsonObject),
+            _ => None,
+        }
+    }
+
+    /// Decodes header text into a YAML value so every format shares one
+    /// normalization and decoding path.
+    pub(crate) fn decode(self, text: &str) -> Result<Value, SyntaxError> {
+        match self {
+            FrontMatterFormat::Yaml => serde_yaml::from_str(text).map_err(|e| SyntaxError {
+                message: e.to_string(),
+                offset: e.location().map(|l| l.index()),
+            }),
+            FrontMatterFormat::Toml => text
+                .parse::<toml::Table>()
+                .map(|table| toml_to_yaml(toml::Value::Table(table)))
+                .map_err(|e| SyntaxError {
+                    message: e.message().to_string(),
+                    offset: e.span().map(|s| s.start),
+                }),
+            FrontMatterFormat::Json | FrontMatterFormat::JsonObject => {
+                serde_json::from_str::<serde_json::Value>(text)
+                    .map_err(|e| SyntaxError {
+                        offset: line_column_offset(text, e.line(), e.column()),
+                        message: e.to_string(),
+                    })
+                    .and_then(|json| {
+                        serde_yaml::to_value(json).map_err(|e| SyntaxError {
+                            message: e.to_string(),
+                            offset: None,
+                        })
+                    })
+            }
+        }
+    }
+
+    /// Serializes a header in this format, delimiters included.
+    pub(crate) fn render<T: Serialize>(self, header: &T) -> Result<String, String> {
+        match self {
+            FrontMatterFormat::Yaml => serde_yaml::to_string(header)
+                .map(|text| format!("---\n{text}---\n"))
+                .map_err(|e| e.to_string()),
+            FrontMatterFormat::Toml => toml::to_string(header)
+                .map(|text| format!("+++\n{text}+++\n"))
+                .map_err(|e| e.to_string()),
+            FrontMatterFormat::Json => serde_json::to_string_pretty(header)
+                .map(|text| format!(";;;\n{text}\n;;;\n"))
+                .map_err(|e| e.to_string()),
+            FrontMatterFormat::JsonObject => serde_json::to_string_pretty(header)
+                .map(|text| format!("{text}\n"))
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// The line that declares `id` at the top of a header in this format.
+    pub(crate) fn id_line(self, id: &str) -> String {
+        match self {
+            FrontMatterFormat::Yaml => format!("id: {id}\n"),
+            FrontMatterFormat::Toml => format!("id = \"{id}\"\n"),
+            FrontMatterFormat::Json | FrontMatterFormat::JsonObject => {
+                format!("  \"id\": \"{id}\",\n")
+            }
+        }
+    }
+}
+
+/// Splits a scroll file into front matter and body. Blank lines may precede
+/// the opening delimiter, and so may `#` comment lines directly above it; a
+/// `#` line followed by a blank line is a markdown heading, so the file has
+/// no front matter.
+pub(crate) fn split(input: &str) -> Result<RawFrontMatter<'_>, SplitError> {
+    let mut offset = 0;
+    let mut comment: Option<SplitError> = None;
+    let mut lines = input.split_inclusive('\n');
+    let (open_offset, open_line, format) = loop {
+        let Some(line) = lines.next() else {
+            return Err(SplitError::NoOpening { offset: 0, len: 1 });
+        };
+        let trimmed = line.trim_end();
+        if trimmed.trim_start().is_empty() {
+            if let Some(heading) = comment {
+                return Err(heading);
+            }
+            offset += line.len();
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            comment.get_or_insert(SplitError::NoOpening {
+                offset,
+                len: trimmed.chars().count(),
+            });
+            offset += line.len();
+            continue;
+        }
+        match FrontMatterFormat::detect(trimmed) {
+            Some(format) => break (offset, line, format),
+            None => {
+                return Err(SplitError::NoOpening {
+                    offset,
+                    len: trimmed.chars().count(),
+                })
+            }
+        }
+    };
+
+    let Some(delimiter) = format.delimiter() else {
+        return split_json_object(input, open_offset);
+    };
+    let unclosed = SplitError::Unclosed {
+        offset: open_offset,
+        len: delimiter.len(),
+    };
+
+    let header_offset = open_offset + open_line.len();
+    let mut cursor = header_offset;
+    for line in lines {
+        if line.trim_end() == delimiter {
+            return Ok(RawFrontMatter {
+                format,
+                header: &input[header_offset..cursor],
+                header_offset,
+                open_offset,
+                open_len: delimiter.len(),
+                body: &input[cursor + line.len()..],
+            });
+        }
+        cursor += line.len();
+    }
+    Err(unclosed)
+}
+
+/// A bare JSON object ends at its matching brace; the rest of that line is
+/// dropped and the body starts on the next one.
+fn split_json_object(input: &str, open_offset: usize) -> Result<RawFrontMatter<'_>, SplitError> {
+    let rest = &input[open_offset..];
+    let mut stream = serde_json::Deserializer::from_str(rest).into_iter::<serde_json::Value>();
+    let end = match stream.next() {
+        Some(Ok(_)) => stream.byte_offset(),
+        _ => {
+            return Err(SplitError::Unclosed {
+                offset: open_offset,
+                len: 1,
+            })
+        }
+    };
+    let after = &rest[end..];
+    let body = after.find('\n').map(|idx| &after[idx + 1..]).unwrap_or("");
+    Ok(RawFrontMatter {
+        format: FrontMatterFormat::JsonObject,
+        header: &rest[..end],
+        header_offset: open_offset,
+        open_offset,
+        open_len: 1,
+        body,
+    })
+}
+
+/// Converts TOML to YAML values; datetimes become RFC 3339 strings.
+fn toml_to_yaml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.into_iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .into_iter()
+                .map(|(k, v)| (Value::String(k), toml_to_yaml(v)))
+                .collect::<Mapping>(),
+        ),
+    }
+}
+
+/// Byte offset of a 1-based line/column position, as reported by serde_json.
+fn line_column_offset(text: &str, line: usize, column: usize) -> Option<usize> {
+    if line == 0 {
+        return None;
+    }
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(line - 1)
+        .map(str::len)
+        .sum();
+    Some((line_start + column.saturating_sub(1)).min(text.len()))
+}
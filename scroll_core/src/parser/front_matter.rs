@@ -0,0 +1,157 @@
+//! Typed view of a scroll header as it appears between the front-matter delimiters.
+//! Both strict and lenient parsing decode into this shape before a `Scroll` is built,
+//! and `ScrollWriter` serializes it back so every `Scroll` field survives a round trip.
+// src/parser/front_matter.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Mapping;
+use uuid::Uuid;
+
+use super::migrate::CURRENT_SCHEMA_VERSION;
+use crate::schema::{EmotionSignature, ScrollStatus, ScrollType, YamlMetadata};
+use crate::scroll::{Scroll, ScrollLink};
+
+/// One entry of `linked_scrolls`: either a resolved link or a raw reference
+/// (file name or title) that the archive loader resolves later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LinkEntry {
+    Resolved(ScrollLink),
+    Reference(String),
+}
+
+/// Header fields understood by the parser, in the order the writer emits them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrontMatter {
+    /// Header schema the file was written against; see `parser::migrate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+    pub title: String,
+    pub scroll_type: ScrollType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ScrollStatus>,
+    pub emotion_signature: EmotionSignature,
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archetype: Option<String>,
+    #[serde(default)]
+    pub quorum_required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sigil: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_phrase: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linked_scrolls: Vec<LinkEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authored_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// Every other header key, see `YamlMetadata::extensions`.
+    #[serde(flatten, default, skip_serializing_if = "Mapping::is_empty")]
+    pub extensions: Mapping,
+}
+
+impl FrontMatter {
+    /// Keys declared by the schema; any other key lands in `extensions`.
+    pub const FIELDS: &'static [&'static str] = &[
+        "schema_version",
+        "id",
+        "title",
+        "scroll_type",
+        "status",
+        "emotion_signature",
+        "tags",
+        "archetype",
+        "quorum_required",
+        "sigil",
+        "invocation_phrase",
+        "linked_scrolls",
+        "authored_by",
+        "created",
+        "last_modified",
+        "file_path",
+    ];
+
+    /// Captures every persisted field of `scroll`. Scroll-level fields win over
+    /// their `yaml_metadata` mirrors, and `origin.last_modified` is authoritative.
+    /// Links the resolver derived from references are left out; the header
+    /// keeps the references themselves.
+    pub fn from_scroll(scroll: &Scroll) -> Self {
+        let linked_scrolls = scroll
+            .linked_scrolls
+            .iter()
+            .filter(|link| link.source.is_none())
+            .cloned()
+            .map(LinkEntry::Resolved)
+            .chain(
+                scroll
+                    .yaml_metadata
+                    .linked_scrolls
+                    .iter()
+                    .cloned()
+                    .map(LinkEntry::Reference),
+            )
+            .collect();
+
+        Self {
+            schema_version: Some(CURRENT_SCHEMA_VERSION),
+            id: Some(scroll.id),
+            title: scroll.title.clone(),
+            scroll_type: scroll.scroll_type.clone(),
+            status: Some(scroll.status.clone()),
+            emotion_signature: scroll.emotion_signature.clone(),
+            tags: scroll.tags.clone(),
+            archetype: scroll.archetype.clone(),
+            quorum_required: scroll.quorum_required,
+            sigil: Some(scroll.sigil.clone()),
+            invocation_phrase: Some(scroll.invocation_phrase.clone()),
+            linked_scrolls,
+            authored_by: scroll.origin.authored_by.clone(),
+            created: Some(scroll.origin.created),
+            last_modified: Some(scroll.origin.last_modified),
+            file_path: scroll.yaml_metadata.file_path.clone(),
+            extensions: scroll.yaml_metadata.extensions.clone(),
+        }
+    }
+
+    /// Links in the header that already point at a scroll id.
+    pub fn resolved_links(&self) -> Vec<ScrollLink> {
+        self.linked_scrolls
+            .iter()
+            .filter_map(|entry| match entry {
+                LinkEntry::Resolved(link) => Some(link.clone()),
+                LinkEntry::Reference(_) => None,
+            })
+            .collect()
+    }
+
+    /// Splits out the portion of the header that is stored on `YamlMetadata`.
+    pub fn metadata(&self) -> YamlMetadata {
+        YamlMetadata {
+            title: self.title.clone(),
+            scroll_type: self.scroll_type.clone(),
+            emotion_signature: self.emotion_signature.clone(),
+            tags: self.tags.clone(),
+            archetype: self.archetype.clone(),
+            quorum_required: self.quorum_required,
+            last_modified: self.last_modified,
+            file_path: self.file_path.clone(),
+            linked_scrolls: self
+                .linked_scrolls
+                .iter()
+                .filter_map(|entry| match entry {
+                    LinkEntry::Reference(reference) => Some(reference.clone()),
+                    LinkEntry::Resolved(_) => None,
+                })
+                .collect(),
+            extensions: self.extensions.clone(),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Lenient header normalization for scrolls written by hand in the archive.
+//! Rewrites loosely typed values (lowercase types, `tone // resonance` strings,
+//! bare strings for lists) into the shape expected by `FrontMatter`.
+// src/parser/lenient.rs
+
+use serde_yaml::{Mapping, Value};
+
+use crate::schema::{EmotionSignature, ScrollStatus, ScrollType};
+use crate::type_registry::TypeRegistry;
+
+/// A header value that could not be coerced, tagged with its top-level key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub(crate) fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Normalizes a header mapping in place so it decodes as `FrontMatter`.
+///
+/// `body` is used to recover a title from the first markdown heading and
+/// `fallback_title` (usually the file stem) is used when there is none.
+pub fn normalize(
+    header: &mut Mapping,
+    body: &str,
+    fallback_title: Option<&str>,
+) -> Result<(), FieldError> {
+    for (alias, key) in [("invocation", "invocation_phrase"), ("type", "scroll_type")] {
+        if !header.contains_key(key) {
+            if let Some(value) = header.remove(alias) {
+                header.insert(key.into(), value);
+            }
+        }
+    }
+
+    normalize_title(header, body, fallback_title)?;
+
+    let scroll_type = match header.get("scroll_type") {
+        None | Some(Value::Null) => ScrollType::default(),
+        Some(Value::String(s)) => s
+            .parse::<ScrollType>()
+            .map_err(|e| FieldError::new("scroll_type", e))?,
+        Some(_) => return Err(FieldError::new("scroll_type", "expected a string")),
+    };
+    header.insert("scroll_type".into(), to_value(&scroll_type));
+
+    match header.get("status") {
+        None | Some(Value::Null) => {
+            header.remove("status");
+        }
+        Some(Value::String(s)) => {
+            let status = s
+                .parse::<ScrollStatus>()
+                .map_err(|e| FieldError::new("status", e))?;
+            header.insert("status".into(), to_value(&status));
+        }
+        Some(_) => return Err(FieldError::new("status", "expected a string")),
+    }
+
+    let emotion = match header.remove("emotion_signature") {
+        None | Some(Value::Null) => TypeRegistry::global()
+            .default_emotion(&scroll_type)
+            .unwrap_or_else(EmotionSignature::neutral),
+        Some(Value::String(s)) => s
+            .parse::<EmotionSignature>()
+            .map_err(|e| FieldError::new("emotion_signature", e))?,
+        Some(Value::Mapping(mut m)) => {
+            if !m.contains_key("emphasis") {
+                m.insert("emphasis".into(), Value::from(0.0));
+            }
+            for key in ["tone", "resonance"] {
+                if !m.contains_key(key) {
+                    m.insert(key.into(), Value::from(""));
+                }
+            }
+            serde_yaml::from_value(Value::Mapping(m))
+                .map_err(|e| FieldError::new("emotion_signature", e.to_string()))?
+        }
+        Some(_) => {
+            return Err(FieldError::new(
+                "emotion_signature",
+                "expected a string or mapping",
+            ))
+        }
+    };
+    header.insert("emotion_signature".into(), to_value(&emotion));
+
+    for key in ["tags", "linked_scrolls"] {
+        // Resolved links are written as `{ target, link_type }` mappings.
+        let allow_mappings = key == "linked_scrolls";
+        let list =
+            string_list(header.remove(key), allow_mappings).map_err(|e| FieldError::new(key, e))?;
+        header.insert(key.into(), Value::Sequence(list));
+    }
+
+    for key in [
+        "sigil",
+        "invocation_phrase",
+        "archetype",
+        "file_path",
+        "authored_by",
+    ] {
+        match header.get(key) {
+            Some(Value::Null) => {
+                header.remove(key);
+            }
+            Some(Value::Bool(_)) | Some(Value::Number(_)) => {
+                let text = scalar_to_string(&header[key]);
+                header.insert(key.into(), Value::String(text));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(Value::String(flag)) = header.get("quorum_required") {
+        let flag = flag.trim().eq_ignore_ascii_case("true") || flag.trim() == "yes";
+        header.insert("quorum_required".into(), Value::Bool(flag));
+    }
+
+    Ok(())
+}
+
+fn normalize_title(
+    header: &mut Mapping,
+    body: &str,
+    fallback_title: Option<&str>,
+) -> Result<(), FieldError> {
+    let title = match header.get("title") {
+        Some(Value::String(s)) if !s.trim().is_empty() => return Ok(()),
+        Some(Value::Number(_)) | Some(Value::Bool(_)) => scalar_to_string(&header["title"]),
+        _ => first_heading(body)
+            .or_else(|| fallback_title.map(str::to_string))
+            .ok_or_else(|| {
+                FieldError::new(
+                    "title",
+                    "scroll has no title and no heading to derive one from",
+                )
+            })?,
+    };
+    header.insert("title".into(), Value::String(title));
+    Ok(())
+}
+
+/// Returns the text of the first ATX heading in a markdown body.
+fn first_heading(body: &str) -> Option<String> {
+    body.lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+fn string_list(value: Option<Value>, allow_mappings: bool) -> Result<Vec<Value>, String> {
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::String(s)) => Ok(s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Value::from)
+            .collect()),
+        Some(Value::Sequence(items)) => items
+            .into_iter()
+            .filter(|v| !v.is_null())
+            .map(|v| match v {
+                Value::String(_) => Ok(v),
+                Value::Bool(_) | Value::Number(_) => Ok(Value::String(scalar_to_string(&v))),
+                Value::Mapping(_) if allow_mappings => Ok(v),
+                _ => Err("expected a list of strings".to_string()),
+            })
+            .collect(),
+        Some(_) => Err("expected a list of strings".to_string()),
+    }
+}
+
+fn scalar_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Value {
+    serde_yaml::to_value(value).expect("schema types serialize to YAML")
+}
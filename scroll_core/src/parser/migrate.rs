@@ -0,0 +1,137 @@
+//! Step-by-step upgrades of older scroll headers to the current schema.
+//! Each migration lifts a header from one `schema_version` to the next;
+//! headers without a version are treated as version 0.
+// src/parser/migrate.rs
+
+use serde_yaml::{Mapping, Value};
+
+use super::lenient::FieldError;
+use crate::schema::{EmotionSignature, ScrollType};
+
+/// Version written by `ScrollWriter` and expected after migration.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+const VERSION_KEY: &str = "schema_version";
+
+/// One upgrade step from `from` to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    apply: fn(&mut Mapping) -> Result<(), FieldError>,
+}
+
+/// All migrations in order; `MIGRATIONS[n].from == n`.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "rename `type`, `invocation` and `emotion` to their current keys",
+        apply: rename_legacy_keys,
+    },
+    Migration {
+        from: 1,
+        description: "convert emotion strings to mappings and map old type names",
+        apply: structure_values,
+    },
+];
+
+/// Type names used by older scrolls, mapped to their current spelling.
+const LEGACY_TYPE_NAMES: &[(&str, &str)] = &[
+    ("Reference Scroll", "Reference"),
+    ("System Design", "System"),
+    ("System Setup", "System"),
+    ("Scrollbook Placeholder", "Scrollbook"),
+];
+
+/// Declared schema version of a header; 0 when absent.
+pub fn schema_version(header: &Mapping) -> Result<u32, FieldError> {
+    match header.get(VERSION_KEY) {
+        None | Some(Value::Null) => Ok(0),
+        Some(value) => value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| FieldError::new(VERSION_KEY, "expected a non-negative integer")),
+    }
+}
+
+/// Upgrades `header` in place to `CURRENT_SCHEMA_VERSION` and returns the
+/// version it started from. Headers from a newer schema are rejected.
+pub fn upgrade(header: &mut Mapping) -> Result<u32, FieldError> {
+    let from = schema_version(header)?;
+    if from > CURRENT_SCHEMA_VERSION {
+        return Err(FieldError::new(
+            VERSION_KEY,
+            format!(
+                "schema_version {from} is newer than the supported version {CURRENT_SCHEMA_VERSION}"
+            ),
+        ));
+    }
+    for migration in &MIGRATIONS[from as usize..] {
+        (migration.apply)(header)?;
+    }
+    header.insert(VERSION_KEY.into(), Value::from(CURRENT_SCHEMA_VERSION));
+    Ok(from)
+}
+
+/// True when `upgrade` changed anything besides stamping the version.
+pub fn rewrites_content(before: &Mapping, after: &Mapping) -> bool {
+    let strip = |m: &Mapping| {
+        let mut m = m.clone();
+        m.remove(VERSION_KEY);
+        m
+    };
+    strip(before) != strip(after)
+}
+
+fn rename_legacy_keys(header: &mut Mapping) -> Result<(), FieldError> {
+    for (old, new) in [
+        ("type", "scroll_type"),
+        ("invocation", "invocation_phrase"),
+        ("emotion", "emotion_signature"),
+    ] {
+        if !header.contains_key(new) {
+            if let Some(value) = header.remove(old) {
+                header.insert(new.into(), value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn structure_values(header: &mut Mapping) -> Result<(), FieldError> {
+    if let Some(Value::String(emotion)) = header.get("emotion_signature") {
+        let emotion = emotion
+            .parse::<EmotionSignature>()
+            .map_err(|e| FieldError::new("emotion_signature", e))?;
+        let mut value = Mapping::new();
+        value.insert("tone".into(), emotion.tone.into());
+        value.insert("emphasis".into(), short_float(emotion.emphasis));
+        value.insert("resonance".into(), emotion.resonance.into());
+        if let Some(intensity) = emotion.intensity {
+            value.insert("intensity".into(), short_float(intensity));
+        }
+        header.insert("emotion_signature".into(), Value::Mapping(value));
+    }
+
+    if let Some(Value::String(label)) = header.get("scroll_type") {
+        let label = LEGACY_TYPE_NAMES
+            .iter()
+            .find(|(old, _)| old.eq_ignore_ascii_case(label.trim()))
+            .map(|(_, new)| new.to_string())
+            .unwrap_or_else(|| label.clone());
+        // Built-in types get their canonical casing; custom names are kept.
+        let label = match label.parse::<ScrollType>() {
+            Ok(ScrollType::Custom(_)) | Err(_) => label,
+            Ok(builtin) => builtin.to_string(),
+        };
+        header.insert("scroll_type".into(), Value::String(label));
+    }
+    Ok(())
+}
+
+/// Writes `0.3` rather than the widened `0.30000001192092896`.
+fn short_float(value: f32) -> Value {
+    value
+        .to_string()
+        .parse::<f64>()
+        .map_or_else(|_| Value::from(f64::from(value)), Value::from)
+}
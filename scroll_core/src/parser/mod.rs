@@ -0,0 +1,412 @@
+// ===============================
+// src/parser/mod.rs
+// ===============================
+
+pub mod body;
+pub mod error;
+pub mod format;
+pub mod front_matter;
+pub mod lenient;
+pub mod migrate;
+
+use std::fs;
+use std::path::{Component, Path};
+
+use serde_yaml::{Mapping, Value};
+use uuid::Uuid;
+
+use crate::schema::{ScrollStatus, ScrollType};
+use crate::type_registry::TypeRegistry;
+
+use crate::scroll::{Scroll, ScrollOrigin};
+use crate::validator::{validate_scroll, validate_status};
+
+pub use body::ScrollBody;
+pub use error::{ParseError, ParseErrorKind, Span};
+pub use format::FrontMatterFormat;
+pub use front_matter::FrontMatter;
+pub use migrate::CURRENT_SCHEMA_VERSION;
+
+use format::{RawFrontMatter, SplitError};
+
+/// Sigil given to scrolls whose header does not declare one.
+pub const DEFAULT_SIGIL: &str = "🔧";
+/// Invocation phrase given to scrolls whose header does not declare one.
+pub const DEFAULT_INVOCATION_PHRASE: &str = "Let form meet function in code and myth.";
+
+/// Namespace for ids derived from a scroll's archive-relative path.
+pub const SCROLL_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6a1c_53e2_9f4b_4d0e_8c2a_7b31_d5f0_e947);
+
+/// How strictly a scroll header is interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// The header must match the `YamlMetadata` shape exactly.
+    #[default]
+    Strict,
+    /// Accepts the archive's hand-written headers: lowercase scroll types and
+    /// statuses, `tone // resonance` emotion strings, missing tags and so on.
+    Lenient,
+}
+
+/// The header portion of a scroll together with its byte offset in the file,
+/// used to translate header locations back into file positions.
+struct Header<'a> {
+    text: &'a str,
+    offset: usize,
+    format: FrontMatterFormat,
+    open_offset: usize,
+    open_len: usize,
+}
+
+impl<'a> From<&RawFrontMatter<'a>> for Header<'a> {
+    fn from(raw: &RawFrontMatter<'a>) -> Self {
+        Self {
+            text: raw.header,
+            offset: raw.header_offset,
+            format: raw.format,
+            open_offset: raw.open_offset,
+            open_len: raw.open_len,
+        }
+    }
+}
+
+impl Header<'_> {
+    /// Span of the opening delimiter, used for errors about the header as a whole.
+    fn delimiter_span(&self, input: &str) -> Span {
+        Span::from_offset(input, self.open_offset, self.open_len)
+    }
+
+    /// Span of a top-level key, falling back to the opening delimiter.
+    fn key_span(&self, input: &str, key: &str) -> Span {
+        match locate_key(self.text, key, self.format) {
+            Some(idx) => Span::from_offset(input, self.offset + idx, key.chars().count()),
+            None => self.delimiter_span(input),
+        }
+    }
+}
+
+/// Deterministic id for a scroll whose header has no `id`, derived from its
+/// path relative to the archive root. Separators are normalized to `/` so the
+/// id is the same on every platform.
+pub fn scroll_id_for_path(relative: &Path) -> Uuid {
+    let key = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    Uuid::new_v5(&SCROLL_ID_NAMESPACE, key.as_bytes())
+}
+
+pub fn parse_scroll_from_file<P: AsRef<Path>>(path: P) -> Result<Scroll, ParseError> {
+    parse_scroll_from_file_with_mode(path, ParseMode::Strict)
+}
+
+/// Parses a scroll file; without an `id` header the id is derived from `path`
+/// as given. Use [`parse_archive_scroll`] when the archive root is known.
+pub fn parse_scroll_from_file_with_mode<P: AsRef<Path>>(
+    path: P,
+    mode: ParseMode,
+) -> Result<Scroll, ParseError> {
+    let path = path.as_ref();
+    read_and_parse(path, path, mode)
+}
+
+/// Parses a scroll that lives under `archive_root`, deriving its id from the
+/// archive-relative path when the header does not declare one.
+pub fn parse_archive_scroll<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_root: P,
+    path: Q,
+    mode: ParseMode,
+) -> Result<Scroll, ParseError> {
+    let path = path.as_ref();
+    let relative = path.strip_prefix(archive_root.as_ref()).unwrap_or(path);
+    read_and_parse(path, relative, mode)
+}
+
+fn read_and_parse(path: &Path, relative: &Path, mode: ParseMode) -> Result<Scroll, ParseError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| ParseError::new(ParseErrorKind::Io(e.to_string())).with_path(path))?;
+    let mut scroll = parse_with(
+        &contents,
+        mode,
+        Some(path),
+        Some(scroll_id_for_path(relative)),
+    )
+    .map_err(|e| e.with_path(path))?;
+    scroll.origin.source_path = Some(path.to_path_buf());
+    Ok(scroll)
+}
+
+pub fn parse_scroll(input: &str) -> Result<Scroll, ParseError> {
+    parse_scroll_with_mode(input, ParseMode::Strict)
+}
+
+pub fn parse_scroll_with_mode(input: &str, mode: ParseMode) -> Result<Scroll, ParseError> {
+    parse_with(input, mode, None, None)
+}
+
+/// `default_id` is used when the header has no `id`; in-memory input without
+/// one gets a random id.
+fn parse_with(
+    input: &str,
+    mode: ParseMode,
+    path: Option<&Path>,
+    default_id: Option<Uuid>,
+) -> Result<Scroll, ParseError> {
+    let raw = format::split(input).map_err(|e| split_error(input, e))?;
+    let header = Header::from(&raw);
+    let markdown_body = raw.body;
+
+    let mapping = match raw.format.decode(header.text) {
+        Ok(Value::Mapping(m)) => m,
+        Ok(Value::Null) => Mapping::new(),
+        Ok(_) => {
+            return Err(ParseError::new(ParseErrorKind::Format {
+                format: raw.format,
+                message: "scroll header is not a mapping".into(),
+            })
+            .at(input, header.delimiter_span(input)))
+        }
+        Err(e) => {
+            let span = e
+                .offset
+                .map(|idx| Span::from_offset(input, header.offset + idx, 1))
+                .unwrap_or_else(|| header.delimiter_span(input));
+            let message = match e.message.find(" at line ") {
+                Some(idx) => e.message[..idx].to_string(),
+                None => e.message,
+            };
+            let kind = match raw.format {
+                FrontMatterFormat::Yaml => ParseErrorKind::Yaml(message),
+                format => ParseErrorKind::Format { format, message },
+            };
+            return Err(ParseError::new(kind).at(input, span));
+        }
+    };
+
+    let field_error = |e: lenient::FieldError| {
+        ParseError::new(ParseErrorKind::Validation(format!(
+            "{}: {}",
+            e.field, e.message
+        )))
+        .at(input, header.key_span(input, &e.field))
+    };
+
+    let mut mapping = mapping;
+    let original = mapping.clone();
+    migrate::upgrade(&mut mapping).map_err(field_error)?;
+    let migrated = migrate::rewrites_content(&original, &mapping);
+
+    let front_matter: FrontMatter = match mode {
+        ParseMode::Strict => {
+            if let Some(key) = mapping
+                .keys()
+                .filter_map(Value::as_str)
+                .find(|k| is_misspelled_field(k))
+            {
+                return Err(
+                    ParseError::new(ParseErrorKind::UnknownField(key.to_string()))
+                        .at(input, header.key_span(input, key)),
+                );
+            }
+            match raw.format {
+                FrontMatterFormat::Yaml if !migrated => serde_yaml::from_str(header.text)
+                    .map_err(|e| yaml_error(input, &header, e, true))?,
+                _ => decode_mapping(input, &header, &mapping)?,
+            }
+        }
+        ParseMode::Lenient => {
+            let stem = path.and_then(|p| p.file_stem()).and_then(|s| s.to_str());
+            lenient::normalize(&mut mapping, markdown_body, stem).map_err(field_error)?;
+            decode_mapping(input, &header, &mapping)?
+        }
+    };
+
+    let mut front_matter = front_matter;
+    let validation_error = |e: String, key: &str| {
+        ParseError::new(ParseErrorKind::Validation(e)).at(input, header.key_span(input, key))
+    };
+    if let ScrollType::Custom(name) = &front_matter.scroll_type {
+        front_matter.scroll_type = TypeRegistry::global()
+            .resolve(name)
+            .map_err(|e| validation_error(e, "scroll_type"))?;
+    }
+    if let Some(status) = &front_matter.status {
+        validate_status(&front_matter.scroll_type, status)
+            .map_err(|e| validation_error(e, "status"))?;
+    }
+
+    let yaml_metadata = front_matter.metadata();
+    validate_scroll(&yaml_metadata).map_err(|e| {
+        ParseError::new(ParseErrorKind::Validation(e)).at(input, header.delimiter_span(input))
+    })?;
+
+    let now = chrono::Utc::now();
+    let linked_scrolls = front_matter.resolved_links();
+
+    Ok(Scroll {
+        id: front_matter.id.or(default_id).unwrap_or_else(Uuid::new_v4),
+        title: front_matter.title,
+        scroll_type: front_matter.scroll_type,
+        tags: front_matter.tags,
+        archetype: front_matter.archetype,
+        quorum_required: front_matter.quorum_required,
+        markdown_body: markdown_body.to_string(),
+        invocation_phrase: front_matter
+            .invocation_phrase
+            .unwrap_or_else(|| DEFAULT_INVOCATION_PHRASE.to_string()),
+        sigil: front_matter
+            .sigil
+            .unwrap_or_else(|| DEFAULT_SIGIL.to_string()),
+        status: front_matter.status.unwrap_or(ScrollStatus::Draft),
+        emotion_signature: front_matter.emotion_signature,
+        yaml_metadata,
+        linked_scrolls,
+        origin: ScrollOrigin {
+            created: front_matter.created.unwrap_or(now),
+            last_modified: front_matter.last_modified.unwrap_or(now),
+            authored_by: front_matter.authored_by,
+            source_path: None,
+            format: raw.format,
+        },
+    })
+}
+
+/// Decodes a header that no longer matches the file text (normalized, or
+/// converted from TOML/JSON). It goes through YAML text so serde reports which
+/// key failed, and that key is used to locate the error.
+fn decode_mapping(
+    input: &str,
+    header: &Header<'_>,
+    mapping: &Mapping,
+) -> Result<FrontMatter, ParseError> {
+    let normalized = serde_yaml::to_string(mapping)
+        .map_err(|e| ParseError::new(ParseErrorKind::Yaml(e.to_string())))?;
+    serde_yaml::from_str(&normalized).map_err(|e| yaml_error(input, header, e, false))
+}
+
+fn split_error(input: &str, err: SplitError) -> ParseError {
+    let (SplitError::NoOpening { offset, len } | SplitError::Unclosed { offset, len }) = err;
+    ParseError::new(ParseErrorKind::MissingDelimiter)
+        .at(input, Span::from_offset(input, offset, len))
+}
+
+/// Splits a scroll file into its raw front matter and body without decoding
+/// either; used by the writer and id stamping to edit files in place.
+pub(crate) fn split_front_matter(input: &str) -> Option<RawFrontMatter<'_>> {
+    format::split(input).ok()
+}
+
+/// Span of a top-level header key, falling back to the opening delimiter.
+/// `None` when the file has no front matter.
+pub fn header_key_span(input: &str, key: &str) -> Option<Span> {
+    let raw = split_front_matter(input)?;
+    Some(Header::from(&raw).key_span(input, key))
+}
+
+/// Span of the opening front-matter delimiter.
+pub fn header_span(input: &str) -> Option<Span> {
+    let raw = split_front_matter(input)?;
+    Some(Header::from(&raw).delimiter_span(input))
+}
+
+/// Span of a whole 1-based body line, positioned in the full file.
+pub fn body_line_span(input: &str, line: usize) -> Option<Span> {
+    let raw = split_front_matter(input)?;
+    let mut offset = input.len() - raw.body.len();
+    for text in raw.body.split_inclusive('\n').take(line.checked_sub(1)?) {
+        offset += text.len();
+    }
+    let text = input[offset..].lines().next()?;
+    Some(Span::from_offset(input, offset, text.chars().count()))
+}
+
+/// Converts a serde_yaml error into a `ParseError` positioned in the original file.
+///
+/// Errors decoded straight from the header (`located`) carry a usable location;
+/// errors from the lenient path were decoded from rewritten text, so only their
+/// key path (`emotion_signature.emphasis: ...`) is used to find the line.
+fn yaml_error(
+    input: &str,
+    header: &Header<'_>,
+    err: serde_yaml::Error,
+    located: bool,
+) -> ParseError {
+    let full = err.to_string();
+    let message = full
+        .find(" at line ")
+        .map(|idx| full[..idx].to_string())
+        .unwrap_or_else(|| full.clone());
+    let key = message
+        .split_once(": ")
+        .map(|(path, _)| path)
+        .filter(|path| !path.is_empty() && !path.contains(char::is_whitespace))
+        .map(|path| path.split(['.', '[']).next().unwrap_or(path));
+
+    let span = match (err.location().filter(|_| located), key) {
+        (Some(loc), _) if loc.index() > 0 => {
+            Span::from_offset(input, header.offset + loc.index(), 1)
+        }
+        (_, Some(key)) => header.key_span(input, key),
+        _ => header.delimiter_span(input),
+    };
+    let kind = match header.format {
+        FrontMatterFormat::Yaml => ParseErrorKind::Yaml(message),
+        format => ParseErrorKind::Format { format, message },
+    };
+    ParseError::new(kind).at(input, span)
+}
+
+/// Unknown keys are kept as extensions, but in strict mode a key that is one
+/// or two edits away from a declared field is almost always a typo. Keys
+/// under four characters, such as `ids` or `tag`, are too short to tell.
+fn is_misspelled_field(key: &str) -> bool {
+    key.chars().count() >= 4
+        && !FrontMatter::FIELDS.contains(&key)
+        && FrontMatter::FIELDS
+            .iter()
+            .any(|field| edit_distance(key, field) <= (field.len() / 4).max(1))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Byte offset of the line declaring top-level `key` within the header text.
+/// JSON keys are indented and TOML keys are followed by `=` rather than `:`.
+fn locate_key(text: &str, key: &str, format: FrontMatterFormat) -> Option<usize> {
+    let mut offset = 0;
+    for full_line in text.split_inclusive('\n') {
+        let line = match format {
+            FrontMatterFormat::Json | FrontMatterFormat::JsonObject => full_line.trim_start(),
+            _ => full_line,
+        };
+        let indent = full_line.len() - line.len();
+        let unquoted = line
+            .strip_prefix('"')
+            .and_then(|l| l.strip_prefix(key))
+            .and_then(|l| l.strip_prefix('"'))
+            .or_else(|| line.strip_prefix(key));
+        if let Some(rest) = unquoted {
+            if rest.trim_start().starts_with([':', '=']) {
+                return Some(offset + indent);
+            }
+        }
+        offset += full_line.len();
+    }
+    None
+}
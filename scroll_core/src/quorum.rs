@@ -0,0 +1,369 @@
+//! Proposal and approval flow for `quorum_required` scrolls. Changes to such
+//! scrolls are held as pending proposals with a stored diff until enough
+//! votes approve them, then applied through `ScrollWriter` under the Quorum
+//! role. Rejected, expired and stale proposals are archived with their votes;
+//! a proposal goes stale when its scroll changes before it is applied.
+// src/quorum.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use log::info;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::access::Actor;
+use crate::archive::semantic_index::fnv1a;
+use crate::invocation::named_construct::NamedConstruct;
+use crate::invocation::types::{
+    reply_keyword, Invocation, InvocationMode, InvocationResult, InvocationTier,
+};
+use crate::parser::{parse_scroll_from_file_with_mode, ParseMode};
+use crate::scroll_writer::{ScrollPatch, ScrollWriter};
+
+/// Role the applying write is made under, so quorum can amend sealed scrolls.
+pub const QUORUM_ROLE: &str = "Quorum";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuorumConfig {
+    /// Approvals needed to apply a proposal.
+    pub approvals: usize,
+    /// Rejections that close a proposal.
+    pub rejections: usize,
+    /// How long a proposal stays open.
+    pub ttl_hours: i64,
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self {
+            approvals: 2,
+            rejections: 2,
+            ttl_hours: 24 * 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    Pending,
+    Applied,
+    Rejected,
+    Expired,
+    /// The scroll changed after the proposal was submitted.
+    Stale,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vote {
+    pub voter: Actor,
+    pub approve: bool,
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Proposal {
+    pub id: Uuid,
+    pub scroll_id: Uuid,
+    pub title: String,
+    pub path: PathBuf,
+    pub proposer: Actor,
+    pub patch: ScrollPatch,
+    /// Line diff of the file at submission time against the proposed text.
+    pub diff: String,
+    /// Hash of the file the votes are cast on; the proposal goes stale if
+    /// the file no longer matches it.
+    #[serde(default)]
+    pub base_hash: u64,
+    pub created: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+    pub votes: Vec<Vote>,
+    pub status: ProposalStatus,
+}
+
+impl Proposal {
+    pub fn approvals(&self) -> usize {
+        self.votes.iter().filter(|v| v.approve).count()
+    }
+
+    pub fn rejections(&self) -> usize {
+        self.votes.iter().filter(|v| !v.approve).count()
+    }
+
+    /// Whether the scroll file differs from the version proposed against.
+    pub fn is_stale(&self) -> Result<bool, String> {
+        let current = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        Ok(fnv1a(current.as_bytes()) != self.base_hash)
+    }
+}
+
+/// Whether `actor` holds the Quorum role and may write `quorum_required`
+/// scrolls directly.
+pub(crate) fn holds_quorum_role(actor: &Actor) -> bool {
+    actor
+        .roles
+        .iter()
+        .any(|role| role.eq_ignore_ascii_case(QUORUM_ROLE))
+}
+
+/// What happened to a submitted change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submission {
+    /// The scroll does not require quorum; the change was written directly.
+    Applied,
+    /// The change is waiting for votes as the given proposal.
+    Pending(Uuid),
+}
+
+/// Open proposals and the archive of closed ones.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct QuorumBoard {
+    pub config: QuorumConfig,
+    pending: Vec<Proposal>,
+    closed: Vec<Proposal>,
+}
+
+impl QuorumBoard {
+    pub fn new(config: QuorumConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Reads a board saved with [`save`](Self::save).
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| format!("invalid quorum board: {e}"))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn pending(&self) -> &[Proposal] {
+        &self.pending
+    }
+
+    /// Applied, rejected, expired and stale proposals, oldest first.
+    pub fn closed(&self) -> &[Proposal] {
+        &self.closed
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&Proposal> {
+        self.pending
+            .iter()
+            .chain(&self.closed)
+            .find(|proposal| proposal.id == id)
+    }
+
+    /// Updates the scroll at `path`, or opens a proposal when its header sets
+    /// `quorum_required`.
+    pub fn submit_update(
+        &mut self,
+        path: &Path,
+        patch: ScrollPatch,
+        proposer: &Actor,
+    ) -> Result<Submission, String> {
+        let scroll = parse_scroll_from_file_with_mode(path, ParseMode::Lenient)
+            .map_err(|e| e.to_string())?;
+        if !scroll.quorum_required {
+            ScrollWriter::update_scroll(scroll.id, patch, path, proposer)?;
+            return Ok(Submission::Applied);
+        }
+
+        let current = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let proposed = ScrollWriter::preview_update(&patch, path)?;
+        let created = Utc::now();
+        let proposal = Proposal {
+            id: Uuid::new_v4(),
+            scroll_id: scroll.id,
+            title: scroll.title.clone(),
+            path: path.to_path_buf(),
+            proposer: proposer.clone(),
+            patch,
+            diff: line_diff(&current, &proposed),
+            base_hash: fnv1a(current.as_bytes()),
+            created,
+            expires: created + Duration::hours(self.config.ttl_hours),
+            votes: Vec::new(),
+            status: ProposalStatus::Pending,
+        };
+        info!(
+            "Proposal {} opened by {} for '{}'",
+            proposal.id, proposer, scroll.title
+        );
+        let id = proposal.id;
+        self.pending.push(proposal);
+        Ok(Submission::Pending(id))
+    }
+
+    /// Records a vote and closes the proposal once it reaches quorum, is
+    /// rejected or has expired. A proposal whose scroll changed since it was
+    /// submitted is closed as stale without counting the vote. Each voter
+    /// votes once; proposers cannot vote on their own proposals. If the
+    /// approved change cannot be written, the vote is kept and the proposal
+    /// stays pending.
+    pub fn vote(
+        &mut self,
+        proposal_id: Uuid,
+        voter: &Actor,
+        approve: bool,
+        comment: Option<String>,
+    ) -> Result<ProposalStatus, String> {
+        let index = self.pending_index(proposal_id)?;
+        if self.pending[index].expires <= Utc::now() {
+            self.close(index, ProposalStatus::Expired);
+            return Ok(ProposalStatus::Expired);
+        }
+        if self.pending[index].is_stale()? {
+            self.close(index, ProposalStatus::Stale);
+            return Ok(ProposalStatus::Stale);
+        }
+
+        let proposal = &mut self.pending[index];
+        let same = |actor: &Actor| actor.name.eq_ignore_ascii_case(&voter.name);
+        if same(&proposal.proposer) {
+            return Err(format!("{voter} cannot vote on their own proposal"));
+        }
+        if proposal.votes.iter().any(|vote| same(&vote.voter)) {
+            return Err(format!(
+                "{voter} has already voted on proposal {proposal_id}"
+            ));
+        }
+        proposal.votes.push(Vote {
+            voter: voter.clone(),
+            approve,
+            at: Utc::now(),
+            comment,
+        });
+
+        if proposal.approvals() >= self.config.approvals {
+            let applier = Actor::new(proposal.proposer.name.clone()).with_role(QUORUM_ROLE);
+            ScrollWriter::update_scroll(
+                proposal.scroll_id,
+                proposal.patch.clone(),
+                &proposal.path,
+                &applier,
+            )?;
+            self.close(index, ProposalStatus::Applied);
+            Ok(ProposalStatus::Applied)
+        } else if proposal.rejections() >= self.config.rejections {
+            self.close(index, ProposalStatus::Rejected);
+            Ok(ProposalStatus::Rejected)
+        } else {
+            Ok(ProposalStatus::Pending)
+        }
+    }
+
+    /// Asks a construct to vote with a `Validate` invocation. A reply whose
+    /// first word is "approve"/"yes" approves, "reject"/"no" rejects;
+    /// anything else, or a failed invocation, casts no vote and returns
+    /// `None`.
+    pub fn vote_with_construct<C: NamedConstruct>(
+        &mut self,
+        proposal_id: Uuid,
+        construct: &C,
+    ) -> Result<Option<ProposalStatus>, String> {
+        let proposal = &self.pending[self.pending_index(proposal_id)?];
+        let invocation = Invocation {
+            id: Uuid::new_v4(),
+            phrase: format!(
+                "Should this change to '{}' proposed by {} be applied? Answer approve or reject.\n{}",
+                proposal.title, proposal.proposer, proposal.diff
+            ),
+            invoker: "quorum".into(),
+            invoked: construct.name().to_string(),
+            tier: InvocationTier::Calling,
+            mode: InvocationMode::Validate,
+            resonance_required: false,
+            timestamp: Utc::now(),
+        };
+        let approve = match construct.perform(&invocation, None) {
+            Ok(InvocationResult::Success(reply)) => match reply_keyword(&reply).as_str() {
+                "approve" | "yes" => true,
+                "reject" | "no" => false,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let voter = Actor::new(construct.name()).with_role("construct");
+        self.vote(proposal_id, &voter, approve, None).map(Some)
+    }
+
+    /// Archives every pending proposal whose deadline has passed by `now`.
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<Uuid> {
+        let mut expired = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len() {
+            if self.pending[index].expires <= now {
+                expired.push(self.pending[index].id);
+                self.close(index, ProposalStatus::Expired);
+            } else {
+                index += 1;
+            }
+        }
+        expired
+    }
+
+    fn pending_index(&self, id: Uuid) -> Result<usize, String> {
+        self.pending
+            .iter()
+            .position(|proposal| proposal.id == id)
+            .ok_or_else(|| format!("no pending proposal {id}"))
+    }
+
+    fn close(&mut self, index: usize, status: ProposalStatus) {
+        let mut proposal = self.pending.remove(index);
+        proposal.status = status;
+        info!(
+            "Proposal {} for '{}' closed as {:?} ({} for, {} against)",
+            proposal.id,
+            proposal.title,
+            status,
+            proposal.approvals(),
+            proposal.rejections()
+        );
+        self.closed.push(proposal);
+    }
+}
+
+/// Minimal line diff: unchanged lines prefixed with a space, removals with
+/// `-` and additions with `+`.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // Longest common subsequence lengths of the suffixes.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        } else {
+            out.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        }
+    }
+    out
+}
@@ -2,10 +2,14 @@
 // src/schema.rs
 // ===============================
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Serialized as its label, so custom types read and write as plain strings.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ScrollType {
     Canon,
     Protocol,
@@ -17,6 +21,9 @@ pub enum ScrollType {
 
     #[default]
     Echo,
+
+    /// A type defined in the type registry rather than built in.
+    Custom(String),
 }
 
 impl fmt::Display for ScrollType {
@@ -30,11 +37,54 @@ impl fmt::Display for ScrollType {
             ScrollType::AgentCatalog => "AgentCatalog",
             ScrollType::Echo => "Echo",
             ScrollType::Ritual => "Ritual",
+            ScrollType::Custom(name) => name,
         };
         write!(f, "{}", label)
     }
 }
 
+/// Lowercases a label and drops separators so `agent_catalog`, `Agent-Catalog`
+/// and `AgentCatalog` all compare equal.
+pub(crate) fn normalize_label(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Built-in labels match case- and separator-insensitively; anything else
+/// becomes `ScrollType::Custom` and is checked against the type registry.
+impl FromStr for ScrollType {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize_label(s).as_str() {
+            "canon" => Ok(ScrollType::Canon),
+            "protocol" => Ok(ScrollType::Protocol),
+            "system" => Ok(ScrollType::System),
+            "scrollbook" => Ok(ScrollType::Scrollbook),
+            "agentcatalog" => Ok(ScrollType::AgentCatalog),
+            "myth" => Ok(ScrollType::Myth),
+            "ritual" => Ok(ScrollType::Ritual),
+            "echo" => Ok(ScrollType::Echo),
+            "" => Err(format!("invalid scroll type '{s}'")),
+            _ => Ok(ScrollType::Custom(s.trim().to_string())),
+        }
+    }
+}
+
+impl Serialize for ScrollType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScrollType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let label = String::deserialize(deserializer)?;
+        label.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ScrollStatus {
     Emergent,
@@ -47,6 +97,23 @@ pub enum ScrollStatus {
     Deprecated,
 }
 
+impl FromStr for ScrollStatus {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize_label(s).as_str() {
+            "emergent" => Ok(ScrollStatus::Emergent),
+            "draft" => Ok(ScrollStatus::Draft),
+            "active" => Ok(ScrollStatus::Active),
+            "mythicvalidated" => Ok(ScrollStatus::MythicValidated),
+            "sealed" => Ok(ScrollStatus::Sealed),
+            "archived" => Ok(ScrollStatus::Archived),
+            "latent" => Ok(ScrollStatus::Latent),
+            "deprecated" => Ok(ScrollStatus::Deprecated),
+            _ => Err(format!("unknown scroll status '{s}'")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EmotionSignature {
     pub tone: String,
@@ -151,6 +218,41 @@ impl EmotionSignature {
     }
 }
 
+/// Parses the `tone // resonance` shorthand used in scroll headers, with an
+/// optional trailing `(intensity)` as produced by the `Display` impl.
+impl FromStr for EmotionSignature {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("empty emotion signature".into());
+        }
+
+        let (rest, intensity) = match s.strip_suffix(')').and_then(|r| r.rsplit_once('(')) {
+            Some((head, value)) => {
+                let value = value
+                    .trim()
+                    .parse::<f32>()
+                    .map_err(|_| format!("invalid emotion intensity in '{s}'"))?;
+                (head.trim(), Some(value))
+            }
+            None => (s, None),
+        };
+
+        let (tone, resonance) = match rest.split_once("//") {
+            Some((tone, resonance)) => (tone.trim(), resonance.trim()),
+            None => (rest, ""),
+        };
+
+        Ok(Self {
+            tone: tone.to_string(),
+            emphasis: 0.0,
+            resonance: resonance.to_string(),
+            intensity,
+        })
+    }
+}
+
 impl Default for EmotionSignature {
     fn default() -> Self {
         Self::neutral()
@@ -183,4 +285,45 @@ pub struct YamlMetadata {
     pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(default)]
     pub file_path: Option<String>,
+    /// Raw `linked_scrolls` references (usually filenames) as written in the
+    /// header; resolved into `Scroll::linked_scrolls` once the archive loads.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linked_scrolls: Vec<String>,
+    /// Header keys the schema does not declare, kept with their YAML values
+    /// so team-specific metadata is written back unchanged.
+    #[serde(flatten, default, skip_serializing_if = "Mapping::is_empty")]
+    pub extensions: Mapping,
+}
+
+impl YamlMetadata {
+    /// Raw value of an extension key.
+    pub fn extension(&self, key: &str) -> Option<&Value> {
+        self.extensions.get(key)
+    }
+
+    /// Decodes an extension key into `T`. Returns `None` when the key is
+    /// absent and an error when its value has a different shape.
+    pub fn extension_as<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Option<Result<T, serde_yaml::Error>> {
+        self.extension(key)
+            .map(|value| serde_yaml::from_value(value.clone()))
+    }
+
+    /// String value of an extension key, if it is a string.
+    pub fn extension_str(&self, key: &str) -> Option<&str> {
+        self.extension(key).and_then(Value::as_str)
+    }
+
+    /// Sets an extension key, replacing any previous value.
+    pub fn set_extension<T: Serialize>(
+        &mut self,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<(), serde_yaml::Error> {
+        let value = serde_yaml::to_value(value)?;
+        self.extensions.insert(Value::String(key.into()), value);
+        Ok(())
+    }
 }
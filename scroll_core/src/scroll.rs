@@ -2,19 +2,27 @@
 
 use chrono::{DateTime, Utc};
 use log::info;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::archive::link_resolver::ReferenceSource;
+use crate::parser::{FrontMatterFormat, ScrollBody};
 use crate::schema::{EmotionSignature, ScrollStatus, ScrollType, YamlMetadata};
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct ScrollOrigin {
     pub created: DateTime<Utc>,
     pub authored_by: Option<String>,
     pub last_modified: DateTime<Utc>,
+    /// File the scroll was parsed from, if it came from disk.
+    pub source_path: Option<PathBuf>,
+    /// Front-matter format the scroll is written back in.
+    pub format: FrontMatterFormat,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum ScrollLinkType {
     Ancestor,
     Reflection,
@@ -23,13 +31,18 @@ pub enum ScrollLinkType {
     Echo,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct ScrollLink {
     pub target: Uuid,
     pub link_type: ScrollLinkType,
+    /// Where the link resolver found the reference this link was derived
+    /// from. `None` for links declared in the header or added with
+    /// `link_to`; only those are written back to the header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ReferenceSource>,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Scroll {
     pub id: Uuid,
     pub title: String,
@@ -76,6 +89,8 @@ impl ScrollBuilder {
                 quorum_required: false,
                 last_modified: None,
                 file_path: None,
+                linked_scrolls: vec![],
+                extensions: Default::default(),
             },
             tags: vec![],
             archetype: None,
@@ -147,6 +162,8 @@ impl Scroll {
                 created: now,
                 authored_by: params.authored_by,
                 last_modified: now,
+                source_path: None,
+                format: FrontMatterFormat::Yaml,
             },
         }
     }
@@ -173,6 +190,7 @@ impl Scroll {
         self.linked_scrolls.push(ScrollLink {
             target: other.id,
             link_type: link_type.clone(),
+            source: None,
         });
         self.origin.last_modified = Utc::now();
         info!(
@@ -181,6 +199,11 @@ impl Scroll {
         );
     }
 
+    /// Parses `markdown_body` into sections, blockquotes and code blocks.
+    pub fn body(&self) -> ScrollBody {
+        ScrollBody::parse(&self.markdown_body)
+    }
+
     pub fn is_linked_to(&self, other_id: &Uuid) -> bool {
         self.linked_scrolls
             .iter()
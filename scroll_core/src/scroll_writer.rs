@@ -1,23 +1,33 @@
 //! ScrollWriter persists scrolls and updates existing ones in the archive.
-//! It validates input and applies patches while emitting timestamps.
+//! It validates input and applies patches while emitting timestamps. Edits
+//! to sealed or archived scrolls go through [`AccessPolicy`].
 //! See [ScrollWriter](../AGENTS.md#scrollwriter) for its role.
 // scroll_writer.rs – Hand of the Archive
 //===========================================
 
 use chrono::Utc;
-use serde_yaml;
-use std::fs::File;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 use uuid::Uuid;
 
+use crate::access::{AccessPolicy, Actor, WriteAction};
+use crate::archive::archive_memory::ArchiveMemory;
+use crate::archive::watcher::ArchiveChange;
 use crate::artifact::WritableArtifact;
-use crate::parser::parse_scroll_from_file;
+use crate::parser::{
+    parse_scroll_from_file_with_mode, split_front_matter, FrontMatter, FrontMatterFormat, ParseMode,
+};
+use crate::quorum::holds_quorum_role;
 use crate::schema::{EmotionSignature, ScrollStatus, ScrollType, YamlMetadata};
 use crate::scroll::{Scroll, ScrollOrigin};
+use crate::state_manager::{StateMachine, TransitionRequest};
 use crate::validator::validate_scroll;
 
 /// Patch structure for updating existing scroll fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ScrollPatch {
     pub title: Option<String>,
     pub markdown_body: Option<String>,
@@ -27,10 +37,7 @@ pub struct ScrollPatch {
 
 impl WritableArtifact for Scroll {
     fn to_string_representation(&self) -> String {
-        let yaml = serde_yaml::to_string(&self.yaml_metadata)
-            .unwrap_or_else(|_| "error: could not serialize metadata".to_string());
-
-        format!("---\n{}---\n\n{}", yaml.trim(), self.markdown_body.trim())
+        render_scroll(self, None)
     }
 
     fn file_extension(&self) -> &'static str {
@@ -53,43 +60,128 @@ pub struct ScrollWriter;
 
 impl ScrollWriter {
     /// Writes a scroll to disk as a markdown file.
-    pub fn write_scroll(scroll: &Scroll, path: &Path) -> Result<(), String> {
-        validate_scroll(&scroll.yaml_metadata).map_err(|e| format!("Validation failed: {}", e))?;
-        ArtifactWriter::write_artifact(scroll, path)
-    }
+    ///
+    /// If `path` (or the file the scroll was parsed from) already has a header,
+    /// unchanged keys are kept byte-for-byte along with their comments; only
+    /// changed fields are re-serialized. Extension keys are written back from
+    /// `yaml_metadata.extensions`.
+    ///
+    /// No access check is made; outside the crate every write goes through
+    /// [`write_scroll_as`](Self::write_scroll_as).
+    pub(crate) fn write_scroll(scroll: &Scroll, path: &Path) -> Result<(), String> {
+        validate_scroll(&FrontMatter::from_scroll(scroll).metadata())
+            .map_err(|e| format!("Validation failed: {}", e))?;
 
-    /// Applies patch and updates an existing scroll.
-    pub fn update_scroll(_id: Uuid, updates: ScrollPatch, path: &Path) -> Result<(), String> {
-        let mut scroll = parse_scroll_from_file(path).map_err(|e| e.to_string())?;
+        let template = fs::read_to_string(path).ok().or_else(|| {
+            scroll
+                .origin
+                .source_path
+                .as_ref()
+                .and_then(|source| fs::read_to_string(source).ok())
+        });
+        let header = template
+            .as_deref()
+            .and_then(split_front_matter)
+            .filter(|raw| raw.format == scroll.origin.format)
+            .map(|raw| raw.header);
 
-        if let Some(title) = updates.title {
-            scroll.title = title.clone();
-            scroll.yaml_metadata.title = title;
-        }
+        let mut file = File::create(path).map_err(|e| e.to_string())?;
+        file.write_all(render_scroll(scroll, header).as_bytes())
+            .map_err(|e| e.to_string())
+    }
 
-        if let Some(body) = updates.markdown_body {
-            scroll.markdown_body = body;
-        }
+    /// Writes `scroll` over the file at `path` on behalf of `actor`, refusing
+    /// when the scroll currently stored there is protected or cannot be
+    /// parsed.
+    pub fn write_scroll_as(scroll: &Scroll, path: &Path, actor: &Actor) -> Result<(), String> {
+        authorize_existing(path, actor, WriteAction::Write)?;
+        Self::write_scroll(scroll, path)
+    }
 
-        if let Some(tags) = updates.tags {
-            scroll.yaml_metadata.tags = tags;
-        }
+    /// Writes `scroll` to `path` on behalf of `actor` and puts it into the
+    /// running `archive`, returning the change so it can be passed on to
+    /// caches or the bus.
+    pub fn write_to_archive(
+        scroll: &Scroll,
+        path: &Path,
+        actor: &Actor,
+        archive: &mut dyn ArchiveMemory,
+    ) -> Result<ArchiveChange, String> {
+        Self::write_scroll_as(scroll, path, actor)?;
+        let mut written = scroll.clone();
+        written.origin.source_path = Some(path.to_path_buf());
+        archive.upsert(written).map_err(|e| e.to_string())
+    }
 
-        if let Some(sigil) = updates.sigil {
-            scroll.sigil = sigil;
+    /// Applies patch and updates an existing scroll on behalf of `actor`.
+    /// Sealed and archived scrolls require an override role, and
+    /// `quorum_required` scrolls the Quorum role, which approved
+    /// [`QuorumBoard`](crate::quorum::QuorumBoard) proposals write under.
+    pub fn update_scroll(
+        _id: Uuid,
+        updates: ScrollPatch,
+        path: &Path,
+        actor: &Actor,
+    ) -> Result<(), String> {
+        patch_file(updates, path, actor).map(|_| ())
+    }
+
+    /// Like [`update_scroll`](Self::update_scroll), then replaces scroll
+    /// `id` in the running `archive` with the patched scroll. Refuses, before
+    /// writing, when `path` holds a different scroll than `id`.
+    pub fn update_in_archive(
+        id: Uuid,
+        updates: ScrollPatch,
+        path: &Path,
+        actor: &Actor,
+        archive: &mut dyn ArchiveMemory,
+    ) -> Result<ArchiveChange, String> {
+        let on_disk = parse_scroll_from_file_with_mode(path, ParseMode::Lenient)
+            .map_err(|e| e.to_string())?
+            .id;
+        // Undeclared ids are derived from the path relative to the archive
+        // root, so the archive's own record of the file also counts.
+        let stored_here = archive
+            .get_scroll_by_id(id)
+            .and_then(|scroll| scroll.origin.source_path.as_deref())
+            .is_some_and(|stored| same_file(stored, path));
+        if on_disk != id && !stored_here {
+            return Err(format!(
+                "{} holds scroll {on_disk}, not {id}",
+                path.display()
+            ));
         }
+        let mut scroll = patch_file(updates, path, actor)?;
+        scroll.id = id;
+        archive.update(scroll).map_err(|e| e.to_string())
+    }
 
-        let now = chrono::Utc::now();
-        scroll.origin.last_modified = now;
-        scroll.yaml_metadata.last_modified = Some(now);
-        Self::write_scroll(&scroll, path)
+    /// The file text `update_scroll` would write, without writing it or
+    /// checking access.
+    pub fn preview_update(updates: &ScrollPatch, path: &Path) -> Result<String, String> {
+        let mut scroll = parse_scroll_from_file_with_mode(path, ParseMode::Lenient)
+            .map_err(|e| e.to_string())?;
+        apply_patch(&mut scroll, updates.clone());
+        let template = fs::read_to_string(path).ok();
+        let header = template
+            .as_deref()
+            .and_then(split_front_matter)
+            .filter(|raw| raw.format == scroll.origin.format)
+            .map(|raw| raw.header);
+        Ok(render_scroll(&scroll, header))
     }
 
-    /// Marks a scroll as sealed.
-    pub fn seal_scroll(scroll: &mut Scroll) -> Result<(), String> {
-        scroll.status = ScrollStatus::Sealed;
-        scroll.origin.last_modified = chrono::Utc::now();
-        Ok(())
+    /// Seals a scroll through the standard lifecycle and writes it to `path`.
+    /// Resealing a scroll that is already sealed or archived needs an
+    /// override role.
+    pub fn seal_scroll(scroll: &mut Scroll, path: &Path, actor: &Actor) -> Result<(), String> {
+        AccessPolicy::default().authorize(actor, scroll, WriteAction::Seal)?;
+        authorize_existing(path, actor, WriteAction::Seal)?;
+        let request = TransitionRequest::by(actor.clone()).because("sealed");
+        StateMachine::standard()
+            .apply(scroll, ScrollStatus::Sealed, &request)
+            .map_err(|e| e.to_string())?;
+        Self::write_scroll(scroll, path)
     }
 
     /// Creates a draft scroll from symbolic input.
@@ -116,6 +208,8 @@ impl ScrollWriter {
                 quorum_required: false,
                 last_modified: Some(now),
                 file_path: None,
+                linked_scrolls: vec![],
+                extensions: Default::default(),
             },
 
             tags: tags.clone(),
@@ -132,7 +226,207 @@ impl ScrollWriter {
                 created: now,
                 authored_by: None,
                 last_modified: now,
+                source_path: None,
+                format: FrontMatterFormat::Yaml,
             },
         }
     }
 }
+
+/// Checks `actor` may perform `action` on the scroll stored at `path`. A
+/// missing file is fine; one that does not parse is refused, since its
+/// status is unknown.
+fn authorize_existing(path: &Path, actor: &Actor, action: WriteAction) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let existing = parse_scroll_from_file_with_mode(path, ParseMode::Lenient).map_err(|e| {
+        format!(
+            "Refusing to {action} {}: the stored scroll could not be parsed: {e}",
+            path.display()
+        )
+    })?;
+    AccessPolicy::default()
+        .authorize(actor, &existing, action)
+        .map(|_| ())
+}
+
+/// Both paths name the same existing file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Patches the scroll stored at `path` and writes it back.
+fn patch_file(updates: ScrollPatch, path: &Path, actor: &Actor) -> Result<Scroll, String> {
+    let mut scroll =
+        parse_scroll_from_file_with_mode(path, ParseMode::Lenient).map_err(|e| e.to_string())?;
+    AccessPolicy::default().authorize(actor, &scroll, WriteAction::Update)?;
+    if scroll.quorum_required && !holds_quorum_role(actor) {
+        return Err(format!(
+            "'{}' requires quorum approval; submit the change with QuorumBoard::submit_update",
+            scroll.title
+        ));
+    }
+    apply_patch(&mut scroll, updates);
+    ScrollWriter::write_scroll(&scroll, path)?;
+    Ok(scroll)
+}
+
+/// Copies the patched fields into `scroll` and bumps its timestamps.
+fn apply_patch(scroll: &mut Scroll, updates: ScrollPatch) {
+    if let Some(title) = updates.title {
+        scroll.title = title.clone();
+        scroll.yaml_metadata.title = title;
+    }
+
+    if let Some(body) = updates.markdown_body {
+        scroll.markdown_body = body;
+    }
+
+    if let Some(tags) = updates.tags {
+        scroll.tags = tags.clone();
+        scroll.yaml_metadata.tags = tags;
+    }
+
+    if let Some(sigil) = updates.sigil {
+        scroll.sigil = sigil;
+    }
+
+    let now = chrono::Utc::now();
+    scroll.origin.last_modified = now;
+    scroll.yaml_metadata.last_modified = Some(now);
+}
+
+/// Renders a scroll file in its original front-matter format, followed by the
+/// body exactly as stored. Only YAML headers are merged with `template`.
+fn render_scroll(scroll: &Scroll, template: Option<&str>) -> String {
+    let front_matter = FrontMatter::from_scroll(scroll);
+    let header = match (scroll.origin.format, template) {
+        (FrontMatterFormat::Yaml, Some(template)) => {
+            let fresh = match serde_yaml::to_value(&front_matter) {
+                Ok(Value::Mapping(m)) => m,
+                _ => Mapping::new(),
+            };
+            Ok(format!("---\n{}---\n", merge_header(template, &fresh)))
+        }
+        (format, _) => format.render(&front_matter),
+    };
+    let header = header
+        .or_else(|_| FrontMatterFormat::Yaml.render(&front_matter))
+        .unwrap_or_default();
+    format!("{}{}", header, scroll.markdown_body)
+}
+
+/// Replaces the header of `contents` with `header`, keeping its front-matter
+/// format and body. YAML headers are merged so comments survive.
+pub(crate) fn rewrite_header(contents: &str, header: &Mapping) -> Option<String> {
+    let raw = split_front_matter(contents)?;
+    let rendered = match raw.format {
+        FrontMatterFormat::Yaml => format!("---\n{}---\n", merge_header(raw.header, header)),
+        format => format.render(header).ok()?,
+    };
+    let prefix = &contents[..raw.open_offset];
+    Some(format!("{prefix}{rendered}{}", raw.body))
+}
+
+/// A top-level key of an existing header with its original text, including
+/// the comment and blank lines directly above it.
+struct HeaderBlock {
+    key: Option<String>,
+    trivia: String,
+    text: String,
+}
+
+/// Rewrites `template` so it decodes to `fresh`. Blocks whose value did not
+/// change are copied verbatim, keys missing from `fresh` are dropped and new
+/// keys are appended in canonical order.
+fn merge_header(template: &str, fresh: &Mapping) -> String {
+    let existing = match serde_yaml::from_str::<Value>(template) {
+        Ok(Value::Mapping(m)) => m,
+        _ => return serde_yaml::to_string(fresh).unwrap_or_default(),
+    };
+
+    let mut out = String::new();
+    let mut written: Vec<String> = Vec::new();
+    for block in split_header_blocks(template) {
+        let Some(key) = block.key.as_deref() else {
+            out.push_str(&block.trivia);
+            out.push_str(&block.text);
+            continue;
+        };
+        match fresh.get(key) {
+            Some(value) if existing.get(key) == Some(value) => {
+                out.push_str(&block.trivia);
+                out.push_str(&block.text);
+            }
+            Some(value) => {
+                out.push_str(&block.trivia);
+                out.push_str(&render_entry(key, value));
+            }
+            None => out.push_str(&block.trivia),
+        }
+        written.push(key.to_string());
+    }
+
+    for (key, value) in fresh {
+        if let Some(key) = key.as_str().filter(|k| !written.iter().any(|w| w == k)) {
+            out.push_str(&render_entry(key, value));
+        }
+    }
+    out
+}
+
+fn render_entry(key: &str, value: &Value) -> String {
+    let mut entry = Mapping::new();
+    entry.insert(Value::from(key), value.clone());
+    serde_yaml::to_string(&entry).unwrap_or_default()
+}
+
+/// Splits header text into top-level key blocks. Indented lines and block
+/// sequence items belong to the key above them; comments and blank lines are
+/// attached to the key that follows. Trailing trivia becomes a keyless block.
+fn split_header_blocks(text: &str) -> Vec<HeaderBlock> {
+    let mut blocks: Vec<HeaderBlock> = Vec::new();
+    let mut trivia = String::new();
+    for line in text.split_inclusive('\n') {
+        let line = if line.ends_with('\n') {
+            line.to_string()
+        } else {
+            format!("{line}\n")
+        };
+        let trimmed = line.trim();
+        let continuation = line.starts_with([' ', '\t']) || line.starts_with('-');
+        if trimmed.is_empty() || (trimmed.starts_with('#') && !continuation) {
+            trivia.push_str(&line);
+        } else if continuation {
+            match blocks.last_mut() {
+                Some(block) if trivia.is_empty() => block.text.push_str(&line),
+                Some(block) => {
+                    block.text.push_str(&std::mem::take(&mut trivia));
+                    block.text.push_str(&line);
+                }
+                None => trivia.push_str(&line),
+            }
+        } else {
+            let key = line
+                .split_once(':')
+                .map(|(k, _)| k.trim().trim_matches(['"', '\'']).to_string());
+            blocks.push(HeaderBlock {
+                key,
+                trivia: std::mem::take(&mut trivia),
+                text: line,
+            });
+        }
+    }
+    if !trivia.is_empty() {
+        blocks.push(HeaderBlock {
+            key: None,
+            trivia,
+            text: String::new(),
+        });
+    }
+    blocks
+}
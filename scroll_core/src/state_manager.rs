@@ -1,17 +1,95 @@
 //! Handles state transitions for Scroll objects and logs each change.
-//! This module ensures timestamps are updated and events are traceable.
+//! A [`StateMachine`] holds the allowed edges with their guards and hooks;
+//! every transition it applies is appended to the scroll's
+//! `transition_history` header key so the history travels with the file.
+//! Each change is also published as a [`ScrollTransitionEvent`] to attached
+//! buses and the active session.
 //! See [ScrollWriter](../AGENTS.md#scrollwriter) for persistence.
 // ===============================
 // src/state_manager.rs
 // ===============================
 
-use chrono::Utc;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
 use log::info;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+use crate::access::Actor;
+use crate::events::transition_event::ScrollTransitionEvent;
+use crate::orchestra::Bus;
+use crate::parser::{parse_scroll_from_file_with_mode, ParseMode};
+use crate::quorum::holds_quorum_role;
 use crate::schema::ScrollStatus;
 use crate::scroll::Scroll;
+use crate::scroll_writer::ScrollWriter;
+use crate::sessions::session::ScrollSession;
+
+/// Header key the transition history is stored under.
+pub const HISTORY_KEY: &str = "transition_history";
+
+static STANDARD: OnceLock<StateMachine> = OnceLock::new();
+static PUBLISHER: OnceLock<Mutex<Publisher>> = OnceLock::new();
+
+/// Where transition events go.
+#[derive(Default)]
+struct Publisher {
+    buses: Vec<Bus>,
+    session: Option<Arc<Mutex<ScrollSession>>>,
+}
+
+fn publisher() -> &'static Mutex<Publisher> {
+    PUBLISHER.get_or_init(Mutex::default)
+}
+
+/// Broadcasts every later transition on `bus`. Subscribers recognise the
+/// messages with [`ScrollTransitionEvent::from_message`].
+pub fn publish_transitions_to(bus: Bus) {
+    if let Ok(mut publisher) = publisher().lock() {
+        publisher.buses.push(bus);
+    }
+}
+
+/// Stops publishing to every attached bus.
+pub fn clear_transition_buses() {
+    if let Ok(mut publisher) = publisher().lock() {
+        publisher.buses.clear();
+    }
+}
+
+/// Sets the session later transitions are appended to as `ScrollEvent`s,
+/// replacing any previous one; `None` detaches it.
+pub fn set_active_session(session: Option<Arc<Mutex<ScrollSession>>>) {
+    if let Ok(mut publisher) = publisher().lock() {
+        publisher.session = session;
+    }
+}
+
+fn publish(event: ScrollTransitionEvent) {
+    let (buses, session) = match publisher().lock() {
+        Ok(publisher) => (publisher.buses.clone(), publisher.session.clone()),
+        Err(_) => return,
+    };
+    for bus in &buses {
+        bus.send(event.to_message());
+    }
+    if let Some(session) = session {
+        if let Ok(mut session) = session.lock() {
+            session.events.push(event.to_scroll_event());
+            session.last_update_time = Utc::now().timestamp() as u64;
+        }
+    }
+}
 
+/// Sets the status without lifecycle checks and publishes the change.
 pub fn transition(scroll: &mut Scroll, new_status: ScrollStatus) {
+    let record = set_status(scroll, new_status);
+    publish(ScrollTransitionEvent::from_record(scroll, &record));
+}
+
+fn set_status(scroll: &mut Scroll, new_status: ScrollStatus) -> TransitionRecord {
     let old_status = scroll.status.clone();
     scroll.status = new_status;
     scroll.origin.last_modified = Utc::now();
@@ -24,6 +102,13 @@ pub fn transition(scroll: &mut Scroll, new_status: ScrollStatus) {
         scroll.origin.last_modified,
         describe_status(scroll.status.clone())
     );
+    TransitionRecord {
+        from: old_status,
+        to: scroll.status.clone(),
+        actor: None,
+        reason: None,
+        at: scroll.origin.last_modified,
+    }
 }
 
 pub fn describe_status(status: ScrollStatus) -> &'static str {
@@ -39,34 +124,328 @@ pub fn describe_status(status: ScrollStatus) -> &'static str {
     }
 }
 
+/// Whether the standard lifecycle allows `current -> next`.
 pub fn is_valid_transition(current: &ScrollStatus, next: &ScrollStatus) -> bool {
-    use ScrollStatus::*;
-    match (current, next) {
-        (Draft, Active) => true,
-        (Active, Sealed) => true,
-        (Sealed, Archived) => true,
-        (Emergent, Draft) => true,
-        (Latent, Emergent) => true,
-        (_, _) if current == next => true,
-        _ => false,
-    }
+    StateMachine::standard().allows(current, next)
 }
 
+/// Moves `scroll` through the standard lifecycle with no actor or reason.
 pub fn try_transition(scroll: &mut Scroll, next_status: ScrollStatus) -> Result<(), String> {
-    if !is_valid_transition(&scroll.status, &next_status) {
-        return Err(format!(
-            "Invalid state transition: {:?} -> {:?}",
-            scroll.status, next_status
-        ));
+    StateMachine::standard()
+        .apply(scroll, next_status, &TransitionRequest::default())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// One entry of a scroll's transition history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransitionRecord {
+    pub from: ScrollStatus,
+    pub to: ScrollStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// Who asks for a transition and why.
+#[derive(Debug, Clone, Default)]
+pub struct TransitionRequest {
+    pub actor: Option<Actor>,
+    pub reason: Option<String>,
+    /// Set when a quorum has approved the change, e.g. by a closed proposal.
+    pub quorum_approved: bool,
+}
+
+impl TransitionRequest {
+    pub fn by(actor: Actor) -> Self {
+        Self {
+            actor: Some(actor),
+            ..Self::default()
+        }
     }
 
-    if matches!(
-        (&scroll.status, &next_status),
-        (ScrollStatus::Draft, ScrollStatus::Active)
-    ) {
-        scroll.validate()?;
+    pub fn because(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
     }
 
-    transition(scroll, next_status);
-    Ok(())
+    pub fn quorum_approved(mut self) -> Self {
+        self.quorum_approved = true;
+        self
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TransitionError {
+    #[error("Invalid state transition: {from:?} -> {to:?}")]
+    NotAllowed {
+        from: ScrollStatus,
+        to: ScrollStatus,
+    },
+    #[error("transition {from:?} -> {to:?} blocked by guard '{guard}': {reason}")]
+    Guarded {
+        from: ScrollStatus,
+        to: ScrollStatus,
+        guard: String,
+        reason: String,
+    },
+    #[error("transition vetoed: {0}")]
+    Vetoed(String),
+    #[error("could not persist transition: {0}")]
+    Persist(String),
+}
+
+type GuardFn = dyn Fn(&Scroll, &TransitionRequest) -> Result<(), String> + Send + Sync;
+type BeforeHook =
+    dyn Fn(&Scroll, &ScrollStatus, &TransitionRequest) -> Result<(), String> + Send + Sync;
+type AfterHook = dyn Fn(&Scroll, &TransitionRecord) + Send + Sync;
+
+/// A named precondition on an edge.
+pub struct Guard {
+    pub name: String,
+    check: Box<GuardFn>,
+}
+
+impl Guard {
+    pub fn new(
+        name: impl Into<String>,
+        check: impl Fn(&Scroll, &TransitionRequest) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            check: Box::new(check),
+        }
+    }
+
+    /// The scroll must pass [`Scroll::validate`].
+    pub fn validation() -> Self {
+        Self::new("validation", |scroll, _| scroll.validate())
+    }
+
+    /// `quorum_required` scrolls need an approved request or an actor
+    /// holding the Quorum role.
+    pub fn quorum() -> Self {
+        Self::new("quorum", |scroll, request| {
+            let quorum_actor = request.actor.as_ref().is_some_and(holds_quorum_role);
+            if !scroll.quorum_required || request.quorum_approved || quorum_actor {
+                Ok(())
+            } else {
+                Err("scroll requires quorum approval".into())
+            }
+        })
+    }
+}
+
+struct Edge {
+    from: ScrollStatus,
+    to: ScrollStatus,
+    guards: Vec<Guard>,
+}
+
+/// Allowed status edges with guards, plus hooks run around every transition.
+/// Staying in the same status is always allowed.
+#[derive(Default)]
+pub struct StateMachine {
+    edges: Vec<Edge>,
+    before: Vec<Box<BeforeHook>>,
+    after: Vec<Box<AfterHook>>,
+}
+
+impl StateMachine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The scroll lifecycle:
+    ///
+    /// ```text
+    /// Latent -> Emergent -> Draft -> Active -> MythicValidated -> Sealed -> Archived
+    /// ```
+    ///
+    /// Activation and mythic validation must pass validation; validation,
+    /// sealing and reopening need quorum for `quorum_required` scrolls. Draft,
+    /// Active and Emergent scrolls can go Latent, Latent scrolls can be
+    /// revived as drafts, and active, validated or sealed scrolls can be
+    /// deprecated before archiving.
+    pub fn standard() -> &'static StateMachine {
+        STANDARD.get_or_init(|| {
+            use ScrollStatus::*;
+            StateMachine::new()
+                .allow(Latent, Emergent)
+                .allow(Latent, Draft)
+                .allow(Emergent, Draft)
+                .allow(Emergent, Latent)
+                .allow_guarded(Draft, Active, vec![Guard::validation()])
+                .allow(Draft, Latent)
+                .allow_guarded(
+                    Active,
+                    MythicValidated,
+                    vec![Guard::validation(), Guard::quorum()],
+                )
+                .allow_guarded(Active, Sealed, vec![Guard::quorum()])
+                .allow(Active, Draft)
+                .allow(Active, Latent)
+                .allow_guarded(MythicValidated, Sealed, vec![Guard::quorum()])
+                .allow(MythicValidated, Active)
+                .allow_guarded(Sealed, Active, vec![Guard::quorum()])
+                .allow(Sealed, Archived)
+                .allow(Active, Deprecated)
+                .allow(MythicValidated, Deprecated)
+                .allow(Sealed, Deprecated)
+                .allow(Deprecated, Archived)
+        })
+    }
+
+    pub fn allow(self, from: ScrollStatus, to: ScrollStatus) -> Self {
+        self.allow_guarded(from, to, Vec::new())
+    }
+
+    pub fn allow_guarded(
+        mut self,
+        from: ScrollStatus,
+        to: ScrollStatus,
+        guards: Vec<Guard>,
+    ) -> Self {
+        self.edges.push(Edge { from, to, guards });
+        self
+    }
+
+    /// Runs before every transition; an error vetoes it.
+    pub fn before(
+        mut self,
+        hook: impl Fn(&Scroll, &ScrollStatus, &TransitionRequest) -> Result<(), String>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.before.push(Box::new(hook));
+        self
+    }
+
+    /// Runs after every transition with the recorded entry.
+    pub fn after(
+        mut self,
+        hook: impl Fn(&Scroll, &TransitionRecord) + Send + Sync + 'static,
+    ) -> Self {
+        self.after.push(Box::new(hook));
+        self
+    }
+
+    pub fn allows(&self, from: &ScrollStatus, to: &ScrollStatus) -> bool {
+        from == to || self.edge(from, to).is_some()
+    }
+
+    /// Statuses reachable from `from` in one step.
+    pub fn next_states(&self, from: &ScrollStatus) -> Vec<ScrollStatus> {
+        self.edges
+            .iter()
+            .filter(|edge| &edge.from == from)
+            .map(|edge| edge.to.clone())
+            .collect()
+    }
+
+    /// Checks the edge and its guards, runs the hooks, changes the status,
+    /// appends the transition to the scroll's history and publishes it.
+    pub fn apply(
+        &self,
+        scroll: &mut Scroll,
+        to: ScrollStatus,
+        request: &TransitionRequest,
+    ) -> Result<TransitionRecord, TransitionError> {
+        let record = self.record(scroll, to, request)?;
+        self.announce(scroll, &record);
+        Ok(record)
+    }
+
+    /// Applies a transition to the scroll stored at `path` and writes it
+    /// back, history included. The write is access-checked as the request's
+    /// actor, so leaving a protected status needs an override role. After
+    /// hooks run and the event is published only once the file is written.
+    pub fn apply_to_file(
+        &self,
+        path: &Path,
+        to: ScrollStatus,
+        request: &TransitionRequest,
+    ) -> Result<TransitionRecord, TransitionError> {
+        let mut scroll = parse_scroll_from_file_with_mode(path, ParseMode::Lenient)
+            .map_err(|e| TransitionError::Persist(e.to_string()))?;
+        let record = self.record(&mut scroll, to, request)?;
+        let actor = request
+            .actor
+            .clone()
+            .unwrap_or_else(|| Actor::new("system"));
+        ScrollWriter::write_scroll_as(&scroll, path, &actor).map_err(TransitionError::Persist)?;
+        self.announce(&scroll, &record);
+        Ok(record)
+    }
+
+    /// Checks the edge, its guards and the before hooks, then changes the
+    /// status and appends the record to the scroll's history.
+    fn record(
+        &self,
+        scroll: &mut Scroll,
+        to: ScrollStatus,
+        request: &TransitionRequest,
+    ) -> Result<TransitionRecord, TransitionError> {
+        let from = scroll.status.clone();
+        if from != to {
+            let edge = self
+                .edge(&from, &to)
+                .ok_or_else(|| TransitionError::NotAllowed {
+                    from: from.clone(),
+                    to: to.clone(),
+                })?;
+            for guard in &edge.guards {
+                (guard.check)(scroll, request).map_err(|reason| TransitionError::Guarded {
+                    from: from.clone(),
+                    to: to.clone(),
+                    guard: guard.name.clone(),
+                    reason,
+                })?;
+            }
+        }
+        for hook in &self.before {
+            hook(scroll, &to, request).map_err(TransitionError::Vetoed)?;
+        }
+
+        let record = TransitionRecord {
+            actor: request.actor.as_ref().map(|a| a.name.clone()),
+            reason: request.reason.clone(),
+            ..set_status(scroll, to)
+        };
+        let mut history = transition_history(scroll);
+        history.push(record.clone());
+        scroll
+            .yaml_metadata
+            .set_extension(HISTORY_KEY, history)
+            .map_err(|e| TransitionError::Persist(e.to_string()))?;
+        scroll.yaml_metadata.last_modified = Some(record.at);
+        Ok(record)
+    }
+
+    /// Runs the after hooks and publishes the transition.
+    fn announce(&self, scroll: &Scroll, record: &TransitionRecord) {
+        for hook in &self.after {
+            hook(scroll, record);
+        }
+        publish(ScrollTransitionEvent::from_record(scroll, record));
+    }
+
+    fn edge(&self, from: &ScrollStatus, to: &ScrollStatus) -> Option<&Edge> {
+        self.edges
+            .iter()
+            .find(|edge| &edge.from == from && &edge.to == to)
+    }
+}
+
+/// The transitions recorded in the scroll's header, oldest first. Malformed
+/// entries are ignored.
+pub fn transition_history(scroll: &Scroll) -> Vec<TransitionRecord> {
+    scroll
+        .yaml_metadata
+        .extension_as::<Vec<TransitionRecord>>(HISTORY_KEY)
+        .and_then(Result::ok)
+        .unwrap_or_default()
 }
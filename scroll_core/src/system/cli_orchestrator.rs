@@ -9,6 +9,7 @@ use crate::construct_ai::ConstructAI;
 use crate::construct_ai::ConstructContext;
 use crate::invocation::aelren::AelrenHerald;
 use crate::invocation::invocation_manager::InvocationManager;
+use crate::parser::FrontMatterFormat;
 use crate::schema::{EmotionSignature, ScrollStatus, ScrollType, YamlMetadata};
 use crate::scroll::Scroll;
 use chrono::Utc;
@@ -116,6 +117,8 @@ pub fn run_cli(manager: &InvocationManager, aelren: &AelrenHerald, scrolls: &[Sc
                         quorum_required: false,
                         last_modified: Some(now),
                         file_path: None,
+                        linked_scrolls: vec![],
+                        extensions: Default::default(),
                     },
                     tags: vec!["ephemeral".into(), "prompt".into()],
                     archetype: None,
@@ -130,6 +133,8 @@ pub fn run_cli(manager: &InvocationManager, aelren: &AelrenHerald, scrolls: &[Sc
                         created: now,
                         authored_by: Some("CLI".into()),
                         last_modified: now,
+                        source_path: None,
+                        format: FrontMatterFormat::Yaml,
                     },
                 };
 
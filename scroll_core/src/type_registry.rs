@@ -0,0 +1,213 @@
+//! Registry of scroll types: the built-in `ScrollType` variants plus custom
+//! types loaded from a YAML config. Each definition lists required header
+//! fields, allowed statuses and a default emotion signature.
+// src/type_registry.rs
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{normalize_label, EmotionSignature, ScrollStatus, ScrollType, YamlMetadata};
+
+/// Environment variable naming the type config file.
+pub const TYPES_ENV: &str = "SCROLL_CORE_TYPES";
+/// Config file read from the working directory when `TYPES_ENV` is unset.
+pub const DEFAULT_TYPES_FILE: &str = "scroll_types.yaml";
+
+static GLOBAL: OnceLock<TypeRegistry> = OnceLock::new();
+
+/// How scrolls of one type are validated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeDefinition {
+    pub name: String,
+    /// Header fields that must be present and non-empty. Extension keys may
+    /// be listed as well.
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+    /// Statuses a scroll of this type may declare; empty allows any.
+    #[serde(default)]
+    pub allowed_statuses: Vec<ScrollStatus>,
+    /// Emotion given to lenient scrolls that declare none.
+    #[serde(default)]
+    pub default_emotion: Option<EmotionSignature>,
+}
+
+impl TypeDefinition {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            required_fields: Vec::new(),
+            allowed_statuses: Vec::new(),
+            default_emotion: None,
+        }
+    }
+}
+
+/// Shape of the type config file:
+///
+/// ```yaml
+/// types:
+///   - name: Lore
+///     required_fields: [tags]
+///     allowed_statuses: [Draft, Active]
+///     default_emotion: { tone: wistful, emphasis: 0.4, resonance: ancestral }
+/// ```
+#[derive(Debug, Deserialize)]
+struct TypeConfig {
+    #[serde(default)]
+    types: Vec<TypeDefinition>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeRegistry {
+    definitions: HashMap<String, TypeDefinition>,
+}
+
+impl TypeRegistry {
+    /// Definitions for the built-in `ScrollType` variants.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for scroll_type in [
+            ScrollType::Canon,
+            ScrollType::Protocol,
+            ScrollType::System,
+            ScrollType::Scrollbook,
+            ScrollType::AgentCatalog,
+            ScrollType::Ritual,
+            ScrollType::Echo,
+        ] {
+            registry.register(TypeDefinition::new(scroll_type.to_string()));
+        }
+        let mut myth = TypeDefinition::new(ScrollType::Myth.to_string());
+        myth.required_fields.push("tags".into());
+        registry.register(myth);
+        registry
+    }
+
+    /// Built-in definitions extended (or overridden) by a YAML config.
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        let config: TypeConfig =
+            serde_yaml::from_str(text).map_err(|e| format!("invalid type config: {e}"))?;
+        let mut registry = Self::builtin();
+        for definition in config.types {
+            registry.register(definition);
+        }
+        Ok(registry)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("could not read {}: {e}", path.display()))?;
+        Self::from_yaml(&text)
+    }
+
+    pub fn register(&mut self, definition: TypeDefinition) {
+        self.definitions
+            .insert(normalize_label(&definition.name), definition);
+    }
+
+    pub fn get(&self, scroll_type: &ScrollType) -> Option<&TypeDefinition> {
+        self.definitions
+            .get(&normalize_label(&scroll_type.to_string()))
+    }
+
+    /// Parses a type label, using the registered spelling for custom types so
+    /// `lore` and `Lore` resolve to the same `ScrollType::Custom`.
+    pub fn resolve(&self, label: &str) -> Result<ScrollType, String> {
+        match label.parse::<ScrollType>()? {
+            ScrollType::Custom(name) => match self.definitions.get(&normalize_label(&name)) {
+                Some(definition) => Ok(ScrollType::Custom(definition.name.clone())),
+                None => Err(format!("unknown scroll type '{name}'")),
+            },
+            builtin => Ok(builtin),
+        }
+    }
+
+    /// Checks that the type is registered and its required fields are set.
+    pub fn check_metadata(&self, metadata: &YamlMetadata) -> Result<(), String> {
+        let definition = self
+            .get(&metadata.scroll_type)
+            .ok_or_else(|| format!("unknown scroll type '{}'", metadata.scroll_type))?;
+        for field in &definition.required_fields {
+            if !has_field(metadata, field) {
+                return Err(format!(
+                    "{} scrolls must include `{}`.",
+                    definition.name, field
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_status(
+        &self,
+        scroll_type: &ScrollType,
+        status: &ScrollStatus,
+    ) -> Result<(), String> {
+        match self.get(scroll_type) {
+            Some(definition)
+                if !definition.allowed_statuses.is_empty()
+                    && !definition.allowed_statuses.contains(status) =>
+            {
+                Err(format!(
+                    "{} scrolls cannot be {:?}; allowed: {:?}",
+                    definition.name, status, definition.allowed_statuses
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Default emotion for a type, if its definition declares one.
+    pub fn default_emotion(&self, scroll_type: &ScrollType) -> Option<EmotionSignature> {
+        self.get(scroll_type)
+            .and_then(|definition| definition.default_emotion.clone())
+    }
+
+    /// The process-wide registry, loaded on first use from `$SCROLL_CORE_TYPES`
+    /// or `scroll_types.yaml`, falling back to the built-in types.
+    pub fn global() -> &'static TypeRegistry {
+        GLOBAL.get_or_init(Self::load_default)
+    }
+
+    /// Replaces the config-file lookup with an explicit registry. Fails if
+    /// the global registry is already in use.
+    pub fn install(registry: TypeRegistry) -> Result<(), TypeRegistry> {
+        GLOBAL.set(registry)
+    }
+
+    fn load_default() -> TypeRegistry {
+        let path = std::env::var(TYPES_ENV).ok().or_else(|| {
+            Path::new(DEFAULT_TYPES_FILE)
+                .exists()
+                .then(|| DEFAULT_TYPES_FILE.to_string())
+        });
+        match path {
+            Some(path) => Self::from_file(Path::new(&path)).unwrap_or_else(|e| {
+                warn!("{e}; using built-in scroll types only");
+                Self::builtin()
+            }),
+            None => Self::builtin(),
+        }
+    }
+}
+
+fn has_field(metadata: &YamlMetadata, field: &str) -> bool {
+    match field {
+        "title" => !metadata.title.trim().is_empty(),
+        "tags" => !metadata.tags.is_empty(),
+        "archetype" => metadata.archetype.is_some(),
+        "file_path" => metadata.file_path.is_some(),
+        "last_modified" => metadata.last_modified.is_some(),
+        "linked_scrolls" => !metadata.linked_scrolls.is_empty(),
+        "emotion_signature" => !metadata.emotion_signature.is_empty(),
+        "scroll_type" | "quorum_required" => true,
+        other => metadata
+            .extension(other)
+            .is_some_and(|value| !value.is_null()),
+    }
+}
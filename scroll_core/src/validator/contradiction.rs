@@ -0,0 +1,564 @@
+//! Cross-scroll contradiction checks. Canonical facts — statuses, invocation
+//! phrases and "X is the Y" identity claims — are gathered from every scroll
+//! and compared; each conflict names both places it was found. A construct
+//! can be plugged in as a semantic judge to confirm or dismiss candidates.
+// src/validator/contradiction.rs
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_yaml::Value;
+use uuid::Uuid;
+
+use crate::archive::archive_memory::ArchiveMemory;
+use crate::invocation::named_construct::NamedConstruct;
+use crate::invocation::types::{
+    reply_keyword, Invocation, InvocationMode, InvocationResult, InvocationTier,
+};
+use crate::parser::DEFAULT_INVOCATION_PHRASE;
+use crate::schema::ScrollStatus;
+use crate::scroll::Scroll;
+use crate::validator::rules::Location;
+use crate::validator::spec::ContradictionCheck;
+
+/// The contradiction types listed in the validator spec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContradictionKind {
+    /// Conflicting lore: identities or invocation phrases.
+    Mythic,
+    /// Metadata that disagrees with what another scroll records.
+    Structural,
+    Tone,
+    /// Disagreement about whether a scroll is sealed.
+    Access,
+}
+
+impl ContradictionKind {
+    pub fn rule_id(self) -> &'static str {
+        match self {
+            ContradictionKind::Mythic => "contradiction.mythic",
+            ContradictionKind::Structural => "contradiction.structural",
+            ContradictionKind::Tone => "contradiction.tone",
+            ContradictionKind::Access => "contradiction.access",
+        }
+    }
+}
+
+/// One side of a contradiction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Site {
+    pub scroll_id: Uuid,
+    pub location: Location,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Confirmed,
+    Dismissed,
+    Unsure,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contradiction {
+    pub kind: ContradictionKind,
+    pub message: String,
+    /// Where the conflicting claim was made.
+    pub first: Site,
+    /// The fact it conflicts with.
+    pub second: Site,
+    /// Both scrolls are named on one line of the `cross_ref` scroll, which
+    /// records known forks.
+    pub acknowledged: bool,
+    /// The judge's verdict, when one is attached.
+    pub verdict: Option<Verdict>,
+}
+
+/// Decides whether a candidate contradiction is real.
+pub trait ContradictionJudge {
+    fn judge(&self, contradiction: &Contradiction, first: &Scroll, second: &Scroll) -> Verdict;
+}
+
+/// Asks a construct to judge candidates with a `Validate` invocation. A reply
+/// whose first word is "confirm"/"yes" confirms, "dismiss"/"no" dismisses;
+/// anything else, or a failed invocation, is `Unsure`.
+pub struct ConstructJudge<C: NamedConstruct> {
+    construct: C,
+}
+
+impl<C: NamedConstruct> ConstructJudge<C> {
+    pub fn new(construct: C) -> Self {
+        Self { construct }
+    }
+}
+
+impl<C: NamedConstruct> ContradictionJudge for ConstructJudge<C> {
+    fn judge(&self, contradiction: &Contradiction, first: &Scroll, second: &Scroll) -> Verdict {
+        let invocation = Invocation {
+            id: Uuid::new_v4(),
+            phrase: format!(
+                "Do '{}' and '{}' contradict each other? {} Answer confirm or dismiss.",
+                first.title, second.title, contradiction.message
+            ),
+            invoker: "validator".into(),
+            invoked: self.construct.name().to_string(),
+            tier: InvocationTier::Calling,
+            mode: InvocationMode::Validate,
+            resonance_required: false,
+            timestamp: Utc::now(),
+        };
+        match self.construct.perform(&invocation, Some(first.clone())) {
+            Ok(InvocationResult::Success(reply)) => match reply_keyword(&reply).as_str() {
+                "confirm" | "yes" => Verdict::Confirmed,
+                "dismiss" | "no" => Verdict::Dismissed,
+                _ => Verdict::Unsure,
+            },
+            _ => Verdict::Unsure,
+        }
+    }
+}
+
+/// A fact one scroll states about a subject.
+#[derive(Debug, Clone)]
+struct Claim {
+    site: Site,
+    subject: String,
+    value: String,
+}
+
+#[derive(Default)]
+pub struct ContradictionChecker {
+    /// Title or file name of the scroll that records accepted forks.
+    cross_ref: Option<String>,
+    judge: Option<Box<dyn ContradictionJudge + Send + Sync>>,
+}
+
+impl ContradictionChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_spec(check: &ContradictionCheck) -> Self {
+        Self {
+            cross_ref: check.cross_ref.clone(),
+            judge: None,
+        }
+    }
+
+    /// Attaches a judge; candidates it dismisses are dropped.
+    pub fn with_judge(mut self, judge: impl ContradictionJudge + Send + Sync + 'static) -> Self {
+        self.judge = Some(Box::new(judge));
+        self
+    }
+
+    pub fn check(&self, archive: &dyn ArchiveMemory) -> Vec<Contradiction> {
+        let mut scrolls = archive.get_all_scrolls();
+        scrolls.sort_by(|a, b| a.title.cmp(&b.title).then(a.id.cmp(&b.id)));
+        self.check_scrolls(&scrolls)
+    }
+
+    pub fn check_scrolls(&self, scrolls: &[&Scroll]) -> Vec<Contradiction> {
+        let names = NameIndex::new(scrolls);
+        let by_id: HashMap<Uuid, &Scroll> = scrolls.iter().map(|s| (s.id, *s)).collect();
+
+        let mut found = Vec::new();
+        found.extend(status_conflicts(scrolls, &names, &by_id));
+        found.extend(invocation_conflicts(scrolls, &names, &by_id));
+        found.extend(identity_conflicts(scrolls));
+
+        let forks = self
+            .cross_ref
+            .as_deref()
+            .and_then(|name| names.get(name))
+            .and_then(|id| by_id.get(&id));
+        for contradiction in &mut found {
+            if let Some(forks) = forks {
+                contradiction.acknowledged = acknowledges(
+                    forks,
+                    by_id[&contradiction.first.scroll_id],
+                    by_id[&contradiction.second.scroll_id],
+                );
+            }
+            if let Some(judge) = &self.judge {
+                contradiction.verdict = Some(judge.judge(
+                    contradiction,
+                    by_id[&contradiction.first.scroll_id],
+                    by_id[&contradiction.second.scroll_id],
+                ));
+            }
+        }
+        found.retain(|c| c.verdict != Some(Verdict::Dismissed));
+        found
+    }
+}
+
+/// Scroll ids by lower-cased title, file name and file stem.
+struct NameIndex {
+    ids: HashMap<String, Uuid>,
+    /// Names long enough to look for in prose, with their scroll.
+    mentions: Vec<(String, Uuid)>,
+}
+
+impl NameIndex {
+    fn new(scrolls: &[&Scroll]) -> Self {
+        let mut ids = HashMap::new();
+        for scroll in scrolls {
+            let mut names = vec![scroll.title.trim().to_lowercase()];
+            if let Some(path) = &scroll.origin.source_path {
+                for name in [path.file_name(), path.file_stem()].into_iter().flatten() {
+                    names.push(name.to_string_lossy().to_lowercase());
+                }
+            }
+            for name in names.into_iter().filter(|n| !n.is_empty()) {
+                ids.entry(name).or_insert(scroll.id);
+            }
+        }
+        let mentions = ids
+            .iter()
+            .filter(|(name, _)| name.chars().count() >= 6)
+            .map(|(name, id)| (name.clone(), *id))
+            .collect();
+        Self { ids, mentions }
+    }
+
+    fn get(&self, name: &str) -> Option<Uuid> {
+        self.ids.get(&name.trim().to_lowercase()).copied()
+    }
+
+    /// The single scroll a line of prose names, if exactly one.
+    fn mentioned_in(&self, line: &str) -> Option<Uuid> {
+        let line = line.to_lowercase();
+        let mut found: Vec<Uuid> = self
+            .mentions
+            .iter()
+            .filter(|(name, _)| line.contains(name.as_str()))
+            .map(|(_, id)| *id)
+            .collect();
+        found.sort();
+        found.dedup();
+        (found.len() == 1).then(|| found[0])
+    }
+}
+
+const STATUS_VERBS: &[&str] = &["is", "was", "been", "now", "remains"];
+
+/// Header statuses compared with what other scrolls say about them, both in
+/// YAML index blocks (`title:` + `status:`) and in prose ("X was sealed").
+fn status_conflicts(
+    scrolls: &[&Scroll],
+    names: &NameIndex,
+    by_id: &HashMap<Uuid, &Scroll>,
+) -> Vec<Contradiction> {
+    // (where the claim is made, the scroll it is about, the claimed status)
+    let mut claims: Vec<(Site, Uuid, String)> = Vec::new();
+    for scroll in scrolls {
+        for claim in block_claims(scroll, &["status"]) {
+            if let Some(target) = names.get(&claim.subject) {
+                claims.push((claim.site, target, claim.value));
+            }
+        }
+        for (index, line) in scroll.markdown_body.lines().enumerate() {
+            let Some(target) = names.mentioned_in(line) else {
+                continue;
+            };
+            if let Some(status) = prose_status(line) {
+                let site = Site {
+                    scroll_id: scroll.id,
+                    location: Location::BodyLine(index + 1),
+                };
+                claims.push((site, target, status));
+            }
+        }
+    }
+
+    claims
+        .into_iter()
+        .filter_map(|(site, target, value)| {
+            let scroll = by_id.get(&target)?;
+            let claimed: ScrollStatus = value.parse().ok()?;
+            if target == site.scroll_id || claimed == scroll.status {
+                return None;
+            }
+            let kind = if claimed == ScrollStatus::Sealed || scroll.status == ScrollStatus::Sealed {
+                ContradictionKind::Access
+            } else {
+                ContradictionKind::Structural
+            };
+            Some(Contradiction {
+                kind,
+                message: format!(
+                    "'{}' is described as {:?} but its header says {:?}",
+                    scroll.title, claimed, scroll.status
+                ),
+                first: site,
+                second: Site {
+                    scroll_id: target,
+                    location: Location::Field("status".into()),
+                },
+                acknowledged: false,
+                verdict: None,
+            })
+        })
+        .collect()
+}
+
+/// Invocation phrases recorded for another scroll that differ from its own,
+/// and phrases declared by more than one scroll.
+fn invocation_conflicts(
+    scrolls: &[&Scroll],
+    names: &NameIndex,
+    by_id: &HashMap<Uuid, &Scroll>,
+) -> Vec<Contradiction> {
+    let mut found = Vec::new();
+    for scroll in scrolls {
+        for claim in block_claims(scroll, &["invocation_phrase", "invocation"]) {
+            let Some(target) = names.get(&claim.subject) else {
+                continue;
+            };
+            let Some(owner) = by_id.get(&target) else {
+                continue;
+            };
+            if target == scroll.id || !declares_invocation(owner) {
+                continue;
+            }
+            if normalize_phrase(&claim.value) != normalize_phrase(&owner.invocation_phrase) {
+                found.push(Contradiction {
+                    kind: ContradictionKind::Mythic,
+                    message: format!(
+                        "invocation for '{}' is recorded as \"{}\" but the scroll declares \"{}\"",
+                        owner.title, claim.value, owner.invocation_phrase
+                    ),
+                    first: claim.site,
+                    second: Site {
+                        scroll_id: target,
+                        location: Location::Field("invocation_phrase".into()),
+                    },
+                    acknowledged: false,
+                    verdict: None,
+                });
+            }
+        }
+    }
+
+    let mut owners: HashMap<String, &Scroll> = HashMap::new();
+    for scroll in scrolls.iter().filter(|s| declares_invocation(s)) {
+        let phrase = normalize_phrase(&scroll.invocation_phrase);
+        match owners.get(&phrase) {
+            Some(first) => found.push(Contradiction {
+                kind: ContradictionKind::Mythic,
+                message: format!(
+                    "'{}' and '{}' share the invocation phrase \"{}\"",
+                    first.title, scroll.title, scroll.invocation_phrase
+                ),
+                first: Site {
+                    scroll_id: scroll.id,
+                    location: Location::Field("invocation_phrase".into()),
+                },
+                second: Site {
+                    scroll_id: first.id,
+                    location: Location::Field("invocation_phrase".into()),
+                },
+                acknowledged: false,
+                verdict: None,
+            }),
+            None => {
+                owners.insert(phrase, scroll);
+            }
+        }
+    }
+    found
+}
+
+/// "Sirion is the Architect" in one scroll against "Sirion is the Seeker"
+/// in another.
+fn identity_conflicts(scrolls: &[&Scroll]) -> Vec<Contradiction> {
+    let mut first_seen: HashMap<String, Claim> = HashMap::new();
+    let mut found = Vec::new();
+    for scroll in scrolls {
+        for (index, line) in scroll.markdown_body.lines().enumerate() {
+            for (entity, role) in identity_claims(line) {
+                let claim = Claim {
+                    site: Site {
+                        scroll_id: scroll.id,
+                        location: Location::BodyLine(index + 1),
+                    },
+                    subject: entity.clone(),
+                    value: role,
+                };
+                let Some(earlier) = first_seen.get(&entity.to_lowercase()) else {
+                    first_seen.insert(entity.to_lowercase(), claim);
+                    continue;
+                };
+                if earlier.site.scroll_id != scroll.id && earlier.value != claim.value {
+                    found.push(Contradiction {
+                        kind: ContradictionKind::Mythic,
+                        message: format!(
+                            "{entity} is called \"the {}\" here but \"the {}\" elsewhere",
+                            claim.value, earlier.value
+                        ),
+                        first: claim.site,
+                        second: earlier.site.clone(),
+                        acknowledged: false,
+                        verdict: None,
+                    });
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Claims from mappings in the scroll's YAML blocks that name a scroll
+/// (`title:` or `file:`) alongside one of `keys`.
+fn block_claims(scroll: &Scroll, keys: &[&str]) -> Vec<Claim> {
+    let body = scroll.body();
+    let lines: Vec<&str> = scroll.markdown_body.lines().collect();
+    let mut claims = Vec::new();
+    for block in body.yaml_blocks() {
+        let Ok(value) = serde_yaml::from_str::<Value>(&block.content) else {
+            continue;
+        };
+        let mut found = Vec::new();
+        collect_mappings(&value, keys, &mut found);
+        for (subject, claim) in found {
+            let line = lines
+                .iter()
+                .enumerate()
+                .skip(block.line)
+                .find(|(_, line)| line.contains(subject.as_str()))
+                .map_or(block.line, |(index, _)| index + 1);
+            claims.push(Claim {
+                site: Site {
+                    scroll_id: scroll.id,
+                    location: Location::BodyLine(line),
+                },
+                subject,
+                value: claim,
+            });
+        }
+    }
+    claims
+}
+
+fn collect_mappings(value: &Value, keys: &[&str], out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Mapping(map) => {
+            let subject = ["file", "title"]
+                .iter()
+                .find_map(|k| map.get(*k).and_then(Value::as_str));
+            let claim = keys
+                .iter()
+                .find_map(|k| map.get(*k).and_then(Value::as_str));
+            if let (Some(subject), Some(claim)) = (subject, claim) {
+                out.push((subject.to_string(), claim.to_string()));
+            }
+            for nested in map.values() {
+                collect_mappings(nested, keys, out);
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                collect_mappings(item, keys, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A status word following "is", "was", "been", "now" or "remains".
+fn prose_status(line: &str) -> Option<String> {
+    let words: Vec<String> = line
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    words.windows(2).find_map(|pair| {
+        (STATUS_VERBS.contains(&pair[0].as_str()) && pair[1].parse::<ScrollStatus>().is_ok())
+            .then(|| pair[1].clone())
+    })
+}
+
+/// `(entity, role)` pairs from "Name is the role" sentences, where the name
+/// is one to three capitalized words.
+fn identity_claims(line: &str) -> Vec<(String, String)> {
+    const NOT_NAMES: &[&str] = &["This", "That", "It", "There", "He", "She", "They", "The"];
+    let mut claims = Vec::new();
+    let mut rest = line;
+    while let Some(at) = rest.find(" is the ") {
+        let before = &rest[..at];
+        let after = &rest[at + " is the ".len()..];
+        rest = after;
+
+        let name: Vec<&str> = before
+            .split_whitespace()
+            .rev()
+            .take_while(|w| w.chars().next().is_some_and(char::is_uppercase))
+            .take(3)
+            .collect();
+        let Some(first) = name.last() else {
+            continue;
+        };
+        if NOT_NAMES.contains(first) {
+            continue;
+        }
+        let entity = name
+            .iter()
+            .rev()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let role = after
+            .split(['.', ',', ';', ':', '!', '?', '(', '—'])
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .take(4)
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if !entity.is_empty() && !role.is_empty() {
+            claims.push((entity, role));
+        }
+    }
+    claims
+}
+
+fn declares_invocation(scroll: &Scroll) -> bool {
+    let phrase = scroll.invocation_phrase.trim();
+    !phrase.is_empty() && phrase != DEFAULT_INVOCATION_PHRASE
+}
+
+fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// True when one line of the forks scroll names both scrolls.
+fn acknowledges(forks: &Scroll, first: &Scroll, second: &Scroll) -> bool {
+    let names = |scroll: &Scroll| {
+        let mut names = vec![scroll.title.to_lowercase()];
+        if let Some(stem) = scroll
+            .origin
+            .source_path
+            .as_ref()
+            .and_then(|p| p.file_stem())
+        {
+            names.push(stem.to_string_lossy().to_lowercase());
+        }
+        names
+    };
+    let (first, second) = (names(first), names(second));
+    forks.markdown_body.lines().any(|line| {
+        let line = line.to_lowercase();
+        first.iter().any(|n| line.contains(n.as_str()))
+            && second.iter().any(|n| line.contains(n.as_str()))
+    })
+}
@@ -0,0 +1,38 @@
+// ===============================
+// src/validator/mod.rs
+// ===============================
+
+pub mod contradiction;
+pub mod report;
+pub mod rules;
+pub mod spec;
+
+pub use contradiction::{ContradictionChecker, ContradictionJudge};
+pub use report::{Finding, Severity, ValidationReport};
+pub use rules::{RuleContext, RuleEngine, RuleViolation};
+pub use spec::ValidatorSpec;
+
+use crate::schema::{ScrollStatus, ScrollType, YamlMetadata};
+use crate::type_registry::TypeRegistry;
+
+/// Validates scroll metadata against the global type registry.
+pub fn validate_scroll(metadata: &YamlMetadata) -> Result<(), String> {
+    validate_scroll_with(metadata, TypeRegistry::global())
+}
+
+/// Validates scroll metadata against an explicit type registry.
+pub fn validate_scroll_with(
+    metadata: &YamlMetadata,
+    registry: &TypeRegistry,
+) -> Result<(), String> {
+    if metadata.title.trim().is_empty() {
+        return Err("Scroll must have a non-empty title.".to_string());
+    }
+
+    registry.check_metadata(metadata)
+}
+
+/// Checks that a scroll's status is allowed for its type.
+pub fn validate_status(scroll_type: &ScrollType, status: &ScrollStatus) -> Result<(), String> {
+    TypeRegistry::global().check_status(scroll_type, status)
+}
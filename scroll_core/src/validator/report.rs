@@ -0,0 +1,363 @@
+//! Collected results of a validation run. Every finding carries its rule id,
+//! severity, file and span, and the report renders as text, JSON or SARIF
+//! so CI can annotate the scrolls a change touches.
+// src/validator/report.rs
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::parser::{self, split_front_matter, ParseError, Span};
+use crate::scroll::Scroll;
+use crate::validator::contradiction::Contradiction;
+use crate::validator::rules::{Location, RuleViolation};
+
+/// Header comment that suppresses findings: `# validator-ignore: tone, access.deny_edit`.
+pub const SUPPRESS_COMMENT: &str = "validator-ignore:";
+/// Header key with the same effect, for formats without comments.
+pub const SUPPRESS_KEY: &str = "validator_ignore";
+/// Rule id used for files that fail to parse.
+pub const PARSE_RULE: &str = "parse";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        };
+        f.write_str(label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    /// `None` when the file could not be parsed into a scroll.
+    pub scroll_id: Option<Uuid>,
+    pub title: Option<String>,
+    pub path: Option<PathBuf>,
+    pub span: Option<Span>,
+    /// Other places involved, such as the second side of a contradiction.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<RelatedLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RelatedLocation {
+    pub scroll_id: Uuid,
+    pub title: String,
+    pub path: Option<PathBuf>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+    pub scrolls_checked: usize,
+    /// Findings dropped by suppression comments.
+    pub suppressed: usize,
+}
+
+impl ValidationReport {
+    /// Records the violations found in one scroll, locating each in the
+    /// scroll's source file and dropping the ones its header suppresses.
+    pub fn add_scroll(&mut self, scroll: &Scroll, violations: Vec<RuleViolation>) {
+        self.scrolls_checked += 1;
+        let source = read_source(scroll);
+        let ignored = suppressions(scroll, source.as_deref());
+
+        for violation in violations {
+            if ignored
+                .iter()
+                .any(|pattern| suppresses(pattern, &violation.rule))
+            {
+                self.suppressed += 1;
+                continue;
+            }
+            self.findings.push(Finding {
+                rule: violation.rule,
+                severity: violation.severity,
+                message: violation.message,
+                scroll_id: Some(scroll.id),
+                title: Some(scroll.title.clone()),
+                path: scroll.origin.source_path.clone(),
+                span: locate(source.as_deref(), &violation.location),
+                related: Vec::new(),
+            });
+        }
+    }
+
+    /// Records cross-scroll contradictions as warnings on the scroll making
+    /// the conflicting claim, with the other side as a related location.
+    /// Forks acknowledged by the `cross_ref` scroll are only informational.
+    /// Either scroll's header can suppress the finding.
+    pub fn add_contradictions(&mut self, scrolls: &[&Scroll], contradictions: Vec<Contradiction>) {
+        for contradiction in contradictions {
+            let find = |id| scrolls.iter().find(|s| s.id == id).copied();
+            let (Some(first), Some(second)) = (
+                find(contradiction.first.scroll_id),
+                find(contradiction.second.scroll_id),
+            ) else {
+                continue;
+            };
+            let rule = contradiction.kind.rule_id();
+            let (first_source, second_source) = (read_source(first), read_source(second));
+            let suppressed = [(first, &first_source), (second, &second_source)]
+                .into_iter()
+                .any(|(scroll, source)| {
+                    suppressions(scroll, source.as_deref())
+                        .iter()
+                        .any(|pattern| suppresses(pattern, rule))
+                });
+            if suppressed {
+                self.suppressed += 1;
+                continue;
+            }
+            self.findings.push(Finding {
+                rule: rule.to_string(),
+                severity: if contradiction.acknowledged {
+                    Severity::Info
+                } else {
+                    Severity::Warning
+                },
+                message: contradiction.message,
+                scroll_id: Some(first.id),
+                title: Some(first.title.clone()),
+                path: first.origin.source_path.clone(),
+                span: locate(first_source.as_deref(), &contradiction.first.location),
+                related: vec![RelatedLocation {
+                    scroll_id: second.id,
+                    title: second.title.clone(),
+                    path: second.origin.source_path.clone(),
+                    span: locate(second_source.as_deref(), &contradiction.second.location),
+                }],
+            });
+        }
+    }
+
+    /// Records a file that failed to parse as an error finding.
+    pub fn add_parse_error(&mut self, error: &ParseError) {
+        self.scrolls_checked += 1;
+        self.findings.push(Finding {
+            rule: PARSE_RULE.into(),
+            severity: Severity::Error,
+            message: error.kind.to_string(),
+            scroll_id: None,
+            title: None,
+            path: error.path.clone(),
+            span: error.span,
+            related: Vec::new(),
+        });
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// One `severity[rule]: message` entry per finding followed by a summary:
+    ///
+    /// ```text
+    /// error[structure.required_field]: missing required field `sigil`
+    ///  --> scrolls/example.md:1:1
+    /// ```
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        for finding in &self.findings {
+            let _ = writeln!(
+                out,
+                "{}[{}]: {}",
+                finding.severity, finding.rule, finding.message
+            );
+            let file = finding
+                .path
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .or_else(|| finding.title.clone())
+                .unwrap_or_else(|| "<scroll>".into());
+            let _ = writeln!(out, " --> {}", position(&file, finding.span));
+            for related in &finding.related {
+                let file = related
+                    .path
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| related.title.clone());
+                let _ = writeln!(out, " ... {}", position(&file, related.span));
+            }
+        }
+        let _ = writeln!(
+            out,
+            "{} scroll(s) checked: {} error(s), {} warning(s), {} info, {} suppressed",
+            self.scrolls_checked,
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info),
+            self.suppressed
+        );
+        out
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    /// SARIF 2.1.0 log with one run, suitable for code-scanning uploads.
+    pub fn to_sarif(&self) -> Value {
+        let mut rule_ids: Vec<&str> = self.findings.iter().map(|f| f.rule.as_str()).collect();
+        rule_ids.sort();
+        rule_ids.dedup();
+
+        let results: Vec<Value> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut result = json!({
+                    "ruleId": finding.rule,
+                    "level": finding.severity.sarif_level(),
+                    "message": { "text": finding.message },
+                });
+                if let Some(path) = &finding.path {
+                    result["locations"] = json!([sarif_location(path, finding.span)]);
+                }
+                let related: Vec<Value> = finding
+                    .related
+                    .iter()
+                    .filter_map(|r| r.path.as_ref().map(|p| sarif_location(p, r.span)))
+                    .collect();
+                if !related.is_empty() {
+                    result["relatedLocations"] = json!(related);
+                }
+                result
+            })
+            .collect();
+
+        json!({
+            "version": "2.1.0",
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "scroll_core",
+                        "version": crate::SCROLL_CORE_VERSION,
+                        "rules": rule_ids
+                            .iter()
+                            .map(|id| json!({ "id": id }))
+                            .collect::<Vec<_>>(),
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
+}
+
+fn sarif_location(path: &Path, span: Option<Span>) -> Value {
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": {
+                "uri": path.to_string_lossy().replace('\\', "/"),
+            },
+        },
+    });
+    if let Some(span) = span {
+        location["physicalLocation"]["region"] = json!({
+            "startLine": span.line,
+            "startColumn": span.column,
+            "endColumn": span.column + span.len,
+        });
+    }
+    location
+}
+
+fn position(file: &str, span: Option<Span>) -> String {
+    match span {
+        Some(span) => format!("{file}:{}:{}", span.line, span.column),
+        None => file.to_string(),
+    }
+}
+
+fn read_source(scroll: &Scroll) -> Option<String> {
+    scroll
+        .origin
+        .source_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+}
+
+fn locate(source: Option<&str>, location: &Location) -> Option<Span> {
+    let text = source?;
+    match location {
+        Location::Scroll => parser::header_span(text),
+        Location::Field(key) => parser::header_key_span(text, key),
+        Location::BodyLine(line) => parser::body_line_span(text, *line),
+    }
+}
+
+/// Suppressed rule patterns from header comments and the `validator_ignore` key.
+fn suppressions(scroll: &Scroll, source: Option<&str>) -> Vec<String> {
+    let mut ignored = source.map(comment_suppressions).unwrap_or_default();
+    if let Some(Ok(keys)) = scroll
+        .yaml_metadata
+        .extension_as::<Vec<String>>(SUPPRESS_KEY)
+    {
+        ignored.extend(keys);
+    }
+    ignored
+}
+
+/// Rule patterns from `# validator-ignore:` comments in the header, including
+/// comment lines above the opening delimiter.
+fn comment_suppressions(source: &str) -> Vec<String> {
+    let Some(raw) = split_front_matter(source) else {
+        return Vec::new();
+    };
+    source[..raw.header_offset + raw.header.len()]
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('#'))
+        .filter_map(|comment| comment.trim().strip_prefix(SUPPRESS_COMMENT))
+        .flat_map(|list| list.split(','))
+        .map(|rule| rule.trim().to_string())
+        .filter(|rule| !rule.is_empty())
+        .collect()
+}
+
+/// `tone` suppresses `tone.flat_words`; `*` suppresses everything.
+fn suppresses(pattern: &str, rule: &str) -> bool {
+    pattern == "*"
+        || pattern == rule
+        || rule
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
@@ -0,0 +1,414 @@
+//! Rule engine behind `echo_all()`: turns a `ValidatorSpec` into rules and
+//! evaluates each of them against a scroll or a whole archive.
+// src/validator/rules.rs
+
+use std::fs;
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::archive::archive_loader::{scan_archive, LoadOptions};
+use crate::archive::archive_memory::ArchiveMemory;
+use crate::archive::error::ArchiveError;
+use crate::parser::{
+    self, split_front_matter, ParseMode, DEFAULT_INVOCATION_PHRASE, DEFAULT_SIGIL,
+};
+use crate::schema::ScrollStatus;
+use crate::scroll::Scroll;
+use crate::validator::contradiction::ContradictionChecker;
+use crate::validator::report::{Severity, ValidationReport};
+use crate::validator::spec::{AccessCheck, ContradictionCheck, ToneCheck, ValidatorSpec};
+use crate::validator::validate_scroll;
+
+/// Where in a scroll a violation points.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    /// The scroll as a whole; reported at the header delimiter.
+    Scroll,
+    /// A top-level header key.
+    Field(String),
+    /// A 1-based line of the markdown body.
+    BodyLine(usize),
+}
+
+/// A problem found by one rule, before it is attributed to a scroll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub message: String,
+    pub location: Location,
+}
+
+impl Issue {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            location: Location::Scroll,
+        }
+    }
+
+    pub fn at(mut self, location: Location) -> Self {
+        self.location = location;
+        self
+    }
+}
+
+/// One rule that a scroll failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleViolation {
+    /// Dotted rule id such as `structure.required_field`.
+    pub rule: String,
+    pub severity: Severity,
+    pub scroll_id: Uuid,
+    pub message: String,
+    pub location: Location,
+}
+
+/// Who is asking and why; access rules only apply to edits.
+#[derive(Debug, Clone, Default)]
+pub struct RuleContext {
+    pub actor: Option<String>,
+    pub editing: bool,
+}
+
+impl RuleContext {
+    pub fn edit_by(actor: impl Into<String>) -> Self {
+        Self {
+            actor: Some(actor.into()),
+            editing: true,
+        }
+    }
+}
+
+pub trait Rule: Send + Sync {
+    fn id(&self) -> &str;
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    /// Returns one issue per problem found.
+    fn check(&self, scroll: &Scroll, context: &RuleContext) -> Vec<Issue>;
+}
+
+#[derive(Default)]
+pub struct RuleEngine {
+    rules: Vec<Box<dyn Rule>>,
+    /// Cross-scroll checks run after the per-scroll rules in archive sweeps.
+    contradictions: Option<ContradictionChecker>,
+}
+
+impl RuleEngine {
+    /// Rules for every check declared in `spec`, after the schema rule that
+    /// `validate_scroll` has always applied.
+    pub fn from_spec(spec: &ValidatorSpec) -> Self {
+        let mut engine = Self::default().with_rule(SchemaRule);
+        if let Some(structure) = &spec.structure_check {
+            if structure.must_have_yaml_header {
+                engine = engine.with_rule(HeaderRule);
+            }
+            engine = engine.with_rule(RequiredFieldsRule {
+                fields: structure.required_fields.clone(),
+            });
+        }
+        if let Some(tone) = &spec.tone_check {
+            engine = engine.with_rule(ToneRule(tone.clone()));
+        }
+        if let Some(contradiction) = &spec.contradiction_check {
+            engine = engine.with_rule(FlagTermsRule(contradiction.clone()));
+            engine.contradictions = Some(ContradictionChecker::from_spec(contradiction));
+        }
+        if let Some(access) = &spec.access_check {
+            engine = engine.with_rule(AccessRule(access.clone()));
+        }
+        engine
+    }
+
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    pub fn with_contradiction_checker(mut self, checker: ContradictionChecker) -> Self {
+        self.contradictions = Some(checker);
+        self
+    }
+
+    pub fn rule_ids(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.id()).collect()
+    }
+
+    /// Runs every rule and collects all violations.
+    pub fn evaluate(&self, scroll: &Scroll, context: &RuleContext) -> Vec<RuleViolation> {
+        self.rules
+            .iter()
+            .flat_map(|rule| {
+                rule.check(scroll, context)
+                    .into_iter()
+                    .map(|issue| RuleViolation {
+                        rule: rule.id().to_string(),
+                        severity: rule.severity(),
+                        scroll_id: scroll.id,
+                        message: issue.message,
+                        location: issue.location,
+                    })
+            })
+            .collect()
+    }
+
+    /// The full validation sweep: every rule against every archived scroll,
+    /// ordered by scroll title.
+    pub fn echo_all(&self, archive: &dyn ArchiveMemory) -> ValidationReport {
+        let mut scrolls = archive.get_all_scrolls();
+        scrolls.sort_by(|a, b| a.title.cmp(&b.title).then(a.id.cmp(&b.id)));
+        let context = RuleContext::default();
+        let mut report = ValidationReport::default();
+        for scroll in &scrolls {
+            report.add_scroll(scroll, self.evaluate(scroll, &context));
+        }
+        self.cross_check(&scrolls, &mut report);
+        report
+    }
+
+    /// Parses and checks every scroll file the archive loader would read
+    /// under `archive_root`, honouring `.scrollignore` rules.
+    /// Files that fail to parse are reported as `parse` errors instead of
+    /// aborting the run.
+    pub fn validate_directory(
+        &self,
+        archive_root: &Path,
+    ) -> Result<ValidationReport, ArchiveError> {
+        let (paths, scan) = scan_archive(archive_root, &LoadOptions::default())
+            .map_err(|e| ArchiveError::Io(std::io::Error::other(e)))?;
+
+        let context = RuleContext::default();
+        let mut report = ValidationReport::default();
+        for failure in &scan.failed {
+            report.add_parse_error(&failure.error);
+        }
+        let mut scrolls = Vec::new();
+        for path in paths {
+            match parser::parse_archive_scroll(archive_root, &path, ParseMode::Lenient) {
+                Ok(scroll) => {
+                    report.add_scroll(&scroll, self.evaluate(&scroll, &context));
+                    scrolls.push(scroll);
+                }
+                Err(e) => report.add_parse_error(&e),
+            }
+        }
+        let scrolls: Vec<&Scroll> = scrolls.iter().collect();
+        self.cross_check(&scrolls, &mut report);
+        Ok(report)
+    }
+
+    fn cross_check(&self, scrolls: &[&Scroll], report: &mut ValidationReport) {
+        if let Some(checker) = &self.contradictions {
+            report.add_contradictions(scrolls, checker.check_scrolls(scrolls));
+        }
+    }
+}
+
+/// Title and type-registry checks from `validate_scroll`.
+struct SchemaRule;
+
+impl Rule for SchemaRule {
+    fn id(&self) -> &str {
+        "structure.schema"
+    }
+
+    fn check(&self, scroll: &Scroll, _: &RuleContext) -> Vec<Issue> {
+        let field = if scroll.yaml_metadata.title.trim().is_empty() {
+            "title"
+        } else {
+            "scroll_type"
+        };
+        validate_scroll(&scroll.yaml_metadata)
+            .err()
+            .into_iter()
+            .map(|message| Issue::new(message).at(Location::Field(field.into())))
+            .collect()
+    }
+}
+
+/// The scroll's source file opens with a front-matter header.
+struct HeaderRule;
+
+impl Rule for HeaderRule {
+    fn id(&self) -> &str {
+        "structure.yaml_header"
+    }
+
+    fn check(&self, scroll: &Scroll, _: &RuleContext) -> Vec<Issue> {
+        let Some(path) = &scroll.origin.source_path else {
+            return Vec::new();
+        };
+        match fs::read_to_string(path) {
+            Ok(text) if split_front_matter(&text).is_some() => Vec::new(),
+            Ok(_) => vec![Issue::new("scroll has no front-matter header")],
+            Err(e) => vec![Issue::new(format!(
+                "could not read {}: {e}",
+                path.display()
+            ))],
+        }
+    }
+}
+
+struct RequiredFieldsRule {
+    fields: Vec<String>,
+}
+
+impl Rule for RequiredFieldsRule {
+    fn id(&self) -> &str {
+        "structure.required_field"
+    }
+
+    fn check(&self, scroll: &Scroll, _: &RuleContext) -> Vec<Issue> {
+        self.fields
+            .iter()
+            .filter(|field| !scroll_has_field(scroll, field))
+            .map(|field| Issue::new(format!("missing required field `{field}`")))
+            .collect()
+    }
+}
+
+/// Checks scroll-level fields first (accepting the legacy `invocation`
+/// spelling), then header extensions. The sigil and invocation phrase the
+/// parser fills in for headers that omit them do not count.
+fn scroll_has_field(scroll: &Scroll, field: &str) -> bool {
+    match field {
+        "title" => !scroll.title.trim().is_empty(),
+        "invocation" | "invocation_phrase" => {
+            let phrase = scroll.invocation_phrase.trim();
+            !phrase.is_empty() && phrase != DEFAULT_INVOCATION_PHRASE
+        }
+        "sigil" => {
+            let sigil = scroll.sigil.trim();
+            !sigil.is_empty() && sigil != DEFAULT_SIGIL
+        }
+        "emotion_signature" | "emotion" => !scroll.emotion_signature.is_empty(),
+        "tags" => !scroll.tags.is_empty(),
+        "archetype" => scroll.archetype.is_some(),
+        "authored_by" => scroll.origin.authored_by.is_some(),
+        "linked_scrolls" => {
+            !scroll.linked_scrolls.is_empty() || !scroll.yaml_metadata.linked_scrolls.is_empty()
+        }
+        "id" | "scroll_type" | "status" | "quorum_required" => true,
+        other => scroll
+            .yaml_metadata
+            .extension(other)
+            .is_some_and(|value| !value.is_null()),
+    }
+}
+
+struct ToneRule(ToneCheck);
+
+impl Rule for ToneRule {
+    fn id(&self) -> &str {
+        "tone.flat_words"
+    }
+
+    /// Tone drift warns rather than blocks, per the spec.
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, scroll: &Scroll, _: &RuleContext) -> Vec<Issue> {
+        let body = &scroll.markdown_body;
+        if let Some(token) = self.0.bypass_token.as_deref() {
+            if body.contains(token) {
+                return Vec::new();
+            }
+        }
+        let flat = words(body)
+            .filter(|word| {
+                self.0
+                    .flat_words
+                    .iter()
+                    .any(|f| f.eq_ignore_ascii_case(word))
+            })
+            .count();
+        if flat > self.0.max_flat_words {
+            vec![Issue::new(format!(
+                "{flat} flat words (at most {} allowed)",
+                self.0.max_flat_words
+            ))]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Mentions of `auto_flag_terms` in the body, for contradiction review.
+struct FlagTermsRule(ContradictionCheck);
+
+impl Rule for FlagTermsRule {
+    fn id(&self) -> &str {
+        "contradiction.flag_term"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, scroll: &Scroll, _: &RuleContext) -> Vec<Issue> {
+        let lines: Vec<String> = scroll
+            .markdown_body
+            .lines()
+            .map(str::to_lowercase)
+            .collect();
+        self.0
+            .auto_flag_terms
+            .iter()
+            .filter(|term| !term.trim().is_empty())
+            .filter_map(|term| {
+                let needle = term.to_lowercase();
+                let line = lines.iter().position(|line| line.contains(&needle))?;
+                Some(
+                    Issue::new(format!("mentions flagged term '{term}'"))
+                        .at(Location::BodyLine(line + 1)),
+                )
+            })
+            .collect()
+    }
+}
+
+struct AccessRule(AccessCheck);
+
+impl Rule for AccessRule {
+    fn id(&self) -> &str {
+        "access.deny_edit"
+    }
+
+    fn check(&self, scroll: &Scroll, context: &RuleContext) -> Vec<Issue> {
+        if !context.editing || !self.0.deny_edit {
+            return Vec::new();
+        }
+        let status = self
+            .0
+            .if_status
+            .as_deref()
+            .and_then(|s| s.parse::<ScrollStatus>().ok())
+            .unwrap_or(ScrollStatus::Sealed);
+        if scroll.status != status {
+            return Vec::new();
+        }
+        let allowed = context.actor.as_deref().is_some_and(|actor| {
+            self.0
+                .allowed_overrides
+                .iter()
+                .any(|o| o.eq_ignore_ascii_case(actor))
+        });
+        if allowed {
+            Vec::new()
+        } else {
+            vec![Issue::new(format!(
+                "{:?} scrolls cannot be edited by {}",
+                status,
+                context.actor.as_deref().unwrap_or("an unnamed actor")
+            ))
+            .at(Location::Field("status".into()))]
+        }
+    }
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+}
@@ -0,0 +1,153 @@
+//! Typed form of the `validator_spec` block defined in
+//! `Scrollbook_Validator_Specs.md`. Specs load from plain YAML or from the
+//! first `yaml` block of a markdown scroll that declares one.
+// src/validator/spec.rs
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use crate::parser::body::CodeBlock;
+use crate::parser::ScrollBody;
+
+/// Key the spec is nested under in scrolls and config files.
+pub const SPEC_KEY: &str = "validator_spec";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorSpec {
+    #[serde(default)]
+    pub structure_check: Option<StructureCheck>,
+    #[serde(default)]
+    pub tone_check: Option<ToneCheck>,
+    #[serde(default)]
+    pub contradiction_check: Option<ContradictionCheck>,
+    #[serde(default)]
+    pub access_check: Option<AccessCheck>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructureCheck {
+    #[serde(default)]
+    pub must_have_yaml_header: bool,
+    /// Header or scroll fields that must be present and non-empty.
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToneCheck {
+    /// Scroll whose register the tone is measured against.
+    #[serde(default)]
+    pub compare_to: Option<String>,
+    pub max_flat_words: usize,
+    /// Token that exempts a body from the tone check.
+    #[serde(default)]
+    pub bypass_token: Option<String>,
+    /// Words counted as flat; defaults to [`DEFAULT_FLAT_WORDS`].
+    #[serde(default = "default_flat_words")]
+    pub flat_words: Vec<String>,
+}
+
+/// Everyday filler the tone check counts when a spec lists none.
+pub const DEFAULT_FLAT_WORDS: &[&str] = &[
+    "basically",
+    "stuff",
+    "things",
+    "okay",
+    "ok",
+    "nice",
+    "very",
+    "really",
+    "just",
+    "simply",
+];
+
+fn default_flat_words() -> Vec<String> {
+    DEFAULT_FLAT_WORDS.iter().map(|w| w.to_string()).collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContradictionCheck {
+    #[serde(default)]
+    pub cross_ref: Option<String>,
+    /// Terms that flag a scroll for contradiction review when they appear.
+    #[serde(default)]
+    pub auto_flag_terms: Vec<String>,
+    /// Contradiction kinds with their descriptions, e.g. `mythic: ...`.
+    #[serde(default)]
+    pub types: Vec<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessCheck {
+    /// Status the check applies to, e.g. `sealed`.
+    #[serde(default)]
+    pub if_status: Option<String>,
+    #[serde(default)]
+    pub deny_edit: bool,
+    /// Actors allowed to edit despite `deny_edit`.
+    #[serde(default)]
+    pub allowed_overrides: Vec<String>,
+}
+
+impl ValidatorSpec {
+    /// Parses a spec, either bare or nested under `validator_spec:`.
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_yaml::from_str(text).map_err(|e| format!("invalid validator spec: {e}"))?;
+        let spec = value.get(SPEC_KEY).cloned().unwrap_or(value);
+        serde_yaml::from_value(spec).map_err(|e| format!("invalid validator spec: {e}"))
+    }
+
+    /// Reads the first `validator_spec` block embedded in a markdown scroll.
+    pub fn from_markdown(markdown: &str) -> Result<Self, String> {
+        ScrollBody::parse(markdown)
+            .yaml_blocks()
+            .into_iter()
+            .find_map(spec_block)
+            .ok_or_else(|| format!("no `{SPEC_KEY}` block found"))?
+    }
+
+    /// Loads a spec from a `.md` scroll or a YAML file.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("could not read {}: {e}", path.display()))?;
+        let markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"));
+        if markdown {
+            Self::from_markdown(&text)
+        } else {
+            Self::from_yaml(&text)
+        }
+    }
+}
+
+/// Parses a block that declares `validator_spec`. An unclosed fence swallows
+/// the prose after it, so such blocks are cut back at blank lines until the
+/// YAML parses.
+fn spec_block(block: &CodeBlock) -> Option<Result<ValidatorSpec, String>> {
+    if !block.content.contains(SPEC_KEY) {
+        return None;
+    }
+    if block.closed {
+        return Some(ValidatorSpec::from_yaml(&block.content));
+    }
+    let mut cuts: Vec<usize> = block
+        .content
+        .match_indices("\n\n")
+        .map(|(at, _)| at + 1)
+        .collect();
+    cuts.push(block.content.len());
+    let result = cuts
+        .into_iter()
+        .rev()
+        .map(|cut| ValidatorSpec::from_yaml(&block.content[..cut]))
+        .find(Result::is_ok)
+        .unwrap_or_else(|| ValidatorSpec::from_yaml(&block.content));
+    Some(result)
+}
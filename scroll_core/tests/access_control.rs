@@ -0,0 +1,141 @@
+mod common;
+
+use scroll_core::access::{AccessDecision, AccessLedger, Actor, WriteAction};
+use scroll_core::archive::archive_loader::LoadOptions;
+use scroll_core::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use scroll_core::archive::hybrid_archive::{DirectoryStore, HybridCacheArchive};
+use scroll_core::parser::{parse_scroll_from_file_with_mode, ParseMode};
+use scroll_core::scroll_writer::{ScrollPatch, ScrollWriter};
+use scroll_core::ScrollStatus;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+use common::write_scroll;
+
+fn write(path: &Path, status: &str) {
+    let status = format!("status: {status}\n");
+    write_scroll(path, "Vault Door", &status, "The door holds.\n");
+}
+
+fn retitle(title: &str) -> ScrollPatch {
+    ScrollPatch {
+        title: Some(title.into()),
+        markdown_body: None,
+        tags: None,
+        sigil: None,
+    }
+}
+
+#[test]
+fn sealed_scrolls_refuse_edits_without_override() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("vault_door.md");
+    write(&path, "Sealed");
+    let before = fs::read_to_string(&path).unwrap();
+    let id = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient)
+        .unwrap()
+        .id;
+
+    let err = ScrollWriter::update_scroll(id, retitle("Open Door"), &path, &Actor::new("Wanderer"))
+        .unwrap_err();
+    assert!(err.contains("Access denied"), "{err}");
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+    let keeper = Actor::new("Kael").with_role("quorum");
+    ScrollWriter::update_scroll(id, retitle("Open Door"), &path, &keeper).unwrap();
+    let updated = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    assert_eq!(updated.title, "Open Door");
+    assert_eq!(updated.status, ScrollStatus::Sealed);
+
+    let records = AccessLedger::global().for_scroll(id);
+    let summary: Vec<(&str, WriteAction, &AccessDecision)> = records
+        .iter()
+        .map(|r| (r.actor.name.as_str(), r.action, &r.decision))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("Wanderer", WriteAction::Update, &AccessDecision::Denied),
+            (
+                "Kael",
+                WriteAction::Update,
+                &AccessDecision::Overridden("Quorum".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn seal_persists_and_archived_scrolls_are_protected() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("archive_door.md");
+    write(&path, "Active");
+    let scribe = Actor::new("Scribe");
+
+    let mut scroll = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    ScrollWriter::seal_scroll(&mut scroll, &path, &scribe).unwrap();
+    let sealed = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    assert_eq!(sealed.status, ScrollStatus::Sealed);
+    assert!(AccessLedger::global().for_scroll(scroll.id).is_empty());
+
+    let mut again = sealed.clone();
+    assert!(ScrollWriter::seal_scroll(&mut again, &path, &scribe).is_err());
+
+    write(&path, "Archived");
+    let archived = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    assert!(ScrollWriter::write_scroll_as(&archived, &path, &scribe).is_err());
+    ScrollWriter::write_scroll_as(&archived, &path, &Actor::new("Prime Seeker")).unwrap();
+    let actions: Vec<WriteAction> = AccessLedger::global()
+        .for_scroll(archived.id)
+        .iter()
+        .map(|r| r.action)
+        .collect();
+    assert_eq!(
+        actions,
+        [WriteAction::Seal, WriteAction::Write, WriteAction::Write]
+    );
+}
+
+#[test]
+fn unreadable_files_are_not_overwritten() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("vault_door.md");
+    write(&path, "Active");
+    let scroll = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    fs::write(&path, "---\ntitle: [unclosed\n---\nThe door holds.\n").unwrap();
+
+    let err =
+        ScrollWriter::write_scroll_as(&scroll, &path, &Actor::new("Prime Seeker")).unwrap_err();
+    assert!(err.contains("could not be parsed"), "{err}");
+    assert!(fs::read_to_string(&path).unwrap().contains("[unclosed"));
+}
+
+#[test]
+fn archive_writes_are_access_checked() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("vault_door.md");
+    write(&path, "Sealed");
+    let before = fs::read_to_string(&path).unwrap();
+    let mut sealed = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    sealed.markdown_body = "The door gives way.\n".into();
+
+    let mut memory = InMemoryArchive::new(vec![sealed.clone()]);
+    let wanderer = Actor::new("Wanderer");
+    assert!(ScrollWriter::write_to_archive(&sealed, &path, &wanderer, &mut memory).is_err());
+
+    let store = DirectoryStore::open(dir.path(), LoadOptions::default()).unwrap();
+    let mut archive = HybridCacheArchive::new(store, 2).unwrap();
+    let mut stored = archive.get_all_scrolls()[0].clone();
+    stored.markdown_body = "The door gives way.\n".into();
+    let err = archive.update(stored.clone()).unwrap_err();
+    assert!(err.to_string().contains("Access denied"), "{err}");
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+    let store = DirectoryStore::open(dir.path(), LoadOptions::default()).unwrap();
+    let mut archive = HybridCacheArchive::new(store, 2)
+        .unwrap()
+        .with_actor(Actor::new("Kael").with_role("Override Agent"));
+    archive.update(stored).unwrap();
+    assert!(fs::read_to_string(&path).unwrap().contains("gives way"));
+}
@@ -0,0 +1,134 @@
+use assert_cmd::Command;
+use predicates::str::contains;
+use scroll_core::archive::integrity::{check_archive_integrity, IntegrityIssue, IntegrityLevel};
+use scroll_core::scroll::{Scroll, ScrollLink, ScrollLinkType};
+use std::fs;
+use tempfile::tempdir;
+use uuid::Uuid;
+
+fn scroll(title: &str) -> Scroll {
+    Scroll::builder(title).build()
+}
+
+#[test]
+fn reports_every_kind_of_issue() {
+    let (mut a, mut b, c, mut d, mut e, mut f) = (
+        scroll("Root"),
+        scroll("Branch"),
+        scroll("Leaf"),
+        scroll("Mirror"),
+        scroll("Lonely"),
+        scroll("lonely"),
+    );
+    a.link_to(&b, ScrollLinkType::Ancestor);
+    b.link_to(&c, ScrollLinkType::Ancestor);
+    a.link_to(&c, ScrollLinkType::Derivative);
+    d.link_to(&a, ScrollLinkType::Reflection);
+    let missing = Uuid::new_v4();
+    b.linked_scrolls.push(ScrollLink {
+        target: missing,
+        link_type: ScrollLinkType::Binding,
+        source: None,
+    });
+    e.linked_scrolls.push(ScrollLink {
+        target: e.id,
+        link_type: ScrollLinkType::Derivative,
+        source: None,
+    });
+    f.linked_scrolls.push(ScrollLink {
+        target: f.id,
+        link_type: ScrollLinkType::Echo,
+        source: None,
+    });
+    let scrolls = vec![
+        a.clone(),
+        b.clone(),
+        c.clone(),
+        d.clone(),
+        e.clone(),
+        f.clone(),
+    ];
+
+    let report = check_archive_integrity(&scrolls);
+    let mut cycle = vec![a.id, b.id, c.id];
+    let min = (0..3).min_by_key(|&i| cycle[i]).unwrap();
+    cycle.rotate_left(min);
+    assert_eq!(
+        report.issues,
+        [
+            IntegrityIssue::DanglingLink {
+                scroll_id: b.id,
+                target: missing,
+                link_type: ScrollLinkType::Binding,
+            },
+            IntegrityIssue::AncestryCycle {
+                scrolls: vec![e.id],
+            },
+            IntegrityIssue::SelfLink {
+                scroll_id: f.id,
+                link_type: ScrollLinkType::Echo,
+            },
+            IntegrityIssue::AncestryCycle { scrolls: cycle },
+            IntegrityIssue::AsymmetricReflection {
+                scroll_id: d.id,
+                target: a.id,
+            },
+            IntegrityIssue::Orphan { scroll_id: e.id },
+            IntegrityIssue::Orphan { scroll_id: f.id },
+            IntegrityIssue::DuplicateTitle {
+                title: "Lonely".into(),
+                scrolls: vec![e.id, f.id],
+            },
+        ]
+    );
+    assert_eq!(report.error_count(), 3);
+    assert!(report
+        .render_text()
+        .contains("warning: lonely has a Echo link to itself\n"));
+    assert!(report
+        .render_text()
+        .contains("warning: Mirror reflects Root but is not reflected back\n"));
+
+    assert!(report.enforce(IntegrityLevel::Warn).is_ok());
+    assert!(report.enforce(IntegrityLevel::Strict).is_err());
+    assert_eq!("STRICT".parse(), Ok(IntegrityLevel::Strict));
+    assert!("loud".parse::<IntegrityLevel>().is_err());
+}
+
+#[test]
+fn symmetric_archive_is_clean() {
+    let (mut a, mut b) = (scroll("Root"), scroll("Echo"));
+    a.link_to(&b, ScrollLinkType::Reflection);
+    b.link_to(&a, ScrollLinkType::Reflection);
+    let report = check_archive_integrity(&[a, b]);
+    assert!(report.issues.is_empty());
+    assert!(report.enforce(IntegrityLevel::Strict).is_ok());
+}
+
+#[test]
+fn integrity_command_fails_on_cycles() {
+    let dir = tempdir().unwrap();
+    let write = |name: &str, body: &str| {
+        let text = format!("---\ntitle: {name}\nscroll_type: Canon\n---\n{body}\n");
+        fs::write(dir.path().join(format!("{name}.md")), text).unwrap();
+    };
+    write("Elder", "See [[ancestor:Younger]].");
+    write("Younger", "See [[ancestor:Elder]].");
+
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["integrity", "--archive"])
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stdout(contains("error: ancestry cycle:"));
+
+    write("Younger", "See [[Elder]].");
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["integrity", "--archive"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(contains("2 scroll(s) checked: 0 error(s), 0 warning(s)"));
+}
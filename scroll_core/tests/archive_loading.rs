@@ -0,0 +1,136 @@
+mod common;
+
+use scroll_core::archive::archive_loader::{load_archive, LoadOptions, SkipReason};
+use scroll_core::archive::scroll_ignore::ScrollIgnore;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+use common::write_scroll;
+
+fn names(paths: &[PathBuf], root: &Path) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn loads_recursively_and_reports_skips_and_failures() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_scroll(&root.join("top.md"), "Top", "", "Body.\n");
+    write_scroll(&root.join("system/notes.txt"), "Notes", "", "Body.\n");
+    write_scroll(
+        &root.join("system/deep/core.markdown"),
+        "Core",
+        "",
+        "Body.\n",
+    );
+    write_scroll(&root.join("drafts/wip.md"), "Wip", "", "Body.\n");
+    write_scroll(&root.join("system/scratch.md"), "Scratch", "", "Body.\n");
+    write_scroll(
+        &root.join("system/keep-scratch.md"),
+        "Keeper",
+        "",
+        "Body.\n",
+    );
+    fs::write(root.join("image.png"), "png").unwrap();
+    fs::write(root.join("broken.md"), "---\ntitle: [unclosed\n---\n").unwrap();
+    fs::write(root.join(".scrollignore"), "# drafts stay out\ndrafts/\n").unwrap();
+    fs::write(root.join("system/.scrollignore"), "*scratch.md\n!keep-*\n").unwrap();
+
+    let (scrolls, report) = load_archive(root, &LoadOptions::default()).unwrap();
+    assert_eq!(
+        names(&report.loaded, root),
+        [
+            "system/deep/core.markdown",
+            "system/keep-scratch.md",
+            "system/notes.txt",
+            "top.md"
+        ]
+    );
+    assert_eq!(scrolls.len(), 4);
+    let skipped: Vec<(String, SkipReason)> = report
+        .skipped
+        .iter()
+        .map(|s| {
+            (
+                names(std::slice::from_ref(&s.path), root).remove(0),
+                s.reason,
+            )
+        })
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            ("drafts".to_string(), SkipReason::Ignored),
+            ("image.png".to_string(), SkipReason::Extension),
+            ("system/scratch.md".to_string(), SkipReason::Ignored),
+        ]
+    );
+    assert!(!report.is_clean());
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].path, root.join("broken.md"));
+    assert!(report
+        .summary()
+        .starts_with("Loaded 4 scroll(s), skipped 3, failed 1"));
+
+    let options = LoadOptions::default()
+        .with_extensions(["md"])
+        .recursive(false);
+    let (_, report) = load_archive(root, &options).unwrap();
+    assert_eq!(names(&report.loaded, root), ["top.md"]);
+}
+
+#[test]
+fn ignore_rules_follow_gitignore_globs() {
+    let rules = ScrollIgnore::parse("/build\n**/tmp/*.md\nsecret?.md\n[._]*\n!_index.md\n");
+    let ignored = |path: &str, dir: bool| rules.matched(Path::new(path), dir);
+    assert_eq!(ignored("build", true), Some(true));
+    assert_eq!(ignored("lore/build", true), None);
+    assert_eq!(ignored("tmp/a.md", false), Some(true));
+    assert_eq!(ignored("lore/tmp/a.md", false), Some(true));
+    assert_eq!(ignored("lore/tmp/sub/a.md", false), None);
+    assert_eq!(ignored("lore/secret1.md", false), Some(true));
+    assert_eq!(ignored("lore/secret12.md", false), None);
+    assert_eq!(ignored(".hidden.md", false), Some(true));
+    assert_eq!(ignored("_index.md", false), Some(false));
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_are_skipped_unless_followed() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("archive");
+    let shared = dir.path().join("shared");
+    write_scroll(&root.join("own.md"), "Own", "", "Body.\n");
+    write_scroll(&shared.join("borrowed.md"), "Borrowed", "", "Body.\n");
+    symlink(&shared, root.join("shared")).unwrap();
+    symlink(&root, root.join("loop")).unwrap();
+
+    let (_, report) = load_archive(&root, &LoadOptions::default()).unwrap();
+    assert_eq!(names(&report.loaded, &root), ["own.md"]);
+    assert!(report
+        .skipped
+        .iter()
+        .all(|s| s.reason == SkipReason::Symlink));
+
+    let options = LoadOptions::default().follow_symlinks(true);
+    let (scrolls, report) = load_archive(&root, &options).unwrap();
+    assert_eq!(
+        names(&report.loaded, &root),
+        ["own.md", "shared/borrowed.md"]
+    );
+    assert_eq!(scrolls.len(), 2);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].reason, SkipReason::Loop);
+    assert!(report.is_clean());
+}
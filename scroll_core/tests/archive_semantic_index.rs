@@ -9,6 +9,6 @@ fn builds_index_for_all_scrolls() {
         Scroll::builder("two").body("body").build(),
     ];
     let mut archive = InMemoryArchive::new(scrolls);
-    archive.build_semantic_index(&TokenEmbedder).unwrap();
+    archive.build_semantic_index(TokenEmbedder).unwrap();
     assert_eq!(archive.semantic_index_len(), 2);
 }
@@ -0,0 +1,107 @@
+mod common;
+
+use scroll_core::archive::archive_loader::{load_archive, LoadOptions};
+use scroll_core::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use scroll_core::archive::semantic_index::TokenEmbedder;
+use scroll_core::archive::watcher::{ArchiveChange, ArchiveWatcher, ARCHIVE_CHANGE_KIND};
+use scroll_core::cache_manager::CacheManager;
+use scroll_core::orchestra::Bus;
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+
+use common::write_scroll;
+
+#[test]
+fn polling_reports_and_applies_only_what_changed() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_scroll(&root.join("river.md"), "River", "", "It flows.\n");
+    write_scroll(&root.join("stone.md"), "Stone", "", "It waits.\n");
+    let (scrolls, _) = load_archive(root, &LoadOptions::default()).unwrap();
+    let mut archive = InMemoryArchive::new(scrolls.clone());
+    archive.build_semantic_index(TokenEmbedder).unwrap();
+    let mut cache = CacheManager::new(10);
+    let mut watcher = ArchiveWatcher::new(root, LoadOptions::default(), &scrolls);
+    assert!(watcher.poll().is_empty());
+
+    write_scroll(
+        &root.join("river.md"),
+        "Flooded River",
+        "",
+        "It floods the valley.\n",
+    );
+    fs::create_dir(root.join("peaks")).unwrap();
+    write_scroll(&root.join("peaks/summit.md"), "Summit", "", "It rises.\n");
+    fs::remove_file(root.join("stone.md")).unwrap();
+    fs::write(root.join("broken.md"), "---\ntitle: [unclosed\n---\n").unwrap();
+
+    let changes = watcher.poll();
+    let mut kinds: Vec<(&str, String)> = changes
+        .iter()
+        .map(|c| {
+            let title = match c {
+                ArchiveChange::Created(s) | ArchiveChange::Modified(s) => s.title.clone(),
+                ArchiveChange::Removed { path, .. } => {
+                    path.file_name().unwrap().to_string_lossy().into()
+                }
+            };
+            (c.kind(), title)
+        })
+        .collect();
+    kinds.sort();
+    assert_eq!(
+        kinds,
+        [
+            ("created", "Summit".to_string()),
+            ("modified", "Flooded River".to_string()),
+            ("removed", "stone.md".to_string()),
+        ]
+    );
+    assert!(watcher.poll().is_empty());
+
+    for change in &changes {
+        archive.apply_change(change).unwrap();
+        cache.apply_change(change);
+    }
+    let mut titles: Vec<String> = archive
+        .get_all_scrolls()
+        .iter()
+        .map(|s| s.title.clone())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["Flooded River", "Summit"]);
+    assert_eq!(archive.semantic_index_len(), 2);
+    let top = archive.query_semantic("flooded valley", 1);
+    assert_eq!(top[0].0.title, "Flooded River");
+    assert_eq!(cache.count(), 1);
+
+    let mut memory = scrolls.clone();
+    for change in &changes {
+        change.apply_to(&mut memory);
+    }
+    assert_eq!(memory.len(), 2);
+}
+
+#[test]
+fn spawned_watcher_publishes_changes_on_the_bus() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    let mut bus = Bus::new();
+    let listener = bus.subscribe("loreweaver");
+    let handle = ArchiveWatcher::new(root, LoadOptions::default(), &[])
+        .spawn(Duration::from_millis(20), Some(bus));
+
+    write_scroll(&root.join("dawn.md"), "Dawn", "", "Light returns.\n");
+    let change = handle
+        .recv_timeout(Duration::from_secs(5))
+        .expect("change reported");
+    assert_eq!(change.kind(), "created");
+
+    let msg = listener.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(msg.payload["kind"], ARCHIVE_CHANGE_KIND);
+    assert_eq!(msg.payload["change"], "created");
+    assert_eq!(msg.payload["title"], "Dawn");
+    assert_eq!(msg.payload["scroll_id"], change.id().to_string());
+    drop(handle);
+}
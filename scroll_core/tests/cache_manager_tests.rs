@@ -28,6 +28,8 @@ fn dummy_scroll(intensity: f32) -> Scroll {
             quorum_required: false,
             last_modified: None,
             file_path: None,
+            linked_scrolls: vec![],
+            extensions: Default::default(),
         },
         tags: vec![],
         archetype: None,
@@ -47,6 +49,8 @@ fn dummy_scroll(intensity: f32) -> Scroll {
             created: Utc::now(),
             authored_by: None,
             last_modified: Utc::now(),
+            source_path: None,
+            format: Default::default(),
         },
     }
 }
@@ -1,9 +1,23 @@
+mod common;
+
 use assert_cmd::Command;
 use predicates::str::contains;
 use sqlx::SqlitePool;
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::Stdio;
+use std::thread::sleep;
+use std::time::Duration;
 use tempfile::tempdir;
 
+use common::write_scroll;
+
+fn indexed(path: &Path) -> usize {
+    let index: serde_json::Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    index["entries"].as_array().unwrap().len()
+}
+
 #[tokio::test]
 async fn chat_cli_records() {
     let dir = tempdir().unwrap();
@@ -43,3 +57,56 @@ async fn chat_cli_records() {
     let count = row.0;
     assert!(count >= 2);
 }
+
+#[test]
+fn chat_saves_the_semantic_index_after_watcher_changes() {
+    let dir = tempdir().unwrap();
+    let archive = dir.path().join("scrolls");
+    fs::create_dir(&archive).unwrap();
+    let index_path = archive.join("semantic_index.json");
+    write_scroll(
+        &archive.join("rust.md"),
+        "Rust",
+        "tags: [lore]\n",
+        "Rust body.\n",
+    );
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("scroll_core"))
+        .env("SCROLL_CORE_USE_MOCK", "1")
+        .env("SCROLL_CI", "1")
+        .env("SCROLL_CORE_ARCHIVE_DIR", &archive)
+        .env("CHAT_DB_PATH", ":memory:")
+        .current_dir(dir.path())
+        .args(["chat", "mythscribe", "--no-banner"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+    let mut reply = || {
+        stdout
+            .by_ref()
+            .map(Result::unwrap)
+            .find(|line| line.contains("pong"))
+            .unwrap()
+    };
+
+    writeln!(stdin, "ping").unwrap();
+    reply();
+    assert_eq!(indexed(&index_path), 1);
+
+    write_scroll(
+        &archive.join("cook.md"),
+        "Cook",
+        "tags: [lore]\n",
+        "Cook body.\n",
+    );
+    sleep(Duration::from_millis(2500));
+    writeln!(stdin, "ping").unwrap();
+    reply();
+    assert_eq!(indexed(&index_path), 2);
+
+    writeln!(stdin, "exit").unwrap();
+    assert!(child.wait().unwrap().success());
+}
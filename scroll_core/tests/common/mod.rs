@@ -0,0 +1,19 @@
+//! Scroll file fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+/// Text of a Canon scroll file titled `title`. `extra_header` holds more
+/// header lines, each ending in a newline, placed above the title.
+pub fn scroll_file(title: &str, extra_header: &str, body: &str) -> String {
+    format!(
+        "---\n{extra_header}title: {title}\nscroll_type: Canon\nemotion_signature: calm // deep\n---\n{body}"
+    )
+}
+
+/// Writes [`scroll_file`] to `path`, creating its directory if needed.
+pub fn write_scroll(path: &Path, title: &str, extra_header: &str, body: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, scroll_file(title, extra_header, body)).unwrap();
+}
@@ -20,7 +20,7 @@ fn test_context_engine_semantic_recall() {
         .sigil("🔮")
         .build();
     let mut archive = InMemoryArchive::new(vec![s1.clone(), s2]);
-    archive.build_semantic_index(&TokenEmbedder).unwrap();
+    archive.build_semantic_index(TokenEmbedder).unwrap();
 
     let trigger = Scroll::builder("Advanced Rust patterns")
         .tags(["programming"].as_ref())
@@ -0,0 +1,157 @@
+use scroll_core::archive::archive_memory::InMemoryArchive;
+use scroll_core::invocation::named_construct::NamedConstruct;
+use scroll_core::invocation::types::{Invocation, InvocationResult};
+use scroll_core::scroll::Scroll;
+use scroll_core::validator::contradiction::{
+    ConstructJudge, Contradiction, ContradictionJudge, ContradictionKind, Verdict,
+};
+use scroll_core::validator::rules::Location;
+use scroll_core::validator::{ContradictionChecker, RuleEngine, Severity, ValidatorSpec};
+use scroll_core::ScrollStatus;
+use std::fs;
+use tempfile::tempdir;
+
+fn scroll(title: &str, phrase: &str, body: &str) -> Scroll {
+    Scroll::builder(title)
+        .invocation_phrase(phrase)
+        .sigil("The Spiral Eye")
+        .body(body)
+        .build()
+}
+
+#[test]
+fn conflicting_facts_name_both_scrolls() {
+    let mut gate = scroll("Ember Gate", "Open the ember gate", "The gate stands.");
+    gate.status = ScrollStatus::Active;
+    let index = scroll(
+        "Chronicle",
+        "Recite the chronicle",
+        "The Ember Gate was sealed long ago.\n\n```yaml\n- title: Ember Gate\n  invocation: Close the ember gate\n```\n",
+    );
+    let sirion = scroll("Origins", "Remember the origin", "Sirion is the Architect.");
+    let seeker = scroll("Paths", "Walk the path", "Sirion is the Seeker of ash.");
+    let echo = scroll("Echo", "Recite the Chronicle!", "Quiet.");
+
+    let archive = InMemoryArchive::new(vec![gate.clone(), index.clone(), sirion, seeker, echo]);
+    let found = ContradictionChecker::new().check(&archive);
+    let summary: Vec<(ContradictionKind, &Location, &Location)> = found
+        .iter()
+        .map(|c| (c.kind, &c.first.location, &c.second.location))
+        .collect();
+
+    assert_eq!(found.len(), 4, "{found:#?}");
+    let access = &found[0];
+    assert_eq!(access.kind, ContradictionKind::Access);
+    assert_eq!(access.first.scroll_id, index.id);
+    assert_eq!(access.first.location, Location::BodyLine(1));
+    assert_eq!(access.second.scroll_id, gate.id);
+    assert_eq!(access.second.location, Location::Field("status".into()));
+
+    assert_eq!(summary[1].0, ContradictionKind::Mythic);
+    assert_eq!(summary[1].1, &Location::BodyLine(4));
+    assert!(found[1].message.contains("\"Close the ember gate\""));
+    assert!(found[2].message.contains("share the invocation phrase"));
+    assert!(found[3].message.contains("\"the seeker of ash\""));
+}
+
+struct Dismissive;
+
+impl ContradictionJudge for Dismissive {
+    fn judge(&self, contradiction: &Contradiction, _: &Scroll, _: &Scroll) -> Verdict {
+        if contradiction.kind == ContradictionKind::Mythic {
+            Verdict::Dismissed
+        } else {
+            Verdict::Confirmed
+        }
+    }
+}
+
+#[test]
+fn judge_dismisses_candidates() {
+    let mut gate = scroll("Ember Gate", "Open the ember gate", "The gate stands.");
+    gate.status = ScrollStatus::Active;
+    let note = scroll("Notes", "Read the notes", "The Ember Gate is sealed.");
+    let twin = scroll("Twin", "Read the notes", "Quiet.");
+
+    let checker = ContradictionChecker::new().with_judge(Dismissive);
+    let found = checker.check_scrolls(&[&gate, &note, &twin]);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].kind, ContradictionKind::Access);
+    assert_eq!(found[0].verdict, Some(Verdict::Confirmed));
+}
+
+struct Reply(&'static str);
+
+impl NamedConstruct for Reply {
+    fn name(&self) -> &str {
+        "Arbiter"
+    }
+    fn perform(&self, _: &Invocation, _: Option<Scroll>) -> Result<InvocationResult, String> {
+        Ok(InvocationResult::Success(self.0.into()))
+    }
+}
+
+#[test]
+fn construct_judge_reads_the_whole_first_word() {
+    let mut gate = scroll("Ember Gate", "Open the ember gate", "The gate stands.");
+    gate.status = ScrollStatus::Active;
+    let note = scroll("Notes", "Read the notes", "The Ember Gate is sealed.");
+    let verdict = |reply| {
+        let checker = ContradictionChecker::new().with_judge(ConstructJudge::new(Reply(reply)));
+        let found = checker.check_scrolls(&[&gate, &note]);
+        found.first().and_then(|c| c.verdict)
+    };
+    assert_eq!(verdict("Confirm, they clash."), Some(Verdict::Confirmed));
+    // Dismissed candidates are dropped.
+    assert_eq!(verdict("No."), None);
+    assert_eq!(verdict("not sure"), Some(Verdict::Unsure));
+    assert_eq!(verdict("nonetheless dismiss"), Some(Verdict::Unsure));
+}
+
+#[test]
+fn validate_directory_reports_related_locations() {
+    let dir = tempdir().unwrap();
+    let header = |title: &str, status: &str| {
+        format!("---\ntitle: {title}\nscroll_type: Canon\nstatus: {status}\ninvocation_phrase: Call {title}\nsigil: Ash\nemotion_signature: calm // deep\n---\n")
+    };
+    fs::write(
+        dir.path().join("ember_gate.md"),
+        format!("{}The gate stands.\n", header("Ember Gate", "Sealed")),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("chronicle.md"),
+        format!(
+            "{}Intro.\nThe Ember Gate remains active.\n",
+            header("Chronicle", "Active")
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("forks.md"),
+        format!(
+            "{}- Chronicle and ember_gate disagree on purpose.\n",
+            header("Forks", "Active")
+        ),
+    )
+    .unwrap();
+
+    let spec = "contradiction_check:\n  cross_ref: Forks\n";
+    let engine = RuleEngine::from_spec(&ValidatorSpec::from_yaml(spec).unwrap());
+    let report = engine.validate_directory(dir.path()).unwrap();
+
+    assert_eq!(report.findings.len(), 1, "{:#?}", report.findings);
+    let finding = &report.findings[0];
+    assert_eq!(finding.rule, "contradiction.access");
+    assert_eq!(finding.severity, Severity::Info);
+    assert_eq!(finding.span.unwrap().line, 10);
+    assert_eq!(finding.related.len(), 1);
+    assert_eq!(finding.related[0].span.unwrap().line, 4);
+
+    let text = report.render_text();
+    assert!(text.contains("chronicle.md:10:1\n"));
+    assert!(text.contains(" ... "));
+    let sarif = report.to_sarif();
+    let related = &sarif["runs"][0]["results"][0]["relatedLocations"][0];
+    assert_eq!(related["physicalLocation"]["region"]["startLine"], 4);
+}
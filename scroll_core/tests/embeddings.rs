@@ -0,0 +1,164 @@
+use scroll_core::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use scroll_core::archive::embedding::{HashingEmbedder, HttpEmbedder};
+use scroll_core::archive::error::ArchiveError;
+use scroll_core::archive::semantic_index::{cosine_similarity, Embedder};
+use scroll_core::Scroll;
+use tempfile::tempdir;
+
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn scroll(title: &str, tags: &[&str], body: &str) -> Scroll {
+    Scroll::builder(title)
+        .tags(tags)
+        .body(body)
+        .invocation_phrase("Invoke")
+        .sigil("🔮")
+        .build()
+}
+
+#[test]
+fn hashing_embedder_produces_normalized_vectors() {
+    let embedder = HashingEmbedder::new(64);
+    let vector = embedder.embed("Tides rise under the pale moon").unwrap();
+    assert_eq!(vector.len(), 64);
+    let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-5);
+    assert_eq!(
+        vector,
+        embedder.embed("Tides rise under the pale moon").unwrap()
+    );
+
+    let related = embedder.embed("The moon pulls the tides").unwrap();
+    let unrelated = embedder.embed("Forge hammers ring on iron").unwrap();
+    assert!(cosine_similarity(&vector, &related) > cosine_similarity(&vector, &unrelated));
+    assert!(embedder
+        .embed("the of and")
+        .unwrap()
+        .iter()
+        .all(|x| *x == 0.0));
+}
+
+#[test]
+fn fitted_idf_favours_rare_words() {
+    let scrolls = vec![
+        scroll("Archive One", &["lore"], "The archive keeps a lantern."),
+        scroll("Archive Two", &["lore"], "The archive keeps a compass."),
+        scroll("Archive Three", &["lore"], "The archive keeps a key."),
+    ];
+    let embedder = HashingEmbedder::default().fit(&scrolls);
+    let query = embedder.embed("archive lantern").unwrap();
+    let lantern = embedder.embed("archive lore lantern").unwrap();
+    let compass = embedder.embed("archive lore compass").unwrap();
+    assert!(cosine_similarity(&query, &lantern) > cosine_similarity(&query, &compass));
+
+    let mut archive = InMemoryArchive::new(scrolls);
+    archive.build_semantic_index(embedder).unwrap();
+    let hits = archive.query_semantic("where is the compass", 3);
+    assert_eq!(hits[0].0.title, "Archive Two");
+    assert!(hits[0].1 > hits[1].1);
+}
+
+fn elements() -> Vec<Scroll> {
+    vec![
+        scroll("Stone", &["earth"], "It waits."),
+        scroll("River", &["water"], "It flows."),
+        scroll("Ember", &["fire"], "It glows."),
+        scroll("Gale", &["air"], "It howls."),
+    ]
+}
+
+#[test]
+fn a_saved_fit_is_restored_so_an_edit_embeds_one_scroll() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("index.json");
+    let mut scrolls = elements();
+    InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, HashingEmbedder::default().fit(&scrolls))
+        .unwrap();
+
+    scrolls[0].markdown_body = "It waits beneath the moss.".into();
+    let mut archive = InMemoryArchive::new(scrolls.clone());
+    let report = archive
+        .load_semantic_index(&path, HashingEmbedder::default().fit(&scrolls))
+        .unwrap();
+    assert_eq!((report.reused, report.embedded), (3, 1));
+    assert!(report.rebuilt.is_none());
+    assert_eq!(archive.query_semantic("moss", 1)[0].0.title, "Stone");
+}
+
+#[test]
+fn refitting_on_request_or_after_drift_rebuilds_a_saved_index() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("index.json");
+    let mut scrolls = elements();
+    let fitted = HashingEmbedder::default().fit(&scrolls);
+    assert_eq!(fitted.identity(), HashingEmbedder::default().identity());
+    InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, fitted)
+        .unwrap();
+
+    let report = InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, HashingEmbedder::default().fit(&scrolls).refit())
+        .unwrap();
+    assert_eq!((report.reused, report.embedded), (0, 4));
+    assert!(report.rebuilt.is_some());
+
+    scrolls.push(scroll("Frost", &["ice"], "It bites."));
+    scrolls.push(scroll("Bloom", &["life"], "It opens."));
+    let report = InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, HashingEmbedder::default().fit(&scrolls))
+        .unwrap();
+    assert_eq!((report.reused, report.embedded), (0, 6));
+    assert!(report.rebuilt.is_some());
+}
+
+#[test]
+fn semantic_index_embeds_archive_changes_with_its_embedder() {
+    let mut archive = InMemoryArchive::new(vec![scroll("Stone", &["earth"], "It waits.")]);
+    archive
+        .build_semantic_index(HashingEmbedder::default())
+        .unwrap();
+    let ember = scroll("Ember", &["fire"], "Coals glow beneath the ash.");
+    archive.insert(ember.clone()).unwrap();
+    assert_eq!(archive.refresh_semantic_index().unwrap(), 1);
+    assert_eq!(archive.query_semantic("glowing coals", 1)[0].0.id, ember.id);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn http_embedder_calls_openai_compatible_endpoint() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/embeddings"))
+        .and(header("authorization", "Bearer test"))
+        .and(body_partial_json(
+            serde_json::json!({ "model": "tiny", "input": "moon" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "index": 0, "embedding": [0.5, -0.25, 1.0] }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/broken/embeddings"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": [] })))
+        .mount(&server)
+        .await;
+
+    let uri = server.uri();
+    tokio::task::spawn_blocking(move || {
+        let embedder = HttpEmbedder::new(&format!("{uri}/v1/"), "tiny").with_api_key("test");
+        assert_eq!(embedder.endpoint, format!("{uri}/v1/embeddings"));
+        assert_eq!(embedder.embed("moon").unwrap(), [0.5, -0.25, 1.0]);
+        assert!(matches!(
+            embedder.embed("sun"),
+            Err(ArchiveError::EmbeddingFailure(_))
+        ));
+        assert!(matches!(
+            HttpEmbedder::new(&format!("{uri}/broken"), "tiny").embed("moon"),
+            Err(ArchiveError::EmbeddingFailure(_))
+        ));
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,77 @@
+use scroll_core::access::Actor;
+use scroll_core::parse_scroll;
+use scroll_core::parser::{parse_scroll_from_file, ParseErrorKind};
+use scroll_core::scroll_writer::ScrollWriter;
+use serde::Deserialize;
+use std::fs;
+use tempfile::tempdir;
+
+const SCROLL: &str = "---\ntitle: Keeper Notes\nscroll_type: Canon\nemotion_signature: {tone: calm, emphasis: 0.2, resonance: deep}\ntags: [lore]\nscrollbook: Trigger Loom\nreviewers:\n  owner: mira\n  quorum: 3\n---\nBody\n";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Reviewers {
+    owner: String,
+    quorum: u32,
+}
+
+#[test]
+fn unknown_keys_are_kept_as_extensions() {
+    let scroll = parse_scroll(SCROLL).unwrap();
+    let meta = &scroll.yaml_metadata;
+
+    assert_eq!(meta.extensions.len(), 2);
+    assert_eq!(meta.extension_str("scrollbook"), Some("Trigger Loom"));
+    assert_eq!(
+        meta.extension_as::<Reviewers>("reviewers")
+            .unwrap()
+            .unwrap(),
+        Reviewers {
+            owner: "mira".into(),
+            quorum: 3
+        }
+    );
+    assert!(meta.extension_as::<u32>("scrollbook").unwrap().is_err());
+    assert!(meta.extension("missing").is_none());
+}
+
+#[test]
+fn extensions_survive_a_write_to_a_new_file() {
+    let dir = tempdir().unwrap();
+    let mut scroll = parse_scroll(SCROLL).unwrap();
+    scroll
+        .yaml_metadata
+        .set_extension("review_round", 2)
+        .unwrap();
+
+    let path = dir.path().join("keeper.md");
+    ScrollWriter::write_scroll_as(&scroll, &path, &Actor::new("Scribe")).unwrap();
+    let reparsed = parse_scroll_from_file(&path).unwrap();
+
+    assert_eq!(
+        reparsed.yaml_metadata.extensions,
+        scroll.yaml_metadata.extensions
+    );
+    assert_eq!(
+        reparsed
+            .yaml_metadata
+            .extension_as::<u32>("review_round")
+            .unwrap()
+            .unwrap(),
+        2
+    );
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("scrollbook: Trigger Loom\n"));
+}
+
+#[test]
+fn strict_mode_still_rejects_misspelled_fields() {
+    let input = SCROLL.replace("tags:", "tagz:");
+    let err = parse_scroll(&input).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnknownField("tagz".into()));
+
+    let input = SCROLL.replace("scrollbook:", "ids: [a, b]\ntag: lore\nscrollbook:");
+    let meta = parse_scroll(&input).unwrap().yaml_metadata;
+    assert_eq!(meta.extension_str("tag"), Some("lore"));
+    assert!(meta.extensions.contains_key("ids"));
+}
@@ -0,0 +1,106 @@
+use scroll_core::access::Actor;
+use scroll_core::archive::identity::stamp_archive_ids;
+use scroll_core::parse_scroll;
+use scroll_core::parser::{
+    parse_scroll_from_file, parse_scroll_with_mode, FrontMatterFormat, ParseErrorKind, ParseMode,
+};
+use scroll_core::scroll_writer::ScrollWriter;
+use std::fs;
+use tempfile::tempdir;
+
+const TOML_SCROLL: &str = "+++\ntitle = \"Tidal Codex\"\nscroll_type = \"Canon\"\ntags = [\"sea\"]\n\n[emotion_signature]\ntone = \"calm\"\nemphasis = 0.5\nresonance = \"deep\"\n+++\nIntro\n\n---\n\nAfter the rule.\n";
+
+const JSON_SCROLL: &str = ";;;\n{\n  \"title\": \"Glass Index\",\n  \"scroll_type\": \"Protocol\",\n  \"tags\": [\"glass\"],\n  \"emotion_signature\": {\"tone\": \"clear\", \"emphasis\": 0.3, \"resonance\": \"bright\"}\n}\n;;;\nBody\n";
+
+const JSON_OBJECT_SCROLL: &str = "{\n  \"title\": \"Bare Object\",\n  \"scroll_type\": \"Echo\",\n  \"tags\": [],\n  \"emotion_signature\": {\"tone\": \"soft\", \"emphasis\": 0.1, \"resonance\": \"low\"}\n}\nBody with {braces}\n";
+
+#[test]
+fn detects_format_from_opening_delimiter() {
+    let toml = parse_scroll(TOML_SCROLL).unwrap();
+    assert_eq!(toml.origin.format, FrontMatterFormat::Toml);
+    assert_eq!(toml.title, "Tidal Codex");
+    assert_eq!(toml.markdown_body, "Intro\n\n---\n\nAfter the rule.\n");
+
+    let json = parse_scroll(JSON_SCROLL).unwrap();
+    assert_eq!(json.origin.format, FrontMatterFormat::Json);
+    assert_eq!(json.emotion_signature.tone, "clear");
+
+    let object = parse_scroll(JSON_OBJECT_SCROLL).unwrap();
+    assert_eq!(object.origin.format, FrontMatterFormat::JsonObject);
+    assert_eq!(object.markdown_body, "Body with {braces}\n");
+}
+
+#[test]
+fn delimiters_only_count_at_line_starts() {
+    let input = "# generated\n---\ntitle: Rules --- and dashes\nscroll_type: Canon\nemotion_signature: {tone: calm, emphasis: 0.1, resonance: low}\ntags: []\n---\nOne\n\n---\n\nTwo\n";
+    let scroll = parse_scroll(input).unwrap();
+    assert_eq!(scroll.title, "Rules --- and dashes");
+    assert_eq!(scroll.markdown_body, "One\n\n---\n\nTwo\n");
+}
+
+#[test]
+fn a_leading_heading_means_there_is_no_front_matter() {
+    let input = "# Title\n\n---\nAn intro between rules.\n---\nBody\n";
+    let err = parse_scroll_with_mode(input, ParseMode::Lenient).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MissingDelimiter);
+    assert_eq!(err.span.unwrap().line, 1);
+}
+
+#[test]
+fn toml_errors_are_located() {
+    let input = "+++\ntitle = \"Broken\"\nscroll_type = Canon\n+++\nBody\n";
+    let err = parse_scroll_with_mode(input, ParseMode::Lenient).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ParseErrorKind::Format {
+            format: FrontMatterFormat::Toml,
+            ..
+        }
+    ));
+    assert_eq!(err.span.unwrap().line, 3);
+}
+
+#[test]
+fn writer_keeps_the_original_format() {
+    let dir = tempdir().unwrap();
+    for (name, source, fence) in [
+        ("tidal.md", TOML_SCROLL, "+++\n"),
+        ("glass.md", JSON_SCROLL, ";;;\n{"),
+        ("bare.md", JSON_OBJECT_SCROLL, "{"),
+    ] {
+        let path = dir.path().join(name);
+        fs::write(&path, source).unwrap();
+        let scroll = parse_scroll_from_file(&path).unwrap();
+
+        let copy = dir.path().join(format!("copy_{name}"));
+        ScrollWriter::write_scroll_as(&scroll, &copy, &Actor::new("Scribe")).unwrap();
+        let written = fs::read_to_string(&copy).unwrap();
+        assert!(written.starts_with(fence), "{name}: {written}");
+
+        // The writer always records `last_modified`, which the source omitted.
+        let mut reparsed = parse_scroll_from_file(&copy).unwrap();
+        reparsed.origin.source_path = scroll.origin.source_path.clone();
+        reparsed.yaml_metadata.last_modified = None;
+        assert_eq!(reparsed, scroll, "{name}");
+    }
+}
+
+#[test]
+fn ids_are_stamped_in_each_format() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("tidal.md"), TOML_SCROLL).unwrap();
+    fs::write(dir.path().join("glass.md"), JSON_SCROLL).unwrap();
+    fs::write(dir.path().join("bare.md"), JSON_OBJECT_SCROLL).unwrap();
+
+    let report = stamp_archive_ids(dir.path(), true).unwrap();
+    assert_eq!(report.stamped.len(), 3);
+    assert!(report.failed.is_empty());
+
+    for (path, id) in report.stamped {
+        assert_eq!(parse_scroll_from_file(&path).unwrap().id, id);
+    }
+    assert!(stamp_archive_ids(dir.path(), true)
+        .unwrap()
+        .stamped
+        .is_empty());
+}
@@ -0,0 +1,122 @@
+use scroll_core::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use scroll_core::archive::full_text::{FullTextIndex, TextHit, TextIndexOptions};
+use scroll_core::archive::semantic_index::TokenEmbedder;
+use scroll_core::core::context_frame_engine::{ContextFrameEngine, ContextMode};
+use scroll_core::Scroll;
+
+fn scroll(title: &str, body: &str) -> Scroll {
+    Scroll::builder(title)
+        .tags(["lore"].as_ref())
+        .body(body)
+        .invocation_phrase("Invoke")
+        .sigil("🔮")
+        .build()
+}
+
+/// A long scrollbook with `line` buried after two hundred lines of filler.
+fn scrollbook(title: &str, line: &str) -> Scroll {
+    let mut body = "The archivists copied ledgers by candlelight.\n".repeat(200);
+    body.push_str(line);
+    body.push('\n');
+    body.push_str(&"Wind moved across the empty hall.\n".repeat(50));
+    scroll(title, &body)
+}
+
+fn titles(hits: &[(Scroll, TextHit)]) -> Vec<&str> {
+    hits.iter().map(|(s, _)| s.title.as_str()).collect()
+}
+
+#[test]
+fn finds_phrases_deep_inside_long_bodies_with_snippets() {
+    let book = scrollbook("Chronicle", "At last the Silver Gate opened onto the sea.");
+    let scattered = scroll("Smithy", "Silver was poured. Later a gate was hung.");
+    let mut archive = InMemoryArchive::new(vec![book.clone(), scattered]);
+    archive.build_semantic_index(TokenEmbedder).unwrap();
+    archive.build_text_index(TextIndexOptions::default());
+
+    // The semantic index only sees the first lines of the book.
+    let semantic = archive.query_semantic("silver gate", 2);
+    assert!(semantic
+        .iter()
+        .all(|(s, score)| s.id != book.id || *score == 0.0));
+
+    let hits = archive.query_text("\"silver gate\"", 5);
+    assert_eq!(titles(&hits), ["Chronicle"]);
+    let snippet = &hits[0].1.snippet;
+    assert!(
+        snippet.contains("the **Silver** **Gate** opened"),
+        "{snippet}"
+    );
+    assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+
+    let hits = archive.query_text("silver gate", 5);
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].0.title, "Smithy");
+    assert!(hits[0].1.score > hits[1].1.score);
+}
+
+#[test]
+fn stemming_stopwords_and_prefixes_are_options() {
+    let river = scroll("River", "The river flows past the sleeping dragons.");
+    let scrolls = vec![river.clone(), scroll("Stone", "A stone waits.")];
+
+    let stemmed = FullTextIndex::build(&scrolls, TextIndexOptions::default());
+    assert_eq!(stemmed.search("rivers flowing", 5)[0].id, river.id);
+    assert!(stemmed.search("the", 5).is_empty());
+    let hit = &stemmed.search("drag*", 5)[0];
+    assert_eq!(hit.id, river.id);
+    assert!(hit.snippet.contains("**dragons**"));
+    assert_eq!(stemmed.search("\"river flows past\"", 5).len(), 1);
+
+    let exact = FullTextIndex::build(
+        &scrolls,
+        TextIndexOptions::default().stemming(false).stopwords(false),
+    );
+    assert!(exact.search("rivers flowing", 5).is_empty());
+    assert_eq!(exact.search("the", 5).len(), 1);
+    assert!(exact.search("\"river past\"", 5).is_empty());
+}
+
+#[test]
+fn text_index_follows_archive_changes() {
+    let mut archive = InMemoryArchive::new(vec![scroll("Stone", "A stone waits.")]);
+    archive.build_text_index(TextIndexOptions::default());
+    let ember = scroll("Ember", "Coals glow beneath the ash.");
+    archive.insert(ember.clone()).unwrap();
+    assert_eq!(titles(&archive.query_text("coals", 5)), ["Ember"]);
+
+    let mut cooled = ember.clone();
+    cooled.markdown_body = "Only cinders remain.".into();
+    archive.update(cooled).unwrap();
+    assert!(archive.query_text("coals", 5).is_empty());
+    assert_eq!(titles(&archive.query_text("cinder*", 5)), ["Ember"]);
+
+    archive.remove(ember.id).unwrap();
+    assert!(archive.query_text("cinders", 5).is_empty());
+    assert_eq!(archive.text_index_len(), 1);
+}
+
+#[test]
+fn context_engine_falls_back_to_full_text_when_semantic_recall_is_weak() {
+    let book = scrollbook(
+        "Harbor Records",
+        "Keepers lit the obsidian lighthouse each winter.",
+    );
+    let mut archive = InMemoryArchive::new(vec![book, scroll("Stone", "A stone waits.")]);
+    archive.build_semantic_index(TokenEmbedder).unwrap();
+    let trigger = Scroll::builder("Obsidian Lighthouse")
+        .tags(["coast"].as_ref())
+        .body("Who keeps the light?")
+        .invocation_phrase("Invoke")
+        .sigil("🔮")
+        .build();
+
+    let engine = ContextFrameEngine::new(&archive, ContextMode::Echo);
+    let ctx = engine.build_context(&trigger);
+    assert!(!ctx.scrolls.iter().any(|s| s.title == "Harbor Records"));
+
+    archive.build_text_index(TextIndexOptions::default());
+    let engine = ContextFrameEngine::new(&archive, ContextMode::Echo);
+    let ctx = engine.build_context(&trigger);
+    assert_eq!(ctx.scrolls[1].title, "Harbor Records");
+}
@@ -0,0 +1,272 @@
+mod common;
+
+use scroll_core::access::Actor;
+use scroll_core::archive::archive_loader::LoadOptions;
+use scroll_core::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use scroll_core::archive::error::ArchiveError;
+use scroll_core::archive::hybrid_archive::{DirectoryStore, HybridCacheArchive};
+use scroll_core::archive::semantic_index::TokenEmbedder;
+use scroll_core::archive::sqlite_archive::SqliteStore;
+use scroll_core::archive::watcher::ArchiveChange;
+use scroll_core::scroll_writer::{ScrollPatch, ScrollWriter};
+use scroll_core::{EmotionSignature, Scroll, ScrollType};
+use std::fs;
+use tempfile::tempdir;
+
+use common::write_scroll;
+
+fn draft(title: &str, tag: &str) -> Scroll {
+    let mut scroll = ScrollWriter::generate_draft(
+        title.into(),
+        ScrollType::Canon,
+        EmotionSignature::curious(),
+        vec![tag.into()],
+    );
+    scroll.markdown_body = "It glows.\n".into();
+    scroll
+}
+
+#[test]
+fn in_memory_mutations_return_changes_and_refresh_the_index() {
+    let ember = draft("Ember", "fire");
+    let mut archive = InMemoryArchive::new(vec![draft("Stone", "earth")]);
+    assert_eq!(archive.insert(ember.clone()).unwrap().kind(), "created");
+    assert!(matches!(
+        archive.insert(ember.clone()),
+        Err(ArchiveError::DuplicateScroll(id)) if id == ember.id
+    ));
+    archive.build_semantic_index(TokenEmbedder).unwrap();
+
+    let mut edited = ember.clone();
+    edited.title = "Ember Tide".into();
+    assert_eq!(archive.upsert(edited).unwrap().kind(), "modified");
+    assert_eq!(archive.semantic_index_len(), 1);
+    assert_eq!(archive.refresh_semantic_index().unwrap(), 1);
+    assert_eq!(
+        archive.query_semantic("ember tide", 1)[0].0.title,
+        "Ember Tide"
+    );
+
+    assert!(matches!(
+        archive.remove(ember.id).unwrap(),
+        ArchiveChange::Removed { id, .. } if id == ember.id
+    ));
+    assert!(matches!(
+        archive.remove(ember.id),
+        Err(ArchiveError::ScrollNotFound(_))
+    ));
+    assert_eq!(archive.count(), 1);
+}
+
+#[test]
+fn hybrid_archive_caches_hot_scrolls_and_pages_cold_ones_from_disk() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_scroll(
+        &root.join("river.md"),
+        "River",
+        "tags: [water]\n",
+        "It flows.\n",
+    );
+    write_scroll(
+        &root.join("stone.md"),
+        "Stone",
+        "tags: [earth]\n",
+        "It waits.\n",
+    );
+    write_scroll(
+        &root.join("ash.md"),
+        "Ash",
+        "tags: [fire]\n",
+        "It settles.\n",
+    );
+
+    let store = DirectoryStore::open(root, LoadOptions::default()).unwrap();
+    let mut archive = HybridCacheArchive::new(store, 1).unwrap();
+    assert_eq!((archive.count(), archive.cache().count()), (3, 0));
+
+    let river = archive.get_scrolls_by_tag("water")[0].id;
+    let stone = archive.get_scrolls_by_tag("earth")[0].id;
+    for _ in 0..3 {
+        archive.get_scroll_by_id(river).unwrap();
+    }
+    assert_eq!(archive.settle(), 2);
+    assert!(archive.is_hot(river));
+    assert!(!archive.is_hot(stone));
+    assert_eq!(archive.fetch(stone).unwrap().title, "Stone");
+    assert!(archive.is_hot(river));
+    assert_eq!(archive.cache().count(), 1);
+
+    let ember = draft("Ember", "fire");
+    assert_eq!(archive.insert(ember.clone()).unwrap().kind(), "created");
+    assert!(root.join("ember.md").exists());
+    assert_eq!(archive.get_scrolls_by_tag("fire").len(), 2);
+
+    let change = archive.remove(stone).unwrap();
+    assert!(matches!(change, ArchiveChange::Removed { path, .. } if path.ends_with("stone.md")));
+    assert!(!root.join("stone.md").exists());
+
+    let patch = ScrollPatch {
+        title: Some("Flooded River".into()),
+        ..Default::default()
+    };
+    let change = ScrollWriter::update_in_archive(
+        river,
+        patch,
+        &root.join("river.md"),
+        &Actor::new("Kael"),
+        &mut archive,
+    )
+    .unwrap();
+    assert_eq!(change.kind(), "modified");
+    assert_eq!(
+        archive.get_scroll_by_id(river).unwrap().title,
+        "Flooded River"
+    );
+
+    let reopened = HybridCacheArchive::new(
+        DirectoryStore::open(root, LoadOptions::default()).unwrap(),
+        1,
+    )
+    .unwrap();
+    let mut titles: Vec<String> = reopened
+        .get_all_scrolls()
+        .iter()
+        .map(|s| s.title.clone())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["Ash", "Ember", "Flooded River"]);
+    assert_eq!(reopened.get_scroll_by_id(ember.id).unwrap().id, ember.id);
+}
+
+#[test]
+fn hybrid_archive_pages_from_sqlite() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("archive.db");
+    let db = db.to_str().unwrap();
+    let mut archive = HybridCacheArchive::new(SqliteStore::open(db).unwrap(), 4).unwrap();
+    assert_eq!(archive.count(), 0);
+
+    let ember = draft("Ember", "fire");
+    let path = dir.path().join("ember.md");
+    let change =
+        ScrollWriter::write_to_archive(&ember, &path, &Actor::new("Kael"), &mut archive).unwrap();
+    assert_eq!(change.kind(), "created");
+    assert!(path.exists());
+    drop(archive);
+
+    let mut archive = HybridCacheArchive::new(SqliteStore::open(db).unwrap(), 4).unwrap();
+    assert_eq!(archive.cache().count(), 0);
+    let stored = archive.get_scrolls_by_tag("fire")[0].clone();
+    assert_eq!(stored.id, ember.id);
+    assert_eq!(stored.origin.source_path.as_deref(), Some(path.as_path()));
+    archive.build_semantic_index(TokenEmbedder).unwrap();
+    assert_eq!(archive.query_semantic("ember", 1)[0].0.id, ember.id);
+    assert_eq!(archive.settle(), 1);
+    assert!(archive.is_hot(ember.id));
+}
+
+#[test]
+fn directory_store_never_overwrites_another_scrolls_file() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_scroll(
+        &root.join("ember.md"),
+        "Old Ember",
+        "tags: [fire]\n",
+        "It smoulders.\n",
+    );
+
+    let store = DirectoryStore::open(root, LoadOptions::default()).unwrap();
+    let mut archive = HybridCacheArchive::new(store, 4).unwrap();
+    archive.insert(draft("Ember", "fire")).unwrap();
+    archive.insert(draft("Ember", "fire")).unwrap();
+
+    assert!(fs::read_to_string(root.join("ember.md"))
+        .unwrap()
+        .contains("Old Ember"));
+    assert!(root.join("ember-2.md").exists());
+    assert!(root.join("ember-3.md").exists());
+    let reopened = DirectoryStore::open(root, LoadOptions::default()).unwrap();
+    assert_eq!(HybridCacheArchive::new(reopened, 1).unwrap().count(), 3);
+}
+
+#[test]
+fn shared_reads_only_hold_cold_scrolls_until_settle() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_scroll(
+        &root.join("river.md"),
+        "River",
+        "tags: [water]\n",
+        "It flows to the sea.\n",
+    );
+    write_scroll(
+        &root.join("stone.md"),
+        "Stone",
+        "tags: [earth]\n",
+        "It waits.\n",
+    );
+    write_scroll(
+        &root.join("ash.md"),
+        "Ash",
+        "tags: [fire]\n",
+        "It settles.\n",
+    );
+
+    let store = DirectoryStore::open(root, LoadOptions::default()).unwrap();
+    let mut archive = HybridCacheArchive::new(store, 1).unwrap();
+    archive.build_semantic_index(TokenEmbedder).unwrap();
+    archive.build_text_index(Default::default());
+    assert_eq!(archive.paged_count(), 0);
+
+    for _ in 0..3 {
+        assert_eq!(archive.query_semantic("flows sea", 1)[0].0.title, "River");
+        assert_eq!(archive.query_text("waits", 1)[0].0.title, "Stone");
+    }
+    assert_eq!(archive.paged_count(), 0);
+
+    assert_eq!(archive.get_all_scrolls().len(), 3);
+    assert_eq!(archive.paged_count(), 3);
+    archive.settle();
+    assert_eq!(archive.paged_count(), 0);
+    assert_eq!(archive.cache().count(), 1);
+}
+
+#[test]
+fn update_in_archive_refuses_a_file_holding_another_scroll() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    write_scroll(
+        &root.join("river.md"),
+        "River",
+        "tags: [water]\n",
+        "It flows.\n",
+    );
+    write_scroll(
+        &root.join("stone.md"),
+        "Stone",
+        "tags: [earth]\n",
+        "It waits.\n",
+    );
+    let before = fs::read_to_string(root.join("river.md")).unwrap();
+
+    let store = DirectoryStore::open(root, LoadOptions::default()).unwrap();
+    let mut archive = HybridCacheArchive::new(store, 2).unwrap();
+    let stone = archive.get_scrolls_by_tag("earth")[0].id;
+    let patch = ScrollPatch {
+        title: Some("Flooded Stone".into()),
+        ..Default::default()
+    };
+    let err = ScrollWriter::update_in_archive(
+        stone,
+        patch,
+        &root.join("river.md"),
+        &Actor::new("Kael"),
+        &mut archive,
+    )
+    .unwrap_err();
+    assert!(err.contains("holds scroll"), "{err}");
+    assert_eq!(fs::read_to_string(root.join("river.md")).unwrap(), before);
+    assert_eq!(archive.get_scroll_by_id(stone).unwrap().title, "Stone");
+}
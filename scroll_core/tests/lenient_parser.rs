@@ -0,0 +1,75 @@
+use scroll_core::archive::archive_loader::{load_scrolls_from_directory, resolve_linked_scrolls};
+use scroll_core::parser::{parse_scroll_with_mode, ParseMode};
+use scroll_core::{parse_scroll, EmotionSignature, ScrollStatus, ScrollType};
+use std::fs;
+use tempfile::tempdir;
+
+const LOOM: &str = r#"
+---
+title: "Scrollbook: Trigger Loom"
+scroll_type: "system"
+status: "mythic_validated"
+sigil: "🪡"
+invocation_phrase: "Weave the threads. Let the prophecy unfurl."
+archetype: "Fate-Binder"
+emotion_signature: "Tension // Alignment"
+linked_scrolls:
+  - Scrollbook_Validator_Specs.md
+---
+
+# 🪡 Scrollbook: Trigger Loom
+"#;
+
+#[test]
+fn lenient_mode_maps_archive_headers() {
+    let scroll = parse_scroll_with_mode(LOOM, ParseMode::Lenient).unwrap();
+    assert_eq!(scroll.scroll_type, ScrollType::System);
+    assert_eq!(scroll.status, ScrollStatus::MythicValidated);
+    assert_eq!(scroll.sigil, "🪡");
+    assert_eq!(
+        scroll.invocation_phrase,
+        "Weave the threads. Let the prophecy unfurl."
+    );
+    assert_eq!(scroll.archetype.as_deref(), Some("Fate-Binder"));
+    assert_eq!(scroll.emotion_signature.tone, "Tension");
+    assert_eq!(scroll.emotion_signature.resonance, "Alignment");
+    assert_eq!(
+        scroll.yaml_metadata.linked_scrolls,
+        vec!["Scrollbook_Validator_Specs.md".to_string()]
+    );
+
+    assert!(parse_scroll(LOOM).is_err());
+}
+
+#[test]
+fn emotion_shorthand_round_trips_display() {
+    let sig: EmotionSignature = "calm // deep (0.20)".parse().unwrap();
+    assert_eq!(sig.tone, "calm");
+    assert_eq!(sig.resonance, "deep");
+    assert_eq!(sig.intensity, Some(0.2));
+    assert_eq!(sig.to_string(), "calm // deep (0.20)");
+}
+
+#[test]
+fn loader_resolves_linked_scroll_filenames() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("Scrollbook_Trigger_Loom.md"), LOOM).unwrap();
+    fs::write(
+        dir.path().join("Scrollbook_Validator_Specs.md"),
+        "---\ntitle: Validator Specs\nscroll_type: protocol\n---\nBody\n",
+    )
+    .unwrap();
+
+    let scrolls = load_scrolls_from_directory(dir.path()).unwrap();
+    let loom = scrolls.iter().find(|s| s.sigil == "🪡").unwrap();
+    let specs = scrolls
+        .iter()
+        .find(|s| s.title == "Validator Specs")
+        .unwrap();
+    assert!(loom.is_linked_to(&specs.id));
+
+    let mut orphan = vec![parse_scroll_with_mode(LOOM, ParseMode::Lenient).unwrap()];
+    let unresolved = resolve_linked_scrolls(&mut orphan);
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].reference, "Scrollbook_Validator_Specs.md");
+}
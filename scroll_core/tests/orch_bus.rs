@@ -102,6 +102,8 @@ fn test_whispering_bus() {
             archetype: None,
             quorum_required: false,
             file_path: None,
+            linked_scrolls: vec![],
+            extensions: Default::default(),
         },
         tags: vec![],
         archetype: None,
@@ -114,6 +116,8 @@ fn test_whispering_bus() {
             created: chrono::Utc::now(),
             last_modified: chrono::Utc::now(),
             authored_by: None,
+            source_path: None,
+            format: Default::default(),
         },
         emotion_signature: scroll_core::EmotionSignature::default(),
         linked_scrolls: vec![],
@@ -0,0 +1,63 @@
+use scroll_core::parse_scroll;
+use scroll_core::parser::{
+    parse_scroll_from_file, parse_scroll_with_mode, ParseErrorKind, ParseMode, Span,
+};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn missing_delimiters_point_at_the_opening_fence() {
+    let err = parse_scroll("\n---\ntitle: Broken\n").unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::MissingDelimiter);
+    let span = err.span.unwrap();
+    assert_eq!((span.line, span.column), (2, 1));
+}
+
+#[test]
+fn yaml_type_errors_are_located_in_the_original_file() {
+    let input = "\n\n---\ntitle: Typed\nscroll_type: Canon\nemotion_signature:\n  tone: calm\n  emphasis: loud\n  resonance: deep\ntags: []\n---\nBody\n";
+    let err = parse_scroll(input).unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Yaml(_)));
+    let span = err.span.unwrap();
+    assert_eq!((span.line, span.column), (8, 13));
+    assert_eq!(err.snippet.as_deref(), Some("  emphasis: loud"));
+}
+
+#[test]
+fn spans_inside_multibyte_characters_do_not_panic() {
+    let source = "title: Ʃigil\n";
+    let span = Span::from_offset(source, 8, 1);
+    assert_eq!((span.line, span.column), (1, 8));
+}
+
+#[test]
+fn unknown_fields_are_rejected_in_strict_mode() {
+    let input = "---\ntitle: Typo\nscroll_typ: Canon\n---\nBody\n";
+    let err = parse_scroll(input).unwrap_err();
+    assert_eq!(err.kind, ParseErrorKind::UnknownField("scroll_typ".into()));
+    let span = err.span.unwrap();
+    assert_eq!((span.line, span.column, span.len), (3, 1, 10));
+}
+
+#[test]
+fn lenient_errors_resolve_to_the_offending_key() {
+    let input = "---\ntitle: Loose\nscroll_type: saga\n---\nBody\n";
+    let err = parse_scroll_with_mode(input, ParseMode::Lenient).unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Validation(_)));
+    assert_eq!(err.span.unwrap().line, 3);
+}
+
+#[test]
+fn rendered_diagnostic_shows_path_and_snippet() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("broken.md");
+    fs::write(&path, "---\ntitle: Myth\nscroll_type: Myth\nemotion_signature:\n  tone: calm\n  emphasis: 0.5\n  resonance: deep\ntags: [\n---\nBody\n").unwrap();
+
+    let err = parse_scroll_from_file(&path).unwrap_err();
+    assert_eq!(err.path.as_deref(), Some(path.as_path()));
+
+    let rendered = err.render();
+    assert!(rendered.starts_with("error: invalid YAML"));
+    assert!(rendered.contains(&format!("--> {}:", path.display())));
+    assert!(rendered.contains("| ^"));
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use scroll_core::access::Actor;
+use scroll_core::invocation::named_construct::NamedConstruct;
+use scroll_core::invocation::types::{Invocation, InvocationResult};
+use scroll_core::parser::{parse_scroll_from_file_with_mode, ParseMode};
+use scroll_core::quorum::{ProposalStatus, QuorumBoard, QuorumConfig, Submission};
+use scroll_core::scroll_writer::{ScrollPatch, ScrollWriter};
+use scroll_core::Scroll;
+use std::path::Path;
+use tempfile::tempdir;
+use uuid::Uuid;
+
+use common::write_scroll;
+
+fn write(path: &Path, quorum: bool, status: &str) {
+    let header = format!("status: {status}\nquorum_required: {quorum}\n");
+    write_scroll(path, "Vault Door", &header, "The door holds.\n");
+}
+
+fn retitle(title: &str) -> ScrollPatch {
+    ScrollPatch {
+        title: Some(title.into()),
+        ..ScrollPatch::default()
+    }
+}
+
+fn title(path: &Path) -> String {
+    parse_scroll_from_file_with_mode(path, ParseMode::Lenient)
+        .unwrap()
+        .title
+}
+
+#[test]
+fn quorum_applies_approved_changes_to_sealed_scrolls() {
+    let dir = tempdir().unwrap();
+    let (open, guarded) = (dir.path().join("open.md"), dir.path().join("guarded.md"));
+    write(&open, false, "Active");
+    write(&guarded, true, "Sealed");
+    let proposer = Actor::new("Kael");
+    let mut board = QuorumBoard::default();
+
+    assert_eq!(
+        board.submit_update(&open, retitle("Open Door"), &proposer),
+        Ok(Submission::Applied)
+    );
+    assert_eq!(title(&open), "Open Door");
+
+    let Submission::Pending(id) = board
+        .submit_update(&guarded, retitle("Warded Door"), &proposer)
+        .unwrap()
+    else {
+        panic!("quorum scroll was written directly");
+    };
+    assert_eq!(title(&guarded), "Vault Door");
+    let diff = &board.get(id).unwrap().diff;
+    assert!(diff.contains("-title: Vault Door\n"), "{diff}");
+    assert!(diff.contains("+title: Warded Door\n"), "{diff}");
+    assert!(diff.contains(" The door holds.\n"), "{diff}");
+
+    assert!(board.vote(id, &proposer, true, None).is_err());
+    let (mira, oren) = (Actor::new("Mira"), Actor::new("Oren"));
+    assert_eq!(
+        board.vote(id, &mira, true, None),
+        Ok(ProposalStatus::Pending)
+    );
+    assert!(board.vote(id, &Actor::new("mira"), true, None).is_err());
+    assert_eq!(
+        board.vote(id, &oren, true, Some("aligned".into())),
+        Ok(ProposalStatus::Applied)
+    );
+
+    assert_eq!(title(&guarded), "Warded Door");
+    assert!(board.pending().is_empty());
+    let closed = &board.closed()[0];
+    assert_eq!(closed.status, ProposalStatus::Applied);
+    assert_eq!(closed.votes.len(), 2);
+    assert_eq!(closed.votes[1].comment.as_deref(), Some("aligned"));
+}
+
+struct Skeptic;
+
+impl NamedConstruct for Skeptic {
+    fn name(&self) -> &str {
+        "Skeptic"
+    }
+    fn perform(&self, _: &Invocation, _: Option<Scroll>) -> Result<InvocationResult, String> {
+        Ok(InvocationResult::Success(
+            "Reject: the door must stay.".into(),
+        ))
+    }
+}
+
+#[test]
+fn rejected_and_expired_proposals_are_archived_with_votes() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("guarded.md");
+    write(&path, true, "Active");
+    let mut board = QuorumBoard::new(QuorumConfig {
+        approvals: 2,
+        rejections: 1,
+        ttl_hours: 1,
+    });
+
+    let Ok(Submission::Pending(rejected)) =
+        board.submit_update(&path, retitle("Broken Door"), &Actor::new("Kael"))
+    else {
+        panic!("expected a proposal");
+    };
+    assert_eq!(
+        board.vote_with_construct(rejected, &Skeptic),
+        Ok(Some(ProposalStatus::Rejected))
+    );
+
+    let Ok(Submission::Pending(stale)) =
+        board.submit_update(&path, retitle("Forgotten Door"), &Actor::new("Kael"))
+    else {
+        panic!("expected a proposal");
+    };
+    board.vote(stale, &Actor::new("Mira"), true, None).unwrap();
+    assert!(board.expire(Utc::now()).is_empty());
+    assert_eq!(board.expire(Utc::now() + Duration::hours(2)), [stale]);
+    assert_eq!(title(&path), "Vault Door");
+
+    let saved = dir.path().join("quorum.json");
+    board.save(&saved).unwrap();
+    let board = QuorumBoard::load(&saved).unwrap();
+    let history: Vec<(ProposalStatus, &str, bool)> = board
+        .closed()
+        .iter()
+        .flat_map(|p| {
+            p.votes
+                .iter()
+                .map(move |v| (p.status, v.voter.name.as_str(), v.approve))
+        })
+        .collect();
+    assert_eq!(
+        history,
+        [
+            (ProposalStatus::Rejected, "Skeptic", false),
+            (ProposalStatus::Expired, "Mira", true),
+        ]
+    );
+}
+
+#[test]
+fn quorum_scrolls_only_change_through_current_proposals() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("guarded.md");
+    write(&path, true, "Active");
+    let kael = Actor::new("Kael");
+    let err =
+        ScrollWriter::update_scroll(Uuid::nil(), retitle("Forced Door"), &path, &kael).unwrap_err();
+    assert!(err.contains("requires quorum approval"), "{err}");
+    assert_eq!(title(&path), "Vault Door");
+
+    let mut board = QuorumBoard::default();
+    let Ok(Submission::Pending(first)) = board.submit_update(&path, retitle("First Door"), &kael)
+    else {
+        panic!("expected a proposal");
+    };
+    let Ok(Submission::Pending(second)) = board.submit_update(&path, retitle("Second Door"), &kael)
+    else {
+        panic!("expected a proposal");
+    };
+    let (mira, oren) = (Actor::new("Mira"), Actor::new("Oren"));
+    board.vote(second, &mira, true, None).unwrap();
+    board.vote(first, &mira, true, None).unwrap();
+    assert_eq!(
+        board.vote(first, &oren, true, None),
+        Ok(ProposalStatus::Applied)
+    );
+    assert_eq!(title(&path), "First Door");
+
+    assert_eq!(
+        board.vote(second, &oren, true, None),
+        Ok(ProposalStatus::Stale)
+    );
+    assert_eq!(title(&path), "First Door");
+    let stale = board.get(second).unwrap();
+    assert_eq!(
+        (stale.status, stale.votes.len()),
+        (ProposalStatus::Stale, 1)
+    );
+}
+
+struct Echo(&'static str);
+
+impl NamedConstruct for Echo {
+    fn name(&self) -> &str {
+        self.0
+    }
+    fn perform(&self, _: &Invocation, _: Option<Scroll>) -> Result<InvocationResult, String> {
+        Ok(InvocationResult::Success(self.0.into()))
+    }
+}
+
+#[test]
+fn construct_votes_read_the_whole_first_word() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("guarded.md");
+    write(&path, true, "Active");
+    let mut board = QuorumBoard::new(QuorumConfig {
+        approvals: 1,
+        rejections: 1,
+        ttl_hours: 1,
+    });
+    let Ok(Submission::Pending(id)) =
+        board.submit_update(&path, retitle("Open Door"), &Actor::new("Kael"))
+    else {
+        panic!("expected a proposal");
+    };
+
+    for unsure in ["not sure about this", "nonetheless approve", "Nope."] {
+        assert_eq!(board.vote_with_construct(id, &Echo(unsure)), Ok(None));
+    }
+    assert_eq!(
+        board.vote_with_construct(id, &Echo("Approve, it fits.")),
+        Ok(Some(ProposalStatus::Applied))
+    );
+}
@@ -0,0 +1,116 @@
+use assert_cmd::Command;
+use predicates::str::contains;
+use scroll_core::archive::schema_migration::migrate_archive;
+use scroll_core::parser::{ParseErrorKind, CURRENT_SCHEMA_VERSION};
+use scroll_core::{parse_scroll, ScrollType};
+use std::fs;
+use tempfile::tempdir;
+
+const LEGACY: &str = "---\n# written before schema_version existed\ntitle: Old Archive\ntype: System Design\ninvocation: Open the stacks\nemotion: calm // deep (0.30)\ntags: [library]\n---\nBody\n";
+
+#[test]
+fn legacy_headers_upgrade_in_strict_mode() {
+    let scroll = parse_scroll(LEGACY).unwrap();
+    assert_eq!(scroll.scroll_type, ScrollType::System);
+    assert_eq!(scroll.invocation_phrase, "Open the stacks");
+    assert_eq!(scroll.emotion_signature.tone, "calm");
+    assert_eq!(scroll.emotion_signature.resonance, "deep");
+    assert!(scroll.yaml_metadata.extensions.is_empty());
+}
+
+#[test]
+fn newer_schema_versions_are_rejected() {
+    let input = LEGACY.replace(
+        "title:",
+        &format!("schema_version: {}\ntitle:", CURRENT_SCHEMA_VERSION + 1),
+    );
+    let err = parse_scroll(&input).unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Validation(ref m) if m.contains("newer")));
+    assert_eq!(err.span.unwrap().line, 3);
+}
+
+#[test]
+fn migrate_archive_rewrites_outdated_files_once() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("legacy.md"), LEGACY).unwrap();
+    let current = format!(
+        "---\nschema_version: {CURRENT_SCHEMA_VERSION}\ntitle: New\nscroll_type: Canon\nemotion_signature: {{tone: calm, emphasis: 0.1, resonance: low}}\ntags: [x]\n---\nBody\n"
+    );
+    fs::write(dir.path().join("current.md"), &current).unwrap();
+
+    let dry_run = migrate_archive(dir.path(), false).unwrap();
+    assert_eq!(dry_run.upgraded.len(), 1);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("legacy.md")).unwrap(),
+        LEGACY
+    );
+
+    let report = migrate_archive(dir.path(), true).unwrap();
+    assert_eq!(report.upgraded, vec![(dir.path().join("legacy.md"), 0)]);
+    assert_eq!(report.current, vec![dir.path().join("current.md")]);
+
+    let upgraded = fs::read_to_string(dir.path().join("legacy.md")).unwrap();
+    assert!(upgraded.contains("# written before schema_version existed\n"));
+    assert!(upgraded.contains("scroll_type: System\n"));
+    assert!(!upgraded.contains("\ntype:"));
+    assert!(upgraded.ends_with("---\nBody\n"));
+    assert_eq!(parse_scroll(&upgraded).unwrap().title, "Old Archive");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("current.md")).unwrap(),
+        current
+    );
+
+    let again = migrate_archive(dir.path(), true).unwrap();
+    assert!(again.upgraded.is_empty());
+}
+
+#[test]
+fn migrate_command_reports_changed_files() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("legacy.md"), LEGACY).unwrap();
+    let archive = dir.path().to_str().unwrap();
+
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["migrate", "--check", "--archive", archive])
+        .assert()
+        .failure()
+        .stdout(contains("outdated:"));
+
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["migrate", "--archive", archive])
+        .assert()
+        .success()
+        .stdout(contains("legacy.md from schema 0"));
+
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["migrate", "--check", "--archive", archive])
+        .assert()
+        .success();
+}
+
+#[test]
+fn every_scanned_file_is_upgraded_current_or_failed() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("empty.md"), "---\n---\nThe Empty Scroll\n").unwrap();
+    let newer = LEGACY.replace("title:", "schema_version: 99\ntitle:");
+    fs::write(dir.path().join("newer.md"), &newer).unwrap();
+
+    let report = migrate_archive(dir.path(), true).unwrap();
+    assert_eq!(report.upgraded, vec![(dir.path().join("empty.md"), 0)]);
+    assert!(report.current.is_empty());
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(
+        report.failed[0].path.as_deref(),
+        Some(dir.path().join("newer.md").as_path())
+    );
+    assert!(fs::read_to_string(dir.path().join("empty.md"))
+        .unwrap()
+        .contains(&format!("schema_version: {CURRENT_SCHEMA_VERSION}")));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("newer.md")).unwrap(),
+        newer
+    );
+}
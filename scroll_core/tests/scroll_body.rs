@@ -0,0 +1,68 @@
+use scroll_core::parser::body::Block;
+use scroll_core::parser::ScrollBody;
+use scroll_core::Scroll;
+use serde_yaml::Value;
+use std::fs;
+
+const BODY: &str = "Intro line\n\n# Rites\n\n> First voice\n> second voice\n\n## Setup\n\n```yaml\nsteps: 2\n```\n\n```rust\nfn main() {}\n```\n\n## Setup\n\n~~~\n# not a heading\n~~~\n\n# Closing #\n\nDone.\n";
+
+#[test]
+fn headings_nest_into_sections() {
+    let body = ScrollBody::parse(BODY);
+    assert_eq!(body.preamble, vec![Block::Paragraph("Intro line".into())]);
+    assert_eq!(body.sections.len(), 2);
+
+    let rites = &body.sections[0];
+    assert_eq!(rites.heading.anchor, "rites");
+    assert_eq!(
+        rites.blocks,
+        vec![Block::Quote("First voice\nsecond voice".into())]
+    );
+    let anchors: Vec<&str> = rites
+        .subsections
+        .iter()
+        .map(|s| s.heading.anchor.as_str())
+        .collect();
+    assert_eq!(anchors, ["setup", "setup-1"]);
+    assert!(rites.markdown.starts_with("# Rites\n"));
+    assert!(rites.markdown.ends_with("~~~\n\n"));
+    assert_eq!(rites.code_blocks().len(), 3);
+
+    assert_eq!(body.section("# Closing").unwrap().heading.line, 24);
+    assert_eq!(body.section("setup-1").unwrap().heading.line, 18);
+}
+
+#[test]
+fn code_blocks_keep_language_and_content() {
+    let body = ScrollBody::parse(BODY);
+    let yaml = body.yaml_blocks();
+    assert_eq!(yaml.len(), 1);
+    assert_eq!(yaml[0].content, "steps: 2\n");
+    assert_eq!(yaml[0].line, 10);
+    assert!(yaml[0].closed);
+    let untagged = &body.code_blocks()[2];
+    assert_eq!(untagged.language, None);
+    assert_eq!(untagged.content, "# not a heading\n");
+}
+
+#[test]
+fn validator_spec_scroll_exposes_its_yaml_block() {
+    let raw = fs::read_to_string("../scrolls/Scrollbook_Validator_Specs.md").unwrap();
+    let scroll = Scroll::builder("Validator Specs").body(raw).build();
+    let body = scroll.body();
+
+    let section = body.section("# II. VALIDATION BLOCK STRUCTURE").unwrap();
+    assert_eq!(section.heading.anchor, "ii-validation-block-structure");
+    assert_eq!(section.heading.level, 2);
+    assert_eq!(body.quotes().len(), 1);
+
+    // The spec's fence is never closed, so the block runs to the end.
+    let block = section.code_blocks()[0];
+    assert!(block.is_language("yaml"));
+    assert!(!block.closed);
+    let spec: Value = serde_yaml::from_str(block.content.split("\n\n").next().unwrap()).unwrap();
+    assert_eq!(
+        spec["validator_spec"]["access_check"]["if_status"],
+        Value::from("sealed")
+    );
+}
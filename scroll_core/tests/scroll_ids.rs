@@ -0,0 +1,94 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use scroll_core::archive::archive_loader::load_scrolls_from_directory;
+use scroll_core::archive::identity::stamp_archive_ids;
+use scroll_core::parser::scroll_id_for_path;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+use uuid::Uuid;
+
+use common::write_scroll;
+
+fn write_ember(dir: &Path, name: &str, id: Option<&str>) {
+    let id_line = id.map(|id| format!("id: {id}\n")).unwrap_or_default();
+    write_scroll(&dir.join(name), "Ember", &id_line, "Body\n");
+}
+
+#[test]
+fn ids_are_stable_across_loads() {
+    let dir = tempdir().unwrap();
+    let declared = "0b6f2c51-3f0e-4a43-9d7e-2f1c8f0d6a11";
+    write_ember(dir.path(), "declared.md", Some(declared));
+    write_ember(dir.path(), "derived.md", None);
+
+    let first = load_scrolls_from_directory(dir.path()).unwrap();
+    let second = load_scrolls_from_directory(dir.path()).unwrap();
+    let ids = |scrolls: &[scroll_core::Scroll]| {
+        let mut ids: Vec<Uuid> = scrolls.iter().map(|s| s.id).collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids(&first), ids(&second));
+    assert!(ids(&first).contains(&Uuid::parse_str(declared).unwrap()));
+    assert!(ids(&first).contains(&scroll_id_for_path(Path::new("derived.md"))));
+}
+
+#[test]
+fn stamping_writes_missing_ids_once() {
+    let dir = tempdir().unwrap();
+    write_ember(dir.path(), "ember.md", None);
+
+    let report = stamp_archive_ids(dir.path(), true).unwrap();
+    let expected = scroll_id_for_path(Path::new("ember.md"));
+    assert_eq!(
+        report.stamped,
+        vec![(dir.path().join("ember.md"), expected)]
+    );
+
+    let contents = fs::read_to_string(dir.path().join("ember.md")).unwrap();
+    assert!(contents.starts_with(&format!("---\nid: {expected}\ntitle: Ember\n")));
+
+    let again = stamp_archive_ids(dir.path(), true).unwrap();
+    assert!(again.stamped.is_empty());
+    assert!(again.collisions.is_empty());
+}
+
+#[test]
+fn stamping_walks_the_archive_like_the_loader() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("lore")).unwrap();
+    write_ember(&dir.path().join("lore"), "nested.md", None);
+    write_ember(dir.path(), "notes.txt", None);
+    write_ember(dir.path(), "draft.md", None);
+    fs::write(dir.path().join(".scrollignore"), "draft.md\n").unwrap();
+
+    let report = stamp_archive_ids(dir.path(), false).unwrap();
+    let stamped: Vec<_> = report.stamped.into_iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        stamped,
+        vec![
+            dir.path().join("lore").join("nested.md"),
+            dir.path().join("notes.txt"),
+        ]
+    );
+}
+
+#[test]
+fn stamp_ids_command_reports_collisions() {
+    let dir = tempdir().unwrap();
+    let shared = "7d4f4a8e-52a1-4a5e-b5a4-0f3c9e2d1b77";
+    write_ember(dir.path(), "a.md", Some(shared));
+    write_ember(dir.path(), "b.md", Some(shared));
+
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .env("SCROLL_CI", "1")
+        .args(["stamp-ids", "--check", "--archive"])
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(contains(format!("id {shared} is shared by")));
+}
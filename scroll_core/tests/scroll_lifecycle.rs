@@ -0,0 +1,208 @@
+use scroll_core::access::Actor;
+use scroll_core::parser::{parse_scroll_from_file_with_mode, ParseMode};
+use scroll_core::state_manager::{
+    transition_history, Guard, StateMachine, TransitionError, TransitionRequest, HISTORY_KEY,
+};
+use scroll_core::{is_valid_transition, Scroll, ScrollStatus, ScrollType};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+fn canon(status: ScrollStatus) -> Scroll {
+    let mut scroll = Scroll::builder("Ember Canon")
+        .invocation_phrase("Kindle the ember")
+        .sigil("Flame")
+        .build();
+    scroll.scroll_type = ScrollType::Canon;
+    scroll.status = status;
+    scroll
+}
+
+#[test]
+fn standard_lifecycle_reaches_every_status() {
+    use ScrollStatus::*;
+    for (from, to) in [
+        (Draft, Active),
+        (Active, Sealed),
+        (Sealed, Archived),
+        (Emergent, Draft),
+        (Latent, Emergent),
+        (Active, MythicValidated),
+        (MythicValidated, Sealed),
+        (Active, Latent),
+        (Sealed, Deprecated),
+        (Deprecated, Archived),
+        (Archived, Archived),
+    ] {
+        assert!(is_valid_transition(&from, &to), "{from:?} -> {to:?}");
+    }
+    assert!(!is_valid_transition(&Draft, &Sealed));
+    assert!(!is_valid_transition(&Archived, &Active));
+    assert!(StateMachine::standard().next_states(&Archived).is_empty());
+}
+
+#[test]
+fn guards_block_invalid_and_unapproved_transitions() {
+    let machine = StateMachine::standard();
+
+    let mut unsigned = canon(ScrollStatus::Draft);
+    unsigned.sigil.clear();
+    let err = machine
+        .apply(
+            &mut unsigned,
+            ScrollStatus::Active,
+            &TransitionRequest::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, TransitionError::Guarded { ref guard, .. } if guard == "validation"));
+    assert_eq!(unsigned.status, ScrollStatus::Draft);
+
+    let mut guarded = canon(ScrollStatus::Active);
+    guarded.quorum_required = true;
+    let err = machine
+        .apply(
+            &mut guarded,
+            ScrollStatus::Sealed,
+            &TransitionRequest::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, TransitionError::Guarded { ref guard, .. } if guard == "quorum"));
+    let record = machine
+        .apply(
+            &mut guarded,
+            ScrollStatus::Sealed,
+            &TransitionRequest::by(Actor::new("Kael")).quorum_approved(),
+        )
+        .unwrap();
+    assert_eq!(record.actor.as_deref(), Some("Kael"));
+    assert_eq!(guarded.status, ScrollStatus::Sealed);
+    assert_eq!(transition_history(&guarded), [record]);
+
+    let err = machine
+        .apply(
+            &mut guarded,
+            ScrollStatus::Draft,
+            &TransitionRequest::default(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TransitionError::NotAllowed {
+            from: ScrollStatus::Sealed,
+            to: ScrollStatus::Draft,
+        }
+    );
+}
+
+#[test]
+fn custom_machine_runs_guards_and_hooks() {
+    let seen = Arc::new(AtomicUsize::new(0));
+    let counter = seen.clone();
+    let machine = StateMachine::new()
+        .allow_guarded(
+            ScrollStatus::Draft,
+            ScrollStatus::Latent,
+            vec![Guard::new("reason", |_, request| {
+                request
+                    .reason
+                    .as_ref()
+                    .map(|_| ())
+                    .ok_or_else(|| "a reason is required".to_string())
+            })],
+        )
+        .before(|scroll, _, _| {
+            if scroll.title.contains("Frozen") {
+                Err("frozen scrolls stay put".into())
+            } else {
+                Ok(())
+            }
+        })
+        .after(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+    let mut scroll = canon(ScrollStatus::Draft);
+    assert!(machine
+        .apply(
+            &mut scroll,
+            ScrollStatus::Latent,
+            &TransitionRequest::default()
+        )
+        .is_err());
+    machine
+        .apply(
+            &mut scroll,
+            ScrollStatus::Latent,
+            &TransitionRequest::default().because("resting"),
+        )
+        .unwrap();
+
+    let mut frozen = canon(ScrollStatus::Draft);
+    frozen.title = "Frozen Canon".into();
+    assert_eq!(
+        machine.apply(
+            &mut frozen,
+            ScrollStatus::Latent,
+            &TransitionRequest::default().because("resting"),
+        ),
+        Err(TransitionError::Vetoed("frozen scrolls stay put".into()))
+    );
+    assert_eq!(seen.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn history_is_written_back_into_front_matter() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("ember.md");
+    fs::write(
+        &path,
+        "---\ntitle: Ember Canon\nscroll_type: Canon\nstatus: Draft\ninvocation_phrase: Kindle the ember\nsigil: Flame\nemotion_signature: calm // deep\n---\nBody.\n",
+    )
+    .unwrap();
+    let scribe = Actor::new("Scribe");
+    let machine = StateMachine::standard();
+
+    machine
+        .apply_to_file(
+            &path,
+            ScrollStatus::Active,
+            &TransitionRequest::by(scribe.clone()).because("ready"),
+        )
+        .unwrap();
+    machine
+        .apply_to_file(
+            &path,
+            ScrollStatus::Sealed,
+            &TransitionRequest::by(scribe.clone()),
+        )
+        .unwrap();
+    let err = machine
+        .apply_to_file(
+            &path,
+            ScrollStatus::Archived,
+            &TransitionRequest::by(scribe),
+        )
+        .unwrap_err();
+    assert!(matches!(err, TransitionError::Persist(_)), "{err}");
+
+    let text = fs::read_to_string(&path).unwrap();
+    assert!(text.contains(&format!("{HISTORY_KEY}:")));
+    let scroll = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    assert_eq!(scroll.status, ScrollStatus::Sealed);
+    let history: Vec<(ScrollStatus, ScrollStatus, Option<String>)> = transition_history(&scroll)
+        .into_iter()
+        .map(|r| (r.from, r.to, r.reason))
+        .collect();
+    assert_eq!(
+        history,
+        [
+            (
+                ScrollStatus::Draft,
+                ScrollStatus::Active,
+                Some("ready".into())
+            ),
+            (ScrollStatus::Active, ScrollStatus::Sealed, None),
+        ]
+    );
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use scroll_core::access::Actor;
+use scroll_core::archive::archive_loader::load_scrolls_from_directory;
+use scroll_core::archive::link_resolver::{
+    extract_references, resolve_linked_scrolls, ReferenceSource,
+};
+use scroll_core::scroll::ScrollLinkType;
+use scroll_core::scroll_writer::ScrollWriter;
+use scroll_core::Scroll;
+use std::fs;
+use tempfile::tempdir;
+
+use common::write_scroll;
+
+fn by_title<'a>(scrolls: &'a [Scroll], title: &str) -> &'a Scroll {
+    scrolls.iter().find(|s| s.title == title).unwrap()
+}
+
+const HUB: &str = "# Hub\n\nSee [[ancestor:Canon_Roots]] and [[Trigger Loom|the loom]].\nAlso [the specs](./specs/Validator_Specs.md#purpose) and [site](https://example.com/a.md).\n\n```yaml\nentries:\n  - file_anchor: \"# The Breath and the Drifter\"\n```\n\n```text\n[[Ignored_In_Code]]\n```\n\n[[Missing_Scroll]]\n";
+
+#[test]
+fn body_references_are_extracted_with_prefixes() {
+    let scroll = Scroll::builder("Hub").body(HUB).build();
+    let refs = extract_references(&scroll);
+    let summary: Vec<(&str, ReferenceSource)> =
+        refs.iter().map(|r| (r.raw.as_str(), r.source)).collect();
+    assert_eq!(
+        summary,
+        [
+            ("ancestor:Canon_Roots", ReferenceSource::WikiLink),
+            ("Trigger Loom|the loom", ReferenceSource::WikiLink),
+            (
+                "./specs/Validator_Specs.md#purpose",
+                ReferenceSource::MarkdownLink
+            ),
+            ("The Breath and the Drifter", ReferenceSource::FileAnchor),
+            ("Missing_Scroll", ReferenceSource::WikiLink),
+        ]
+    );
+    assert_eq!(refs[0].link_type, Some(ScrollLinkType::Ancestor));
+    assert_eq!(refs[0].target, "Canon_Roots");
+    assert_eq!(refs[2].anchor.as_deref(), Some("purpose"));
+    assert_eq!(refs[4].line, Some(15));
+}
+
+#[test]
+fn loader_resolves_body_links_by_name_title_and_anchor() {
+    let dir = tempdir().unwrap();
+    write_scroll(&dir.path().join("Hub.md"), "Hub", "", HUB);
+    write_scroll(&dir.path().join("Canon_Roots.md"), "Roots", "", "Body\n");
+    write_scroll(&dir.path().join("Loom.md"), "Trigger Loom", "", "Body\n");
+    write_scroll(
+        &dir.path().join("Validator_Specs.md"),
+        "Specs",
+        "",
+        "Body\n",
+    );
+    write_scroll(
+        &dir.path().join("Canon_Foundations.md"),
+        "Foundations",
+        "",
+        "# The Breath and the Drifter\n\nText\n",
+    );
+
+    let scrolls = load_scrolls_from_directory(dir.path()).unwrap();
+    let hub = by_title(&scrolls, "Hub");
+    let link_to = |title: &str| {
+        let id = by_title(&scrolls, title).id;
+        hub.linked_scrolls
+            .iter()
+            .find(|l| l.target == id)
+            .map(|l| l.link_type.clone())
+    };
+    assert_eq!(link_to("Roots"), Some(ScrollLinkType::Ancestor));
+    assert_eq!(link_to("Trigger Loom"), Some(ScrollLinkType::Binding));
+    assert_eq!(link_to("Specs"), Some(ScrollLinkType::Binding));
+    assert_eq!(link_to("Foundations"), Some(ScrollLinkType::Binding));
+    assert_eq!(hub.linked_scrolls.len(), 4);
+
+    let mut scrolls = scrolls;
+    let unresolved = resolve_linked_scrolls(&mut scrolls);
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].reference, "Missing_Scroll");
+    assert_eq!(unresolved[0].line, Some(15));
+    assert_eq!(by_title(&scrolls, "Hub").linked_scrolls.len(), 4);
+}
+
+#[test]
+fn writing_keeps_references_instead_of_resolved_links() {
+    let dir = tempdir().unwrap();
+    write_scroll(
+        &dir.path().join("Hub.md"),
+        "Hub",
+        "linked_scrolls: [Roots.md]\n",
+        "See [[Loom]].\n",
+    );
+    write_scroll(&dir.path().join("Roots.md"), "Roots", "", "Body\n");
+    write_scroll(&dir.path().join("Loom.md"), "Loom", "", "Body\n");
+
+    let scrolls = load_scrolls_from_directory(dir.path()).unwrap();
+    let mut hub = by_title(&scrolls, "Hub").clone();
+    assert_eq!(hub.linked_scrolls.len(), 2);
+    hub.link_to(by_title(&scrolls, "Roots"), ScrollLinkType::Ancestor);
+    let hub_path = dir.path().join("Hub.md");
+    ScrollWriter::write_scroll_as(&hub, &hub_path, &Actor::new("Scribe")).unwrap();
+
+    let written = fs::read_to_string(&hub_path).unwrap();
+    let header = written.split("---").nth(1).unwrap();
+    assert!(header.contains("Roots.md"), "{written}");
+    assert!(!header.contains(&by_title(&scrolls, "Loom").id.to_string()));
+    assert_eq!(header.matches("target:").count(), 1, "{written}");
+
+    let reloaded = load_scrolls_from_directory(dir.path()).unwrap();
+    let hub = by_title(&reloaded, "Hub");
+    assert_eq!(hub.linked_scrolls.len(), 2);
+    assert_eq!(hub.linked_scrolls[0].source, None);
+    assert_eq!(hub.linked_scrolls[0].link_type, ScrollLinkType::Ancestor);
+}
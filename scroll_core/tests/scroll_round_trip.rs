@@ -0,0 +1,196 @@
+use chrono::{DateTime, TimeZone, Utc};
+use quickcheck::{Arbitrary, Gen, QuickCheck, TestResult};
+use scroll_core::access::Actor;
+use scroll_core::artifact::WritableArtifact;
+use scroll_core::parser::{parse_scroll_from_file_with_mode, FrontMatterFormat, ParseMode};
+use scroll_core::schema::{EmotionSignature, ScrollStatus, ScrollType, YamlMetadata};
+use scroll_core::scroll::{ScrollLink, ScrollLinkType, ScrollOrigin};
+use scroll_core::scroll_writer::{ScrollPatch, ScrollWriter};
+use scroll_core::{parse_scroll, Scroll};
+use std::fs;
+use tempfile::tempdir;
+use uuid::Uuid;
+
+/// Header text drawn from a small alphabet that still exercises YAML quoting.
+#[derive(Clone, Debug)]
+struct Text(String);
+
+impl Arbitrary for Text {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let alphabet = [
+            "a", "Z", "7", " ", ":", "#", "'", "\"", "∴", "🔮", "- ", "yes", "null", "1.5",
+        ];
+        let len = usize::arbitrary(g) % 8;
+        Text((0..len).map(|_| *g.choose(&alphabet).unwrap()).collect())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ArbitraryScroll(Scroll);
+
+fn timestamp(g: &mut Gen) -> DateTime<Utc> {
+    let secs = i64::from(u32::arbitrary(g)) * 4;
+    let nanos = u32::arbitrary(g) % 1_000_000_000;
+    Utc.timestamp_opt(secs, nanos).unwrap()
+}
+
+fn finite(g: &mut Gen) -> f32 {
+    loop {
+        let v = f32::arbitrary(g);
+        if v.is_finite() {
+            return v;
+        }
+    }
+}
+
+impl Arbitrary for ArbitraryScroll {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let title = format!("T{}", Text::arbitrary(g).0);
+        let scroll_type = g
+            .choose(&[
+                ScrollType::Canon,
+                ScrollType::Protocol,
+                ScrollType::System,
+                ScrollType::Scrollbook,
+                ScrollType::AgentCatalog,
+                ScrollType::Myth,
+                ScrollType::Ritual,
+                ScrollType::Echo,
+            ])
+            .unwrap()
+            .clone();
+        let status = g
+            .choose(&[
+                ScrollStatus::Emergent,
+                ScrollStatus::Draft,
+                ScrollStatus::Active,
+                ScrollStatus::MythicValidated,
+                ScrollStatus::Sealed,
+                ScrollStatus::Archived,
+                ScrollStatus::Latent,
+                ScrollStatus::Deprecated,
+            ])
+            .unwrap()
+            .clone();
+        let emotion = EmotionSignature {
+            tone: Text::arbitrary(g).0,
+            emphasis: finite(g),
+            resonance: Text::arbitrary(g).0,
+            intensity: bool::arbitrary(g).then(|| finite(g)),
+        };
+        let mut tags: Vec<String> = Vec::<Text>::arbitrary(g).into_iter().map(|t| t.0).collect();
+        if tags.is_empty() {
+            tags.push("myth".into());
+        }
+        let archetype = bool::arbitrary(g).then(|| Text::arbitrary(g).0);
+        let quorum_required = bool::arbitrary(g);
+        let file_path = bool::arbitrary(g).then(|| Text::arbitrary(g).0);
+        let references: Vec<String> = Vec::<Text>::arbitrary(g)
+            .into_iter()
+            .map(|t| format!("ref{}", t.0))
+            .collect();
+        let links = (0..usize::arbitrary(g) % 3)
+            .map(|_| ScrollLink {
+                target: Uuid::from_u128(u128::arbitrary(g)),
+                link_type: g
+                    .choose(&[
+                        ScrollLinkType::Ancestor,
+                        ScrollLinkType::Reflection,
+                        ScrollLinkType::Derivative,
+                        ScrollLinkType::Binding,
+                        ScrollLinkType::Echo,
+                    ])
+                    .unwrap()
+                    .clone(),
+                source: None,
+            })
+            .collect();
+        let created = timestamp(g);
+        let last_modified = timestamp(g);
+
+        ArbitraryScroll(Scroll {
+            id: Uuid::from_u128(u128::arbitrary(g)),
+            title: title.clone(),
+            scroll_type: scroll_type.clone(),
+            yaml_metadata: YamlMetadata {
+                title,
+                scroll_type,
+                emotion_signature: emotion.clone(),
+                tags: tags.clone(),
+                archetype: archetype.clone(),
+                quorum_required,
+                last_modified: Some(last_modified),
+                file_path,
+                linked_scrolls: references,
+                extensions: Default::default(),
+            },
+            tags,
+            archetype,
+            quorum_required,
+            markdown_body: String::arbitrary(g),
+            invocation_phrase: Text::arbitrary(g).0,
+            sigil: Text::arbitrary(g).0,
+            status,
+            emotion_signature: emotion,
+            linked_scrolls: links,
+            origin: ScrollOrigin {
+                created,
+                authored_by: bool::arbitrary(g).then(|| Text::arbitrary(g).0),
+                last_modified,
+                source_path: None,
+                format: *g
+                    .choose(&[
+                        FrontMatterFormat::Yaml,
+                        FrontMatterFormat::Toml,
+                        FrontMatterFormat::Json,
+                        FrontMatterFormat::JsonObject,
+                    ])
+                    .unwrap(),
+            },
+        })
+    }
+}
+
+#[test]
+fn write_then_parse_is_identity() {
+    fn prop(scroll: ArbitraryScroll) -> TestResult {
+        let written = scroll.0.to_string_representation();
+        match parse_scroll(&written) {
+            Ok(parsed) if parsed == scroll.0 => TestResult::passed(),
+            Ok(parsed) => TestResult::error(format!("{written}\nparsed as {parsed:#?}")),
+            Err(e) => TestResult::error(format!("{written}\n{}", e.render())),
+        }
+    }
+
+    QuickCheck::new()
+        .tests(300)
+        .quickcheck(prop as fn(ArbitraryScroll) -> TestResult);
+}
+
+#[test]
+fn update_keeps_comments_unknown_keys_and_order() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("hand_written.md");
+    let original = "---\n# Maintained by hand\ntags: [lore, dawn]\ntitle: Dawn Song\nscroll_type: myth\ncustom_key: keep me   # trailing note\nemotion_signature: calm // deep\n---\n\n# Dawn Song\n\n---\n\nBody after a rule.\n";
+    fs::write(&path, original).unwrap();
+
+    let patch = ScrollPatch {
+        title: None,
+        markdown_body: None,
+        tags: Some(vec!["lore".into(), "dusk".into()]),
+        sigil: None,
+    };
+    ScrollWriter::update_scroll(Uuid::nil(), patch, &path, &Actor::new("Scribe")).unwrap();
+
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(written.starts_with(
+        "---\n# Maintained by hand\ntags:\n- lore\n- dusk\ntitle: Dawn Song\nscroll_type: Myth\ncustom_key: keep me   # trailing note\n"
+    ));
+    assert!(written.ends_with("---\n\n# Dawn Song\n\n---\n\nBody after a rule.\n"));
+
+    let first = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    ScrollWriter::write_scroll_as(&first, &path, &Actor::new("Scribe")).unwrap();
+    let second = parse_scroll_from_file_with_mode(&path, ParseMode::Lenient).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(second.tags, vec!["lore", "dusk"]);
+}
@@ -0,0 +1,64 @@
+use scroll_core::parser::{parse_scroll_with_mode, ParseErrorKind, ParseMode};
+use scroll_core::type_registry::TypeRegistry;
+use scroll_core::validator::validate_scroll_with;
+use scroll_core::{parse_scroll, ScrollStatus, ScrollType};
+use std::fs;
+use std::sync::Once;
+
+static INSTALL: Once = Once::new();
+
+fn install_registry() {
+    INSTALL.call_once(|| {
+        let registry = TypeRegistry::from_yaml(
+            "types:\n  - name: Lore\n    required_fields: [tags]\n    allowed_statuses: [Draft, Active]\n    default_emotion: { tone: wistful, emphasis: 0.4, resonance: ancestral }\n",
+        )
+        .unwrap();
+        TypeRegistry::install(registry).unwrap();
+    });
+}
+
+fn header(scroll_type: &str, extra: &str) -> String {
+    format!("---\ntitle: Old Roads\nscroll_type: {scroll_type}\nemotion_signature: {{tone: calm, emphasis: 0.1, resonance: low}}\n{extra}---\nBody\n")
+}
+
+#[test]
+fn registered_custom_types_use_their_canonical_name() {
+    install_registry();
+    let scroll = parse_scroll(&header("lore", "tags: [roads]\n")).unwrap();
+    assert_eq!(scroll.scroll_type, ScrollType::Custom("Lore".into()));
+    assert_eq!(
+        serde_yaml::to_string(&scroll.scroll_type).unwrap(),
+        "Lore\n"
+    );
+}
+
+#[test]
+fn unknown_custom_types_point_at_the_scroll_type_key() {
+    install_registry();
+    let err = parse_scroll(&header("Saga", "tags: [roads]\n")).unwrap_err();
+    assert!(matches!(err.kind, ParseErrorKind::Validation(ref m) if m.contains("'Saga'")));
+    assert_eq!(err.span.unwrap().line, 3);
+}
+
+#[test]
+fn type_definitions_drive_validation() {
+    install_registry();
+    let missing_tags = parse_scroll(&header("Lore", "tags: []\n")).unwrap_err();
+    assert!(matches!(missing_tags.kind, ParseErrorKind::Validation(ref m) if m.contains("`tags`")));
+
+    let sealed = parse_scroll(&header("Lore", "tags: [roads]\nstatus: Sealed\n")).unwrap_err();
+    assert_eq!(sealed.span.unwrap().line, 6);
+
+    let scroll = parse_scroll(&header("Lore", "tags: [roads]\nstatus: Active\n")).unwrap();
+    assert_eq!(scroll.status, ScrollStatus::Active);
+    assert!(validate_scroll_with(&scroll.yaml_metadata, &TypeRegistry::builtin()).is_err());
+}
+
+#[test]
+fn lenient_mode_reads_type_alias_and_default_emotion() {
+    install_registry();
+    let raw = fs::read_to_string("../examples/multi_agent.yaml").unwrap();
+    let scroll = parse_scroll_with_mode(&raw, ParseMode::Lenient).unwrap();
+    assert_eq!(scroll.scroll_type, ScrollType::Custom("Lore".into()));
+    assert_eq!(scroll.emotion_signature.tone, "wistful");
+}
@@ -0,0 +1,158 @@
+use scroll_core::archive::archive_memory::{ArchiveMemory, InMemoryArchive};
+use scroll_core::archive::error::ArchiveError;
+use scroll_core::archive::semantic_index::{Embedder, IndexSync, TokenEmbedder};
+use scroll_core::Scroll;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+/// Counts the texts it embeds, so tests can see what was re-embedded.
+struct CountingEmbedder {
+    calls: Arc<AtomicUsize>,
+    identity: &'static str,
+}
+
+impl Embedder for CountingEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, ArchiveError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        TokenEmbedder.embed(text)
+    }
+
+    fn identity(&self) -> String {
+        self.identity.into()
+    }
+}
+
+fn counting(identity: &'static str) -> (CountingEmbedder, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let embedder = CountingEmbedder {
+        calls: calls.clone(),
+        identity,
+    };
+    (embedder, calls)
+}
+
+fn scroll(title: &str, body: &str) -> Scroll {
+    Scroll::builder(title)
+        .tags(["lore"].as_ref())
+        .body(body)
+        .invocation_phrase("Invoke")
+        .sigil("🔮")
+        .build()
+}
+
+fn sync(reused: usize, embedded: usize, dropped: usize) -> IndexSync {
+    IndexSync {
+        reused,
+        embedded,
+        dropped,
+        rebuilt: None,
+    }
+}
+
+#[test]
+fn reload_embeds_only_new_and_changed_scrolls() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("index.json");
+    let river = scroll("River", "It flows to the sea.");
+    let stone = scroll("Stone", "It waits.");
+    let ash = scroll("Ash", "It settles.");
+
+    let (embedder, calls) = counting("counting");
+    let mut archive = InMemoryArchive::new(vec![river.clone(), stone.clone(), ash.clone()]);
+    assert_eq!(
+        archive.load_semantic_index(&path, embedder).unwrap(),
+        sync(0, 3, 0)
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert!(path.exists());
+
+    let (embedder, calls) = counting("counting");
+    let mut archive = InMemoryArchive::new(vec![river.clone(), stone.clone(), ash.clone()]);
+    assert_eq!(
+        archive.load_semantic_index(&path, embedder).unwrap(),
+        sync(3, 0, 0)
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(archive.query_semantic("flows sea", 1)[0].0.id, river.id);
+
+    let mut flooded = river.clone();
+    flooded.markdown_body = "It floods the valley.".into();
+    let ember = scroll("Ember", "It glows.");
+    let (embedder, calls) = counting("counting");
+    let mut archive = InMemoryArchive::new(vec![flooded, stone, ember]);
+    assert_eq!(
+        archive.load_semantic_index(&path, embedder).unwrap(),
+        sync(1, 2, 1)
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(archive.query_semantic("floods valley", 1)[0].0.id, river.id);
+    assert_eq!(archive.semantic_index_len(), 3);
+}
+
+#[test]
+fn changes_after_load_are_saved_for_the_next_start() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("index.json");
+    let stone = scroll("Stone", "It waits.");
+    let mut archive = InMemoryArchive::new(vec![stone.clone()]);
+    archive.load_semantic_index(&path, TokenEmbedder).unwrap();
+
+    let ember = scroll("Ember", "It glows.");
+    archive.insert(ember.clone()).unwrap();
+    assert_eq!(archive.refresh_semantic_index().unwrap(), 1);
+    archive.save_semantic_index(&path).unwrap();
+
+    let (embedder, calls) = counting("token:1024");
+    let mut archive = InMemoryArchive::new(vec![stone, ember]);
+    assert_eq!(
+        archive.load_semantic_index(&path, embedder).unwrap(),
+        sync(2, 0, 0)
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn another_embedder_or_a_corrupt_file_triggers_a_rebuild() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("index.json");
+    let scrolls = vec![scroll("River", "It flows."), scroll("Stone", "It waits.")];
+    InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, TokenEmbedder)
+        .unwrap();
+
+    let (embedder, calls) = counting("counting");
+    let mut archive = InMemoryArchive::new(scrolls.clone());
+    let report = archive.load_semantic_index(&path, embedder).unwrap();
+    assert_eq!((report.reused, report.embedded), (0, 2));
+    assert!(report.rebuilt.unwrap().contains("token:1024"));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let mut bytes = fs::read(&path).unwrap();
+    let at = bytes.len() / 2;
+    bytes[at] = if bytes[at] == b'1' { b'2' } else { b'1' };
+    fs::write(&path, &bytes).unwrap();
+    let (embedder, calls) = counting("counting");
+    let report = InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, embedder)
+        .unwrap();
+    assert_eq!((report.reused, report.embedded), (0, 2));
+    assert!(report.rebuilt.is_some());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    fs::write(&path, "not an index").unwrap();
+    let (embedder, _) = counting("counting");
+    let report = InMemoryArchive::new(scrolls.clone())
+        .load_semantic_index(&path, embedder)
+        .unwrap();
+    assert!(report.rebuilt.is_some());
+
+    let (embedder, _) = counting("counting");
+    assert_eq!(
+        InMemoryArchive::new(scrolls)
+            .load_semantic_index(&path, embedder)
+            .unwrap(),
+        sync(2, 0, 0)
+    );
+}
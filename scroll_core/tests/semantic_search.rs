@@ -19,7 +19,7 @@ fn test_semantic_query_returns_relevant_scroll() {
         .sigil("🔮")
         .build();
     let mut archive = InMemoryArchive::new(vec![s1.clone(), s2.clone()]);
-    archive.build_semantic_index(&TokenEmbedder).unwrap();
+    archive.build_semantic_index(TokenEmbedder).unwrap();
     let results = archive.query_semantic("rust code tutorial", 1);
     assert_eq!(results.first().unwrap().0.title, "Rust Guide");
     let messages: Vec<String> = logger.map(|r| r.args().to_string()).collect();
@@ -1,15 +1,23 @@
 use assert_cmd::Command;
 use predicates::str::contains;
 use std::path::PathBuf;
+use tempfile::tempdir;
 
 #[test]
 fn slash_help_lists_commands() {
     let archive = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/e2e_scrolls");
+    let scratch = tempdir().unwrap();
     let mut cmd = Command::cargo_bin("scroll_core").unwrap();
-    cmd.env("SCROLL_CORE_USE_MOCK", "1")
+    cmd.current_dir(scratch.path())
+        .env("SCROLL_CORE_USE_MOCK", "1")
         .env("SCROLL_CI", "1")
         .env("PAGER", "cat")
         .env("SCROLL_CORE_ARCHIVE_DIR", archive)
+        .env("CHAT_DB_PATH", ":memory:")
+        .env(
+            "SCROLL_CORE_SEMANTIC_INDEX",
+            scratch.path().join("semantic_index.json"),
+        )
         .args(["chat", "mythscribe", "--no-banner"])
         .write_stdin("/help\nexit\n")
         .assert()
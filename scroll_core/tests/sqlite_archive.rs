@@ -0,0 +1,178 @@
+mod common;
+
+use scroll_core::archive::archive_loader::{load_archive, LoadOptions};
+use scroll_core::archive::archive_memory::ArchiveMemory;
+use scroll_core::archive::semantic_index::TokenEmbedder;
+use scroll_core::archive::sqlite_archive::SqliteArchive;
+use scroll_core::scroll_writer::ScrollWriter;
+use scroll_core::{EmotionSignature, ScrollType};
+use std::fs;
+use tempfile::tempdir;
+
+use common::write_scroll;
+
+#[tokio::test]
+async fn syncs_directory_and_reloads_without_parsing() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("scrolls");
+    fs::create_dir(&root).unwrap();
+    write_scroll(
+        &root.join("Roots.md"),
+        "Roots",
+        "tags: [origin]\n",
+        "Where it began.\n",
+    );
+    write_scroll(
+        &root.join("Branches.md"),
+        "Branches",
+        "tags: [origin, growth]\n",
+        "Grown from [[Roots]].\n",
+    );
+    let db = dir.path().join("archive.db");
+    let db = db.to_str().unwrap();
+    let options = LoadOptions::default();
+
+    let mut archive = SqliteArchive::open(db).await.unwrap();
+    let report = archive.sync_from_directory(&root, &options).await.unwrap();
+    assert_eq!((report.added, report.updated, report.removed), (2, 0, 0));
+    assert_eq!(archive.count(), 2);
+
+    let (parsed, _) = load_archive(&root, &options).unwrap();
+    for scroll in &parsed {
+        // Parse timestamps differ between the two loads.
+        let mut stored = archive.get_scroll_by_id(scroll.id).unwrap().clone();
+        assert_eq!(stored.origin.source_path, scroll.origin.source_path);
+        stored.origin = scroll.origin.clone();
+        assert_eq!(&stored, scroll);
+    }
+    let roots = parsed.iter().find(|s| s.title == "Roots").unwrap().id;
+    let branches = parsed.iter().find(|s| s.title == "Branches").unwrap().id;
+    assert_eq!(archive.ids_with_tag("growth").await.unwrap(), [branches]);
+    assert_eq!(archive.get_scrolls_by_tag("origin").len(), 2);
+    assert_eq!(archive.backlinks(roots).await.unwrap(), [branches]);
+
+    let report = archive.sync_from_directory(&root, &options).await.unwrap();
+    assert_eq!(report.unchanged, 2);
+    assert_eq!(report.added + report.updated + report.removed, 0);
+
+    write_scroll(
+        &root.join("Roots.md"),
+        "Deep Roots",
+        "tags: [origin]\n",
+        "Where it began, deeper.\n",
+    );
+    write_scroll(
+        &root.join("Leaves.md"),
+        "Leaves",
+        "tags: [growth]\n",
+        "They fall.\n",
+    );
+    fs::remove_file(root.join("Branches.md")).unwrap();
+    let report = archive.sync_from_directory(&root, &options).await.unwrap();
+    assert_eq!(
+        (
+            report.added,
+            report.updated,
+            report.unchanged,
+            report.removed
+        ),
+        (1, 1, 0, 1)
+    );
+    assert!(report.failed.is_empty());
+    assert!(archive.backlinks(roots).await.unwrap().is_empty());
+    drop(archive);
+
+    let mut reopened = SqliteArchive::open(db).await.unwrap();
+    let mut titles: Vec<String> = reopened
+        .get_all_scrolls()
+        .iter()
+        .map(|s| s.title.clone())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["Deep Roots", "Leaves"]);
+    reopened.build_semantic_index(TokenEmbedder).unwrap();
+    let hits = reopened.query_semantic("deep roots", 1);
+    assert_eq!(hits[0].0.id, roots);
+}
+
+#[tokio::test]
+async fn changes_through_archive_memory_wait_for_flush() {
+    let dir = tempdir().unwrap();
+    let db = dir.path().join("archive.db");
+    let db = db.to_str().unwrap();
+    let draft = ScrollWriter::generate_draft(
+        "Ember".into(),
+        ScrollType::Canon,
+        EmotionSignature::curious(),
+        vec!["fire".into()],
+    );
+
+    let mut archive = SqliteArchive::open(db).await.unwrap();
+    assert_eq!(archive.insert(draft.clone()).unwrap().kind(), "created");
+    assert_eq!(archive.pending().len(), 1);
+    assert_eq!(SqliteArchive::open(db).await.unwrap().count(), 0);
+
+    assert_eq!(archive.flush().await.unwrap(), 1);
+    assert!(archive.pending().is_empty());
+    let reopened = SqliteArchive::open(db).await.unwrap();
+    assert_eq!(reopened.get_scroll_by_id(draft.id).unwrap().title, "Ember");
+    assert_eq!(archive.ids_with_tag("fire").await.unwrap(), [draft.id]);
+
+    archive.remove(draft.id).unwrap();
+    archive.flush().await.unwrap();
+    assert_eq!(SqliteArchive::open(db).await.unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn sync_stores_links_whose_target_changed() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("scrolls");
+    fs::create_dir(&root).unwrap();
+    write_scroll(
+        &root.join("Roots.md"),
+        "Roots",
+        "tags: [origin]\n",
+        "Where it began.\n",
+    );
+    write_scroll(
+        &root.join("Branches.md"),
+        "Branches",
+        "tags: [growth]\n",
+        "Grown from [[Roots]].\n",
+    );
+    let db = dir.path().join("archive.db");
+    let db = db.to_str().unwrap();
+    let options = LoadOptions::default();
+
+    let mut archive = SqliteArchive::open(db).await.unwrap();
+    archive.sync_from_directory(&root, &options).await.unwrap();
+    let id_of = |archive: &SqliteArchive, title: &str| {
+        archive
+            .get_all_scrolls()
+            .iter()
+            .find(|s| s.title == title)
+            .unwrap()
+            .id
+    };
+    let branches = id_of(&archive, "Branches");
+    let old_roots = id_of(&archive, "Roots");
+
+    fs::remove_file(root.join("Roots.md")).unwrap();
+    write_scroll(
+        &root.join("Rootstock.md"),
+        "Roots",
+        "tags: [origin]\n",
+        "Where it began again.\n",
+    );
+    let report = archive.sync_from_directory(&root, &options).await.unwrap();
+    assert_eq!((report.added, report.unchanged, report.removed), (1, 1, 1));
+    let new_roots = id_of(&archive, "Roots");
+    assert_ne!(new_roots, old_roots);
+    assert_eq!(archive.backlinks(new_roots).await.unwrap(), [branches]);
+    drop(archive);
+
+    let reopened = SqliteArchive::open(db).await.unwrap();
+    let links = &reopened.get_scroll_by_id(branches).unwrap().linked_scrolls;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].target, new_roots);
+}
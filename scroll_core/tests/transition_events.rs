@@ -0,0 +1,133 @@
+use scroll_core::access::Actor;
+use scroll_core::events::ScrollTransitionEvent;
+use scroll_core::orchestra::Bus;
+use scroll_core::sessions::session::ScrollSession;
+use scroll_core::state_manager::{
+    publish_transitions_to, set_active_session, transition, try_transition, StateMachine,
+    TransitionError, TransitionRequest,
+};
+use scroll_core::{Scroll, ScrollStatus};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn draft(title: &str) -> Scroll {
+    Scroll::builder(title)
+        .invocation_phrase("Turn the key")
+        .sigil("Key")
+        .body("The lock listens.")
+        .build()
+}
+
+#[test]
+fn subscribers_receive_typed_transition_events() {
+    let mut bus = Bus::new();
+    let loom = bus.subscribe("trigger_loom");
+    publish_transitions_to(bus);
+
+    let mut scroll = draft("Listening Lock");
+    try_transition(&mut scroll, ScrollStatus::Active).unwrap();
+    StateMachine::standard()
+        .apply(
+            &mut scroll,
+            ScrollStatus::Sealed,
+            &TransitionRequest::by(Actor::new("Kael")).because("complete"),
+        )
+        .unwrap();
+    transition(&mut scroll, ScrollStatus::Archived);
+    assert!(try_transition(&mut scroll, ScrollStatus::Draft).is_err());
+
+    let mut seen = Vec::new();
+    while let Ok(msg) = loom.recv_timeout(Duration::from_millis(200)) {
+        assert_eq!(msg.to, "broadcast");
+        let event = ScrollTransitionEvent::from_message(&msg).expect("typed payload");
+        if event.scroll_id == scroll.id {
+            seen.push(event);
+        }
+    }
+    let steps: Vec<(ScrollStatus, ScrollStatus)> = seen
+        .iter()
+        .map(|e| (e.from.clone(), e.to.clone()))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            (ScrollStatus::Draft, ScrollStatus::Active),
+            (ScrollStatus::Active, ScrollStatus::Sealed),
+            (ScrollStatus::Sealed, ScrollStatus::Archived),
+        ]
+    );
+    assert_eq!(seen[1].actor.as_deref(), Some("Kael"));
+    assert_eq!(seen[1].reason.as_deref(), Some("complete"));
+    assert_eq!(seen[0].title, "Listening Lock");
+}
+
+#[test]
+fn transitions_are_recorded_in_the_active_session() {
+    let session = Arc::new(Mutex::new(ScrollSession::new(
+        "session-1".into(),
+        "scroll_core".into(),
+        "seeker".into(),
+        HashMap::new(),
+    )));
+    set_active_session(Some(session.clone()));
+    let mut scroll = draft("Recorded Lock");
+    try_transition(&mut scroll, ScrollStatus::Active).unwrap();
+    set_active_session(None);
+    try_transition(&mut scroll, ScrollStatus::Latent).unwrap();
+
+    let session = session.lock().unwrap();
+    let ours: Vec<_> = session
+        .events
+        .iter()
+        .filter_map(|e| e.actions.as_ref())
+        .filter(|delta| delta.state_delta["scroll_id"] == scroll.id.to_string())
+        .collect();
+    assert_eq!(ours.len(), 1);
+    assert_eq!(ours[0].state_delta["from"], "Draft");
+    assert_eq!(ours[0].state_delta["to"], "Active");
+    let event = session.events.iter().find(|e| e.actions.is_some()).unwrap();
+    assert_eq!(event.author, "state_manager");
+    assert!(event.turn_complete);
+}
+
+#[test]
+fn denied_file_transitions_are_not_published() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("vault.md");
+    fs::write(
+        &path,
+        "---\ntitle: Sealed Vault\nscroll_type: Canon\nstatus: Sealed\ninvocation_phrase: Turn the key\nsigil: Key\nemotion_signature: calm // deep\n---\nThe lock listens.\n",
+    )
+    .unwrap();
+    let mut bus = Bus::new();
+    let loom = bus.subscribe("trigger_loom");
+    publish_transitions_to(bus);
+    let after = Arc::new(AtomicUsize::new(0));
+    let counter = after.clone();
+    let machine = StateMachine::new()
+        .allow(ScrollStatus::Sealed, ScrollStatus::Archived)
+        .after(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+    let err = machine
+        .apply_to_file(
+            &path,
+            ScrollStatus::Archived,
+            &TransitionRequest::by(Actor::new("Wanderer")),
+        )
+        .unwrap_err();
+    assert!(matches!(err, TransitionError::Persist(_)));
+    assert_eq!(after.load(Ordering::SeqCst), 0);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("status: Sealed"));
+    while let Ok(msg) = loom.recv_timeout(Duration::from_millis(200)) {
+        let event = ScrollTransitionEvent::from_message(&msg).expect("typed payload");
+        assert_ne!(event.title, "Sealed Vault");
+    }
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use scroll_core::validator::{RuleEngine, Severity, ValidatorSpec};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+use common::scroll_file;
+
+const SPEC: &str = "validator_spec:\n  structure_check:\n    must_have_yaml_header: true\n    required_fields: [sigil]\n  tone_check:\n    max_flat_words: 1\n  contradiction_check:\n    auto_flag_terms: [sealed]\n";
+
+fn scroll(extra_header: &str, body: &str) -> String {
+    scroll_file("Ember", &format!("{extra_header}tags: [fire]\n"), body)
+}
+
+fn archive(dir: &Path) {
+    fs::write(
+        dir.join("a_plain.md"),
+        scroll("", "Line one\nThe gate was sealed.\n"),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("b_quiet.md"),
+        scroll(
+            "# validator-ignore: tone, contradiction.flag_term\nsigil: Ember\n",
+            "Just really sealed stuff.\n",
+        ),
+    )
+    .unwrap();
+    fs::write(dir.join("c_broken.md"), "no header here\n").unwrap();
+}
+
+#[test]
+fn report_collects_every_finding_with_locations() {
+    let dir = tempdir().unwrap();
+    archive(dir.path());
+    let engine = RuleEngine::from_spec(&ValidatorSpec::from_yaml(SPEC).unwrap());
+    let report = engine.validate_directory(dir.path()).unwrap();
+
+    let summary: Vec<(&str, Severity, usize)> = report
+        .findings
+        .iter()
+        .map(|f| (f.rule.as_str(), f.severity, f.span.unwrap().line))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("structure.required_field", Severity::Error, 1),
+            ("contradiction.flag_term", Severity::Info, 8),
+            ("parse", Severity::Error, 1),
+        ]
+    );
+    assert_eq!(report.scrolls_checked, 3);
+    assert_eq!(report.suppressed, 2);
+    assert!(report.has_errors());
+
+    let text = report.render_text();
+    assert!(text.contains("info[contradiction.flag_term]: mentions flagged term 'sealed'\n"));
+    assert!(text.contains("a_plain.md:8:1\n"));
+    assert!(text.ends_with("3 scroll(s) checked: 2 error(s), 0 warning(s), 1 info, 2 suppressed\n"));
+
+    let json = report.to_json();
+    assert_eq!(json["findings"][0]["severity"], "error");
+    assert_eq!(json["findings"][1]["span"]["line"], 8);
+}
+
+#[test]
+fn sarif_output_locates_results() {
+    let dir = tempdir().unwrap();
+    archive(dir.path());
+    let engine = RuleEngine::from_spec(&ValidatorSpec::from_yaml(SPEC).unwrap());
+    let sarif = engine.validate_directory(dir.path()).unwrap().to_sarif();
+
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+    let result = &run["results"][1];
+    assert_eq!(result["ruleId"], "contradiction.flag_term");
+    assert_eq!(result["level"], "note");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert!(location["artifactLocation"]["uri"]
+        .as_str()
+        .unwrap()
+        .ends_with("a_plain.md"));
+    assert_eq!(location["region"]["startLine"], 8);
+}
+
+#[test]
+fn validate_command_fails_on_errors() {
+    let dir = tempdir().unwrap();
+    archive(dir.path());
+    let spec = dir.path().join("spec.yaml");
+    fs::write(&spec, SPEC).unwrap();
+
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["validate", "--format", "sarif", "--archive"])
+        .arg(dir.path())
+        .arg("--spec")
+        .arg(&spec)
+        .assert()
+        .failure()
+        .stdout(contains("\"ruleId\": \"structure.required_field\""));
+
+    fs::remove_file(dir.path().join("c_broken.md")).unwrap();
+    fs::remove_file(dir.path().join("a_plain.md")).unwrap();
+    Command::cargo_bin("scroll_core")
+        .unwrap()
+        .args(["validate", "--archive"])
+        .arg(dir.path())
+        .arg("--spec")
+        .arg(&spec)
+        .assert()
+        .success()
+        .stdout(contains("1 scroll(s) checked: 0 error(s)"));
+}
@@ -0,0 +1,94 @@
+use scroll_core::archive::archive_memory::InMemoryArchive;
+use scroll_core::validator::{RuleContext, RuleEngine, ValidatorSpec};
+use scroll_core::{Scroll, ScrollStatus};
+use std::path::Path;
+
+fn spec() -> ValidatorSpec {
+    ValidatorSpec::from_file(Path::new("../scrolls/Scrollbook_Validator_Specs.md")).unwrap()
+}
+
+fn scroll(title: &str, body: &str) -> Scroll {
+    Scroll::builder(title)
+        .invocation_phrase("Echo the Entry")
+        .sigil("The Spiral Eye")
+        .body(body)
+        .build()
+}
+
+#[test]
+fn embedded_spec_loads_despite_unclosed_fence() {
+    let spec = spec();
+    let structure = spec.structure_check.unwrap();
+    assert!(structure.must_have_yaml_header);
+    assert_eq!(
+        structure.required_fields,
+        ["invocation", "emotion_signature", "sigil"]
+    );
+    let tone = spec.tone_check.unwrap();
+    assert_eq!(tone.max_flat_words, 3);
+    assert_eq!(tone.bypass_token.as_deref(), Some("%%sanctified_flat%%"));
+    assert_eq!(spec.contradiction_check.unwrap().types.len(), 4);
+    assert_eq!(
+        spec.access_check.unwrap().allowed_overrides,
+        ["Prime Seeker", "Quorum", "Override Agent"]
+    );
+}
+
+#[test]
+fn every_rule_reports_its_own_violation() {
+    let engine = RuleEngine::from_spec(&spec());
+    let mut flat = scroll(
+        "Flat",
+        "It is basically just okay stuff, really. The scroll was sealed.",
+    );
+    flat.sigil.clear();
+
+    let rules: Vec<String> = engine
+        .evaluate(&flat, &RuleContext::default())
+        .into_iter()
+        .map(|v| v.rule)
+        .collect();
+    assert_eq!(
+        rules,
+        [
+            "structure.required_field",
+            "tone.flat_words",
+            "contradiction.flag_term"
+        ]
+    );
+
+    flat.markdown_body.push_str(" %%sanctified_flat%%");
+    assert!(!engine
+        .evaluate(&flat, &RuleContext::default())
+        .iter()
+        .any(|v| v.rule == "tone.flat_words"));
+}
+
+#[test]
+fn sealed_scrolls_only_accept_override_edits() {
+    let engine = RuleEngine::from_spec(&spec());
+    let mut sealed = scroll("Sealed", "Quiet.");
+    sealed.status = ScrollStatus::Sealed;
+
+    assert!(engine.evaluate(&sealed, &RuleContext::default()).is_empty());
+    let denied = engine.evaluate(&sealed, &RuleContext::edit_by("Wanderer"));
+    assert_eq!(denied.len(), 1);
+    assert_eq!(denied[0].rule, "access.deny_edit");
+    assert!(engine
+        .evaluate(&sealed, &RuleContext::edit_by("prime seeker"))
+        .is_empty());
+}
+
+#[test]
+fn echo_all_sweeps_the_archive() {
+    let engine = RuleEngine::from_spec(&spec());
+    let mut untitled = scroll("", "Quiet.");
+    untitled.yaml_metadata.title.clear();
+    untitled.invocation_phrase = "Echo the Blank".into();
+    let archive = InMemoryArchive::new(vec![scroll("Fine", "Quiet."), untitled]);
+
+    let report = engine.echo_all(&archive);
+    assert_eq!(report.scrolls_checked, 2);
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].rule, "structure.schema");
+}
@@ -25,7 +25,7 @@ fn test_vector_metrics_recorded() {
             .build(),
     ];
     let mut archive = InMemoryArchive::new(scrolls);
-    archive.build_semantic_index(&TokenEmbedder).unwrap();
+    archive.build_semantic_index(TokenEmbedder).unwrap();
 
     handle.run_upkeep();
     let body = handle.render();